@@ -0,0 +1,263 @@
+//! Whether a pull request's approvals satisfy CODEOWNERS
+//!
+//! A file is satisfied once any of its owners has approved: a user owner by
+//! approving themselves, a team owner through any member's approval. Team
+//! membership is looked up through [`GitHubClient`], and only for files no
+//! user owner has already approved.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::github::GitHubClient;
+use crate::reviewers::{fetch_team_members, minimum_cover, ReviewPlan, UnexpandedTeam};
+
+/// Approval state of one changed file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileApproval {
+    pub file: String,
+    pub owners: Vec<String>,
+    /// Approvers that satisfy this file, as `@login` or `@login (@org/team)`
+    pub approved_by: Vec<String>,
+}
+
+/// Which changed files still need a code owner's approval
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalReport {
+    /// Logins that approved, as `@login`
+    pub approvers: Vec<String>,
+    pub approved: Vec<FileApproval>,
+    pub missing: Vec<FileApproval>,
+    /// Changed files without owners (no approval required)
+    pub unowned: Vec<String>,
+    /// Fewest additional owners whose approval would cover every missing file
+    pub still_needed: Vec<String>,
+    /// Teams whose members couldn't be listed; their approvals are unknown
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unexpanded: Vec<UnexpandedTeam>,
+}
+
+impl ApprovalReport {
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Read approving logins from JSON: either a list of logins (`["alice",
+/// "@bob"]`) or the GitHub reviews API response, where a reviewer counts
+/// when their latest approving or blocking review is an approval.
+pub fn parse_approvals(json: &str) -> Result<Vec<String>, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let entries = value
+        .as_array()
+        .ok_or("Expected a JSON array of logins or reviews")?;
+
+    // Reviews come oldest first; comments don't change a reviewer's state
+    let mut latest: HashMap<String, bool> = HashMap::new();
+    let mut order = Vec::new();
+    for entry in entries {
+        let (login, approved) = match entry {
+            Value::String(login) => (login.as_str(), true),
+            Value::Object(review) => {
+                let login = review
+                    .get("user")
+                    .and_then(|user| user.get("login"))
+                    .and_then(Value::as_str)
+                    .ok_or("Review without user.login")?;
+                match review.get("state").and_then(Value::as_str) {
+                    Some("APPROVED") => (login, true),
+                    Some("CHANGES_REQUESTED") | Some("DISMISSED") => (login, false),
+                    _ => continue,
+                }
+            }
+            _ => return Err("Expected a JSON array of logins or reviews".to_string()),
+        };
+        let login = format!("@{}", login.trim_start_matches('@'));
+        if !latest.contains_key(&login) {
+            order.push(login.clone());
+        }
+        latest.insert(login, approved);
+    }
+
+    Ok(order.into_iter().filter(|login| latest[login]).collect())
+}
+
+/// Match approvals against the owners of each file in `plan`. `members`
+/// maps teams to their members; teams missing from it can't be satisfied.
+pub fn approval_report(
+    plan: &ReviewPlan,
+    approvers: &[String],
+    members: &HashMap<String, Vec<String>>,
+    unexpanded: Vec<UnexpandedTeam>,
+) -> ApprovalReport {
+    let approved_login = |login: &str| {
+        approvers
+            .iter()
+            .find(|approver| approver.eq_ignore_ascii_case(login))
+    };
+
+    let mut approved = Vec::new();
+    let mut missing = Vec::new();
+    for file in &plan.files {
+        let mut approved_by = BTreeSet::new();
+        for owner in &file.owners {
+            if let Some(approver) = approved_login(owner) {
+                approved_by.insert(approver.clone());
+            }
+            for member in members.get(owner).into_iter().flatten() {
+                if let Some(approver) = approved_login(member) {
+                    approved_by.insert(format!("{} ({})", approver, owner));
+                }
+            }
+        }
+        let approval = FileApproval {
+            file: file.file.clone(),
+            owners: file.owners.clone(),
+            approved_by: approved_by.into_iter().collect(),
+        };
+        if approval.approved_by.is_empty() {
+            missing.push(approval);
+        } else {
+            approved.push(approval);
+        }
+    }
+
+    let open: Vec<Vec<String>> = missing.iter().map(|file| file.owners.clone()).collect();
+    ApprovalReport {
+        approvers: approvers.to_vec(),
+        approved,
+        missing,
+        unowned: plan.unowned.clone(),
+        still_needed: minimum_cover(&open),
+        unexpanded,
+    }
+}
+
+/// Build the report, expanding only the teams of files no user owner has
+/// approved yet. Without a token teams aren't expanded.
+pub async fn check_approvals(
+    client: &GitHubClient,
+    plan: &ReviewPlan,
+    approvers: &[String],
+    token: Option<&str>,
+) -> ApprovalReport {
+    let direct = approval_report(plan, approvers, &HashMap::new(), Vec::new());
+    let teams: BTreeSet<String> = direct
+        .missing
+        .iter()
+        .flat_map(|file| &file.owners)
+        .filter(|owner| owner.starts_with('@') && owner.contains('/'))
+        .cloned()
+        .collect();
+    if teams.is_empty() {
+        return direct;
+    }
+
+    let (members, unexpanded) = match token {
+        Some(token) => fetch_team_members(client, teams.into_iter().collect(), token).await,
+        None => (
+            HashMap::new(),
+            teams
+                .into_iter()
+                .map(|team| UnexpandedTeam {
+                    team,
+                    reason: "no GitHub token".to_string(),
+                })
+                .collect(),
+        ),
+    };
+    approval_report(plan, approvers, &members, unexpanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reviewers::review_plan;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const CODEOWNERS: &str = "* @org/core\n/docs/ @alice @docs-lead\n/api/ @org/api\n";
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_logins() {
+        let approvers = parse_approvals(r#"["alice", "@bob"]"#).unwrap();
+        assert_eq!(approvers, vec!["@alice", "@bob"]);
+        assert!(parse_approvals("{}").is_err());
+        assert!(parse_approvals("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_reviews_uses_latest_state() {
+        let reviews = r#"[
+            {"user": {"login": "alice"}, "state": "APPROVED"},
+            {"user": {"login": "bob"}, "state": "APPROVED"},
+            {"user": {"login": "bob"}, "state": "CHANGES_REQUESTED"},
+            {"user": {"login": "carol"}, "state": "CHANGES_REQUESTED"},
+            {"user": {"login": "carol"}, "state": "APPROVED"},
+            {"user": {"login": "alice"}, "state": "COMMENTED"},
+            {"user": {"login": "dave"}, "state": "COMMENTED"}
+        ]"#;
+        assert_eq!(parse_approvals(reviews).unwrap(), vec!["@alice", "@carol"]);
+    }
+
+    #[test]
+    fn test_direct_approval() {
+        let plan = review_plan(CODEOWNERS, &files(&["docs/a.md", "src/main.rs"]));
+        let report = approval_report(&plan, &files(&["@ALICE"]), &HashMap::new(), vec![]);
+        assert_eq!(report.approved.len(), 1);
+        assert_eq!(report.approved[0].approved_by, vec!["@ALICE"]);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].file, "src/main.rs");
+        assert_eq!(report.still_needed, vec!["@org/core"]);
+        assert!(!report.is_satisfied());
+    }
+
+    #[tokio::test]
+    async fn test_team_approval_through_github() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orgs/org/teams/core/members"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([{ "login": "bob" }, { "login": "eve" }])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orgs/org/teams/api/members"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let client = GitHubClient::with_base_url(&mock_server.uri());
+        let plan = review_plan(
+            CODEOWNERS,
+            &files(&["docs/a.md", "src/main.rs", "api/x.go", "README"]),
+        );
+        let approvers = files(&["@alice", "@bob"]);
+
+        let report = check_approvals(&client, &plan, &approvers, Some("fake-token")).await;
+        let main = report
+            .approved
+            .iter()
+            .find(|f| f.file == "src/main.rs")
+            .unwrap();
+        assert_eq!(main.approved_by, vec!["@bob (@org/core)"]);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].file, "api/x.go");
+        assert_eq!(report.still_needed, vec!["@org/api"]);
+        assert_eq!(report.unexpanded.len(), 1);
+        assert_eq!(report.unexpanded[0].team, "@org/api");
+
+        // Without a token nothing is fetched and teams stay unsatisfied
+        let report = check_approvals(&client, &plan, &approvers, None).await;
+        assert_eq!(report.missing.len(), 3);
+        assert_eq!(report.unexpanded.len(), 2);
+    }
+}
@@ -0,0 +1,259 @@
+//! Lint baselines - accept existing diagnostics, fail only on new ones
+//!
+//! Entries are keyed by diagnostic code plus a fingerprint of the rule text
+//! and the flagged span, not by line number, so adding or moving rules
+//! doesn't invalidate the baseline. Identical keys are counted, so two
+//! copies of the same broken rule need two baseline entries.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{Diagnostic, NumberOrString, Range};
+
+use crate::report::stable_hash;
+
+/// Default baseline file name, relative to the working directory
+pub const DEFAULT_BASELINE: &str = ".codeowners-baseline.json";
+
+const VERSION: u32 = 1;
+
+/// Accepted diagnostics for one code and fingerprint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub code: String,
+    pub fingerprint: String,
+    /// The rule as written, for reviewers reading the file
+    pub rule: String,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn is_one(count: &usize) -> bool {
+    *count == 1
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// Diagnostics split against a baseline
+#[derive(Debug, Default)]
+pub struct BaselineComparison {
+    /// Not in the baseline
+    pub new: Vec<Diagnostic>,
+    /// Matched a baseline entry
+    pub known: usize,
+    /// Baseline entries that no longer occur (count is how many are gone)
+    pub fixed: Vec<BaselineEntry>,
+}
+
+/// Text with whitespace collapsed, so realigning owners keeps the key
+fn normalized(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn code(diagnostic: &Diagnostic) -> String {
+    match &diagnostic.code {
+        Some(NumberOrString::String(code)) => code.clone(),
+        Some(NumberOrString::Number(code)) => code.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Key of a diagnostic that survives moved lines: (code, fingerprint, rule)
+pub fn key(content: &str, diagnostic: &Diagnostic) -> (String, String, String) {
+    let (fingerprint, rule) = rule_fingerprint(content, diagnostic.range);
+    (code(diagnostic), fingerprint, rule)
+}
+
+/// Fingerprint of the rule text `range` sits on and the span it covers,
+/// without the line number: (fingerprint, normalized rule)
+pub fn rule_fingerprint(content: &str, range: Range) -> (String, String) {
+    let line = content.lines().nth(range.start.line as usize).unwrap_or("");
+    let rule = normalized(line);
+    let span: String = if range.start.line == range.end.line {
+        line.chars()
+            .skip(range.start.character as usize)
+            .take(range.end.character.saturating_sub(range.start.character) as usize)
+            .collect()
+    } else {
+        String::new()
+    };
+    let fingerprint = stable_hash(&format!("{}\0{}", rule, normalized(&span)));
+    (fingerprint, rule)
+}
+
+impl Baseline {
+    /// Baseline accepting every diagnostic in `diagnostics`
+    pub fn from_diagnostics(content: &str, diagnostics: &[Diagnostic]) -> Self {
+        let mut counts: BTreeMap<(String, String), BaselineEntry> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            let (code, fingerprint, rule) = key(content, diagnostic);
+            counts
+                .entry((code.clone(), fingerprint.clone()))
+                .and_modify(|entry| entry.count += 1)
+                .or_insert(BaselineEntry {
+                    code,
+                    fingerprint,
+                    rule,
+                    count: 1,
+                });
+        }
+        Baseline {
+            version: VERSION,
+            entries: counts.into_values().collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read baseline {}: {}", path.display(), e))?;
+        let baseline: Baseline = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid baseline {}: {}", path.display(), e))?;
+        if baseline.version != VERSION {
+            return Err(format!(
+                "Unsupported baseline version {} in {} (expected {})",
+                baseline.version,
+                path.display(),
+                VERSION
+            ));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap() + "\n";
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Split `diagnostics` into new and known, and find entries that are fixed
+    pub fn compare(&self, content: &str, diagnostics: Vec<Diagnostic>) -> BaselineComparison {
+        let mut remaining: BTreeMap<(String, String), usize> = self
+            .entries
+            .iter()
+            .map(|e| ((e.code.clone(), e.fingerprint.clone()), e.count))
+            .collect();
+
+        let mut comparison = BaselineComparison::default();
+        for diagnostic in diagnostics {
+            let (code, fingerprint, _) = key(content, &diagnostic);
+            match remaining.get_mut(&(code, fingerprint)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    comparison.known += 1;
+                }
+                _ => comparison.new.push(diagnostic),
+            }
+        }
+
+        comparison.fixed = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let left = remaining[&(entry.code.clone(), entry.fingerprint.clone())];
+                (left > 0).then(|| BaselineEntry {
+                    count: left,
+                    ..entry.clone()
+                })
+            })
+            .collect();
+        comparison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    fn diagnostic(code: &str, line: u32, start: u32, end: u32) -> Diagnostic {
+        Diagnostic {
+            range: Range {
+                start: Position {
+                    line,
+                    character: start,
+                },
+                end: Position {
+                    line,
+                    character: end,
+                },
+            },
+            code: Some(NumberOrString::String(code.to_string())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_baseline_survives_moved_and_realigned_rules() {
+        let before = "/old/ @a\n/gone/ @b @b\n";
+        let baseline = Baseline::from_diagnostics(
+            before,
+            &[
+                diagnostic("pattern-no-match", 0, 0, 5),
+                // Owner diagnostics run to the end of the line
+                diagnostic("duplicate-owner", 1, 7, u32::MAX),
+            ],
+        );
+        assert_eq!(baseline.entries.len(), 2);
+        assert_eq!(baseline.entries[0].rule, "/gone/ @b @b");
+
+        // Lines moved and realigned, a new issue appeared, /old/ was fixed
+        let after = "* @core\n/gone/   @b   @b\n/new/ @c\n";
+        let comparison = baseline.compare(
+            after,
+            vec![
+                diagnostic("duplicate-owner", 1, 9, u32::MAX),
+                diagnostic("pattern-no-match", 2, 0, 5),
+            ],
+        );
+        assert_eq!(comparison.known, 1);
+        assert_eq!(comparison.new.len(), 1);
+        assert_eq!(comparison.new[0].range.start.line, 2);
+        assert_eq!(comparison.fixed.len(), 1);
+        assert_eq!(comparison.fixed[0].rule, "/old/ @a");
+    }
+
+    #[test]
+    fn test_baseline_counts_identical_rules() {
+        let content = "/gone/ @a\n/gone/ @a\n";
+        let one = Baseline::from_diagnostics(content, &[diagnostic("pattern-no-match", 0, 0, 6)]);
+        let comparison = one.compare(
+            content,
+            vec![
+                diagnostic("pattern-no-match", 0, 0, 6),
+                diagnostic("pattern-no-match", 1, 0, 6),
+            ],
+        );
+        assert_eq!((comparison.known, comparison.new.len()), (1, 1));
+
+        let second = comparison.new[0].clone();
+        let both = Baseline::from_diagnostics(content, &[second.clone(), second]);
+        assert_eq!(both.entries[0].count, 2);
+        let comparison = both.compare(content, vec![diagnostic("pattern-no-match", 1, 0, 6)]);
+        assert!(comparison.new.is_empty());
+        assert_eq!(comparison.fixed[0].count, 1);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_BASELINE);
+        let baseline =
+            Baseline::from_diagnostics("/x/ @a\n", &[diagnostic("pattern-no-match", 0, 0, 3)]);
+        baseline.save(&path).unwrap();
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(!json.contains("count"));
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+
+        fs::write(&path, r#"{"version": 9, "entries": []}"#).unwrap();
+        assert!(Baseline::load(&path).unwrap_err().contains("version 9"));
+    }
+}
@@ -2,22 +2,34 @@ mod commands;
 
 // Re-export shared modules so `crate::*` paths in commands/ submodules still resolve
 use codeowners_lsp as lib;
+pub use lib::approvals;
+pub use lib::baseline;
 pub use lib::blame;
 pub use lib::diagnostics;
 pub use lib::file_cache;
+pub use lib::formatter;
+pub use lib::git;
 pub use lib::github;
+pub use lib::history;
 pub use lib::lookup;
 pub use lib::ownership;
 pub use lib::parser;
+pub use lib::path_suggest;
 pub use lib::pattern;
+pub use lib::query;
+pub use lib::report;
+pub use lib::reviewers;
+pub use lib::rule_move;
+pub use lib::rule_sort;
 pub use lib::settings;
+pub use lib::stats;
 pub use lib::validation;
 
 use std::io;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 
 #[derive(Parser)]
@@ -27,6 +39,10 @@ use clap_complete::{generate, Shell};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Read CODEOWNERS and the file list at this git revision instead of the
+    /// working tree (check, coverage, tree, validate-owners, query)
+    #[arg(long, global = true, value_name = "REV")]
+    rev: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -47,8 +63,17 @@ enum Commands {
         /// Output GitHub Actions annotations (::error, ::warning)
         #[arg(long)]
         github_actions: bool,
+        /// Report format for CI tools (sarif, junit, checkstyle, gitlab)
+        #[arg(long, value_name = "FORMAT", conflicts_with_all = ["json", "github_actions", "fix"])]
+        format: Option<String>,
+        /// Fail only on diagnostics missing from this baseline file
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+        /// Record current diagnostics as the baseline (to --baseline or .codeowners-baseline.json)
+        #[arg(long, conflicts_with_all = ["fix", "json", "github_actions", "format"])]
+        write_baseline: bool,
     },
-    /// Format CODEOWNERS file (normalizes spacing)
+    /// Format CODEOWNERS file (normalizes spacing, optional alignment and owner sorting)
     #[command(alias = "format")]
     Fmt {
         /// Path to CODEOWNERS file (default: auto-detect)
@@ -56,6 +81,27 @@ enum Commands {
         /// Write changes to file (default: dry-run)
         #[arg(short, long)]
         write: bool,
+        /// Print a unified diff and exit non-zero if the file is not formatted
+        #[arg(long, conflicts_with = "write")]
+        check: bool,
+        /// Align owner columns within each section
+        #[arg(long)]
+        align_owners: bool,
+        /// Sort and deduplicate owners on each rule
+        #[arg(long)]
+        sort_owners: bool,
+        /// Sort rules within each section where no file's owners would change
+        #[arg(long)]
+        sort_rules: bool,
+        /// Comment style (preserve, space)
+        #[arg(long)]
+        comment_style: Option<String>,
+        /// Maximum consecutive blank lines to keep
+        #[arg(long)]
+        max_blank_lines: Option<usize>,
+        /// Line endings (auto, lf, crlf)
+        #[arg(long)]
+        line_endings: Option<String>,
     },
     /// Show which rule owns a specific file (or multiple files)
     Check {
@@ -74,6 +120,9 @@ enum Commands {
         /// Read files to check from stdin (one per line)
         #[arg(long)]
         stdin: bool,
+        /// List every matching rule in order and why it wins or loses
+        #[arg(long)]
+        explain: bool,
     },
     /// Show files without owners and coverage percentage
     Coverage {
@@ -92,6 +141,19 @@ enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Report format for CI tools (sarif, junit, checkstyle, gitlab)
+        #[arg(long, value_name = "FORMAT", conflicts_with = "json")]
+        format: Option<String>,
+    },
+    /// List files whose owners change between two git revisions
+    Diff {
+        /// Base revision (branch, tag or commit)
+        base: String,
+        /// Head revision (default: working tree)
+        head: Option<String>,
+        /// Output format (human, json, markdown)
+        #[arg(long, default_value = "human")]
+        format: String,
     },
     /// Generate shell completions
     Completions {
@@ -116,6 +178,84 @@ enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Report format for CI tools (sarif, junit, checkstyle, gitlab)
+        #[arg(long, value_name = "FORMAT", conflicts_with = "json")]
+        format: Option<String>,
+    },
+    /// Show which owners must approve a change and the fewest reviewers that cover it
+    Reviewers {
+        /// Changed file path(s) (positional)
+        #[arg(num_args = 0..)]
+        paths: Vec<String>,
+        /// Changed file path(s) (named, like check)
+        #[arg(long, num_args = 1..)]
+        files: Option<Vec<String>>,
+        /// Read changed files from a file (one per line)
+        #[arg(long, value_name = "PATH")]
+        files_from: Option<PathBuf>,
+        /// Read changed files from stdin (one per line)
+        #[arg(long)]
+        stdin: bool,
+        /// Expand teams to members and suggest the fewest individual reviewers
+        #[arg(long)]
+        expand_teams: bool,
+        /// GitHub token for --expand-teams (or use GITHUB_TOKEN env var)
+        #[arg(long, env = "GITHUB_TOKEN")]
+        token: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check whether PR approvals satisfy CODEOWNERS for the changed files
+    Approvals {
+        /// Changed file path(s) (positional)
+        #[arg(num_args = 0..)]
+        paths: Vec<String>,
+        /// Changed file path(s) (named, like check)
+        #[arg(long, num_args = 1..)]
+        files: Option<Vec<String>>,
+        /// Read changed files from a file (one per line)
+        #[arg(long, value_name = "PATH")]
+        files_from: Option<PathBuf>,
+        /// Read changed files from stdin (one per line)
+        #[arg(long)]
+        stdin: bool,
+        /// JSON list of approving logins, or a GitHub reviews API response ("-" for stdin)
+        #[arg(long, value_name = "PATH")]
+        approvals: PathBuf,
+        /// GitHub token for expanding team owners (or use GITHUB_TOKEN env var)
+        #[arg(long, env = "GITHUB_TOKEN")]
+        token: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show every change to a path's owners from the git history of CODEOWNERS
+    History {
+        /// File path (relative to the repository root)
+        path: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List files matching an ownership query, e.g. 'owner:@org/web and ext:ts and not path:legacy/**'
+    Query {
+        /// Predicates (owner:, ext:, path:, rule:, owners>N, owned, unowned,
+        /// individual-only, team-only) joined with and/or/not and parentheses
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+        /// Separate paths with NUL instead of newlines (for xargs -0)
+        #[arg(short = '0', long)]
+        null: bool,
+        /// Output as JSON (path, rule, line, owners)
+        #[arg(long, conflicts_with = "null")]
+        json: bool,
+    },
+    /// Files, lines and rules per owner and owner set
+    Stats {
+        /// Output format (table, csv, json)
+        #[arg(long, default_value = "table")]
+        format: String,
     },
     /// Show all files color-coded by owner
     Tree,
@@ -153,49 +293,144 @@ enum Commands {
     },
     /// Run all checks for GitHub Actions (outputs annotations, step summary, outputs)
     #[command(name = "gha")]
-    Gha {
-        /// GitHub token (or use GITHUB_TOKEN env var)
-        #[arg(long, env = "GITHUB_TOKEN")]
-        token: String,
-        /// Read changed files from a file (one per line)
-        #[arg(long, value_name = "PATH")]
-        changed_files_from: Option<PathBuf>,
-        /// Read changed files from stdin (one per line)
-        #[arg(long)]
-        changed_files_stdin: bool,
-        /// Skip coverage check for changed files
-        #[arg(long)]
-        no_coverage_changed: bool,
-        /// Skip coverage check for all files
-        #[arg(long)]
-        no_coverage_all: bool,
-        /// Skip owner validation for changed files
-        #[arg(long)]
-        no_owners_changed: bool,
-        /// Skip owner validation for all files
+    Gha(CheckArgs),
+    /// Run all checks for the detected CI provider (GitHub Actions, GitLab CI, Buildkite, Azure Pipelines)
+    Ci {
+        /// CI provider: github, gitlab, buildkite or azure (default: detect from the environment)
         #[arg(long)]
-        no_owners_all: bool,
-        /// Skip lint check
-        #[arg(long)]
-        no_lint: bool,
-        /// Fail on warnings too (like lint --strict)
-        #[arg(long)]
-        strict: bool,
-        /// Disable ::error::/::warning:: annotations
-        #[arg(long)]
-        no_annotations: bool,
-        /// Disable GITHUB_STEP_SUMMARY output
+        provider: Option<String>,
+        #[command(flatten)]
+        checks: CheckArgs,
+    },
+    /// Manage git hooks
+    Hook {
+        #[command(subcommand)]
+        action: HookCommand,
+    },
+    /// Fail when newly added (staged) files have no owner, printing the rules to add
+    CheckStaged {
+        /// Check files added since this revision instead of the staged ones
+        #[arg(long, value_name = "REV")]
+        base: Option<String>,
+    },
+    /// Answer ownership queries as JSON over local HTTP, reloading when CODEOWNERS changes
+    Serve {
+        /// TCP port on 127.0.0.1 (default: 7878 unless --socket is given)
         #[arg(long)]
-        no_summary: bool,
-        /// Disable GITHUB_OUTPUT variables
+        port: Option<u16>,
+        /// Also listen on this Unix socket
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+    },
+    /// Re-run a report whenever CODEOWNERS or the file tree changes, printing only what changed
+    Watch {
+        #[command(subcommand)]
+        report: Option<WatchCommand>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Write a hook script that runs check-staged
+    Install {
+        /// Hook to install: pre-commit or pre-push
+        #[arg(default_value = "pre-commit")]
+        hook: String,
+        /// Replace an existing hook not written by codeowners-cli
         #[arg(long)]
-        no_outputs: bool,
+        force: bool,
     },
 }
 
+/// Reports `watch` can keep up to date
+#[derive(Subcommand)]
+enum WatchCommand {
+    /// Lint diagnostics (the default)
+    Lint,
+    /// Unowned files and the coverage percentage
+    Coverage,
+    /// Owners of the given paths
+    Check {
+        /// File paths to check
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+}
+
+/// Checks and output options shared by `gha` and `ci`
+#[derive(Args)]
+struct CheckArgs {
+    /// GitHub token (or use GITHUB_TOKEN env var)
+    #[arg(long, env = "GITHUB_TOKEN")]
+    token: String,
+    /// Read changed files from a file (one per line)
+    #[arg(long, value_name = "PATH")]
+    changed_files_from: Option<PathBuf>,
+    /// Read changed files from stdin (one per line)
+    #[arg(long)]
+    changed_files_stdin: bool,
+    /// Base revision for the ownership diff when CODEOWNERS changed (default: origin/<PR target branch>)
+    #[arg(long, value_name = "REV")]
+    diff_base: Option<String>,
+    /// Skip coverage check for changed files
+    #[arg(long)]
+    no_coverage_changed: bool,
+    /// Skip coverage check for all files
+    #[arg(long)]
+    no_coverage_all: bool,
+    /// Skip owner validation for changed files
+    #[arg(long)]
+    no_owners_changed: bool,
+    /// Skip owner validation for all files
+    #[arg(long)]
+    no_owners_all: bool,
+    /// Skip lint check
+    #[arg(long)]
+    no_lint: bool,
+    /// Fail lint only on diagnostics missing from this baseline file
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+    /// Skip the required reviewers report
+    #[arg(long)]
+    no_reviewers: bool,
+    /// Expand teams to suggest the fewest individual reviewers
+    #[arg(long)]
+    expand_teams: bool,
+    /// Fail on warnings too (like lint --strict)
+    #[arg(long)]
+    strict: bool,
+    /// Disable annotations (workflow commands, GitLab code quality report)
+    #[arg(long)]
+    no_annotations: bool,
+    /// Disable the job summary (GITHUB_STEP_SUMMARY, Buildkite annotation, Azure summary)
+    #[arg(long)]
+    no_summary: bool,
+    /// Disable output variables (GITHUB_OUTPUT, GitLab dotenv, Buildkite meta-data, Azure variables)
+    #[arg(long)]
+    no_outputs: bool,
+    /// Print a report for CI tools on stdout instead of the summary (sarif, junit, checkstyle, gitlab)
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<String>,
+}
+
 #[tokio::main]
 async fn main() -> ExitCode {
     let args = Cli::parse();
+    let rev = args.rev;
+
+    if rev.is_some()
+        && !matches!(
+            args.command,
+            Commands::Check { .. }
+                | Commands::Coverage { .. }
+                | Commands::Tree
+                | Commands::ValidateOwners { .. }
+                | Commands::Query { .. }
+        )
+    {
+        eprintln!("--rev is only supported by check, coverage, tree, validate-owners and query");
+        return ExitCode::from(2);
+    }
 
     match args.command {
         Commands::Lint {
@@ -204,22 +439,75 @@ async fn main() -> ExitCode {
             fix,
             strict,
             github_actions,
-        } => commands::lint(path, json, fix, strict, github_actions).await,
-        Commands::Fmt { path, write } => commands::fmt(path, write),
+            format,
+            baseline,
+            write_baseline,
+        } => match parse_report_format(format) {
+            Ok(format) => {
+                commands::lint(commands::LintOptions {
+                    path,
+                    json,
+                    fix,
+                    strict,
+                    github_actions,
+                    format,
+                    baseline,
+                    write_baseline,
+                })
+                .await
+            }
+            Err(code) => code,
+        },
+        Commands::Fmt {
+            path,
+            write,
+            check,
+            align_owners,
+            sort_owners,
+            sort_rules,
+            comment_style,
+            max_blank_lines,
+            line_endings,
+        } => commands::fmt(commands::FmtOptions {
+            path,
+            write,
+            check,
+            align_owners,
+            sort_owners,
+            sort_rules,
+            comment_style,
+            max_blank_lines,
+            line_endings,
+        }),
         Commands::Check {
             paths,
             files,
             json,
             files_from,
             stdin,
-        } => commands::check(paths, files, json, files_from, stdin),
+            explain,
+        } => commands::check(paths, files, json, files_from, stdin, explain, rev),
         Commands::Coverage {
             files,
             files_from,
             stdin,
             tree,
             json,
-        } => commands::coverage(files, files_from, stdin, tree, json),
+            format,
+        } => match parse_report_format(format) {
+            Ok(format) => commands::coverage(files, files_from, stdin, tree, json, format, rev),
+            Err(code) => code,
+        },
+        Commands::Diff { base, head, format } => {
+            let format = match format.parse::<commands::DiffFormat>() {
+                Ok(format) => format,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::from(2);
+                }
+            };
+            commands::diff(commands::DiffOptions { base, head, format })
+        }
         Commands::Completions { shell } => {
             generate(
                 shell,
@@ -235,8 +523,95 @@ async fn main() -> ExitCode {
             files_from,
             stdin,
             json,
-        } => commands::validate_owners(&token, files, files_from, stdin, json).await,
-        Commands::Tree => commands::tree(),
+            format,
+        } => match parse_report_format(format) {
+            Ok(format) => {
+                commands::validate_owners(&token, files, files_from, stdin, json, format, rev).await
+            }
+            Err(code) => code,
+        },
+        Commands::Reviewers {
+            mut paths,
+            files,
+            files_from,
+            stdin,
+            expand_teams,
+            token,
+            json,
+        } => {
+            paths.extend(files.unwrap_or_default());
+            let files = match commands::files::collect_files(Some(paths), files_from, stdin) {
+                Ok(Some(set)) => set.into_iter().collect(),
+                Ok(None) => {
+                    eprintln!("No files specified");
+                    return ExitCode::from(1);
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::from(1);
+                }
+            };
+            commands::reviewers(commands::ReviewersOptions {
+                files,
+                json,
+                expand_teams,
+                token,
+            })
+            .await
+        }
+        Commands::Approvals {
+            mut paths,
+            files,
+            files_from,
+            stdin,
+            approvals,
+            token,
+            json,
+        } => {
+            if stdin && approvals.as_os_str() == "-" {
+                eprintln!("--stdin and --approvals - can't both read stdin");
+                return ExitCode::from(2);
+            }
+            paths.extend(files.unwrap_or_default());
+            let files = match commands::files::collect_files(Some(paths), files_from, stdin) {
+                Ok(Some(set)) => set.into_iter().collect(),
+                Ok(None) => {
+                    eprintln!("No files specified");
+                    return ExitCode::from(1);
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::from(1);
+                }
+            };
+            commands::approvals(commands::ApprovalsOptions {
+                files,
+                approvals,
+                token,
+                json,
+            })
+            .await
+        }
+        Commands::History { path, json } => commands::history(path, json),
+        Commands::Query { query, null, json } => commands::query(commands::QueryOptions {
+            query: query.join(" "),
+            format: if json {
+                commands::QueryFormat::Json
+            } else if null {
+                commands::QueryFormat::Null
+            } else {
+                commands::QueryFormat::Lines
+            },
+            rev,
+        }),
+        Commands::Stats { format } => match format.parse::<commands::StatsFormat>() {
+            Ok(format) => commands::stats(format),
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::from(2)
+            }
+        },
+        Commands::Tree => commands::tree(rev),
         Commands::Config => commands::config(),
         Commands::Suggest {
             min_confidence,
@@ -272,45 +647,114 @@ async fn main() -> ExitCode {
             min_files_for_dir: min_files,
             write,
         }),
-        Commands::Gha {
-            token,
-            changed_files_from,
-            changed_files_stdin,
-            no_coverage_changed,
-            no_coverage_all,
-            no_owners_changed,
-            no_owners_all,
-            no_lint,
-            strict,
-            no_annotations,
-            no_summary,
-            no_outputs,
-        } => {
-            // Read changed files
-            let changed_files =
-                match commands::files::collect_files(None, changed_files_from, changed_files_stdin)
-                {
-                    Ok(f) => f.map(|s| s.into_iter().collect()),
+        Commands::Gha(checks) => run_checks(checks, commands::CiProvider::GitHub).await,
+        Commands::Ci { provider, checks } => {
+            let provider = match provider {
+                Some(provider) => match provider.parse::<commands::CiProvider>() {
+                    Ok(provider) => provider,
                     Err(e) => {
-                        eprintln!("::error::{}", e);
-                        return ExitCode::from(1);
+                        eprintln!("{}", e);
+                        return ExitCode::from(2);
                     }
-                };
-
-            commands::gha(commands::GhaOptions {
-                token,
-                changed_files,
-                check_coverage_changed: !no_coverage_changed,
-                check_coverage_all: !no_coverage_all,
-                check_owners_changed: !no_owners_changed,
-                check_owners_all: !no_owners_all,
-                check_lint: !no_lint,
-                strict,
-                output_annotations: !no_annotations,
-                output_summary: !no_summary,
-                output_vars: !no_outputs,
-            })
-            .await
+                },
+                None => match commands::CiProvider::detect() {
+                    Some(provider) => provider,
+                    None => {
+                        eprintln!("Could not detect the CI provider (set --provider)");
+                        return ExitCode::from(2);
+                    }
+                },
+            };
+            run_checks(checks, provider).await
         }
+        Commands::Hook {
+            action: HookCommand::Install { hook, force },
+        } => match hook.parse::<commands::HookKind>() {
+            Ok(kind) => commands::install_hook(kind, force),
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::from(2)
+            }
+        },
+        Commands::CheckStaged { base } => commands::check_staged(base),
+        Commands::Serve { port, socket } => {
+            commands::serve(commands::ServeOptions { port, socket }).await
+        }
+        Commands::Watch { report } => commands::watch(match report {
+            None | Some(WatchCommand::Lint) => commands::WatchReport::Lint,
+            Some(WatchCommand::Coverage) => commands::WatchReport::Coverage,
+            Some(WatchCommand::Check { paths }) => commands::WatchReport::Check(paths),
+        }),
     }
 }
+
+/// Run the `gha` checks, reporting through `provider`
+async fn run_checks(checks: CheckArgs, provider: commands::CiProvider) -> ExitCode {
+    let CheckArgs {
+        token,
+        changed_files_from,
+        changed_files_stdin,
+        diff_base,
+        no_coverage_changed,
+        no_coverage_all,
+        no_owners_changed,
+        no_owners_all,
+        no_lint,
+        baseline,
+        no_reviewers,
+        expand_teams,
+        strict,
+        no_annotations,
+        no_summary,
+        no_outputs,
+        format,
+    } = checks;
+    let format = match parse_report_format(format) {
+        Ok(format) => format,
+        Err(code) => return code,
+    };
+
+    // Read changed files
+    let changed_files =
+        match commands::files::collect_files(None, changed_files_from, changed_files_stdin) {
+            Ok(f) => f.map(|s| s.into_iter().collect()),
+            Err(e) => {
+                provider
+                    .reporter(format.is_some())
+                    .annotate(report::Level::Error, &e);
+                return ExitCode::from(1);
+            }
+        };
+
+    commands::gha(commands::GhaOptions {
+        token,
+        changed_files,
+        diff_base,
+        check_coverage_changed: !no_coverage_changed,
+        check_coverage_all: !no_coverage_all,
+        check_owners_changed: !no_owners_changed,
+        check_owners_all: !no_owners_all,
+        check_lint: !no_lint,
+        baseline,
+        check_reviewers: !no_reviewers,
+        expand_teams,
+        strict,
+        output_annotations: !no_annotations,
+        output_summary: !no_summary,
+        output_vars: !no_outputs,
+        provider,
+        format,
+    })
+    .await
+}
+
+/// Parse an optional `--format` report format, exiting 2 on an unknown one
+fn parse_report_format(format: Option<String>) -> Result<Option<report::ReportFormat>, ExitCode> {
+    format
+        .map(|f| f.parse::<report::ReportFormat>())
+        .transpose()
+        .map_err(|e| {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        })
+}
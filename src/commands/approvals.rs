@@ -0,0 +1,132 @@
+//! Approvals command - whether a PR's approvals already satisfy CODEOWNERS.
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+
+use colored::Colorize;
+
+use crate::approvals::{check_approvals, parse_approvals, ApprovalReport};
+use crate::github::GitHubClient;
+use crate::ownership::find_codeowners;
+use crate::reviewers::review_plan;
+
+/// Options for the approvals command
+pub struct ApprovalsOptions {
+    pub files: Vec<String>,
+    /// JSON list of approving logins or GitHub reviews; `-` reads stdin
+    pub approvals: PathBuf,
+    /// GitHub token for expanding teams (teams stay unsatisfied without one)
+    pub token: Option<String>,
+    pub json: bool,
+}
+
+pub async fn approvals(opts: ApprovalsOptions) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let codeowners_path = match find_codeowners(&cwd) {
+        Some(p) => p,
+        None => {
+            eprintln!("No CODEOWNERS file found");
+            return ExitCode::from(1);
+        }
+    };
+
+    let content = match fs::read_to_string(&codeowners_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let approvals_json = if opts.approvals.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map(|_| input)
+            .map_err(|e| format!("Failed to read stdin: {}", e))
+    } else {
+        fs::read_to_string(&opts.approvals)
+            .map_err(|e| format!("Failed to read '{}': {}", opts.approvals.display(), e))
+    };
+    let approvers = match approvals_json.and_then(|json| parse_approvals(&json)) {
+        Ok(approvers) => approvers,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let plan = review_plan(&content, &opts.files);
+    let report = check_approvals(
+        &GitHubClient::new(),
+        &plan,
+        &approvers,
+        opts.token.as_deref(),
+    )
+    .await;
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_human(&report);
+    }
+
+    if report.is_satisfied() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+fn print_human(report: &ApprovalReport) {
+    for file in &report.approved {
+        println!(
+            "{} {} {}",
+            "✓".green(),
+            file.file,
+            format!("approved by {}", file.approved_by.join(", ")).dimmed()
+        );
+    }
+    for file in &report.missing {
+        println!(
+            "{} {} {}",
+            "✗".red(),
+            file.file,
+            format!("needs {}", file.owners.join(" or ")).yellow()
+        );
+    }
+    for file in &report.unowned {
+        println!("{} {} {}", "-".dimmed(), file, "(no owners)".dimmed());
+    }
+    for team in &report.unexpanded {
+        eprintln!(
+            "{} Could not expand {}: {}",
+            "⚠".yellow(),
+            team.team.cyan(),
+            team.reason
+        );
+    }
+
+    println!();
+    if report.is_satisfied() {
+        println!(
+            "{} All changed files have a code owner approval",
+            "✓".green()
+        );
+    } else {
+        println!(
+            "{} {} {} a code owner approval; fewest reviewers: {}",
+            "✗".red(),
+            report.missing.len().to_string().red(),
+            if report.missing.len() == 1 {
+                "file still needs"
+            } else {
+                "files still need"
+            },
+            report.still_needed.join(" ").cyan()
+        );
+    }
+}
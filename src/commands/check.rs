@@ -1,21 +1,46 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::ExitCode;
-use std::{env, fs};
 
 use colored::Colorize;
 use serde::Serialize;
 
 use super::files::collect_files;
-use crate::ownership::{check_file_ownership_parsed, find_codeowners};
-use crate::parser::parse_codeowners_file_with_positions;
+use super::source::Source;
+use crate::ownership::{check_file_ownership_parsed, explain_ownership_parsed, RuleMatch};
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine, ParsedLine};
 
 #[derive(Serialize)]
-struct CheckResultJson {
+pub(super) struct CheckResultJson {
     owned: bool,
     rule: Option<String>,
     line: Option<u32>,
     owners: Vec<String>,
+    /// Every matching rule, in order (with --explain)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<Vec<RuleMatch>>,
+}
+
+impl CheckResultJson {
+    pub(super) fn new(parsed: &[ParsedLine], file_path: &str, explain: bool) -> Self {
+        let matches = explain.then(|| explain_ownership_parsed(parsed, file_path));
+        match check_file_ownership_parsed(parsed, file_path) {
+            Some(r) => CheckResultJson {
+                owned: true,
+                rule: Some(r.pattern),
+                line: Some(r.line_number + 1),
+                owners: r.owners,
+                matches,
+            },
+            None => CheckResultJson {
+                owned: false,
+                rule: None,
+                line: None,
+                owners: vec![],
+                matches,
+            },
+        }
+    }
 }
 
 pub fn check(
@@ -24,21 +49,13 @@ pub fn check(
     json: bool,
     files_from: Option<PathBuf>,
     stdin: bool,
+    explain: bool,
+    rev: Option<String>,
 ) -> ExitCode {
-    let cwd = env::current_dir().expect("Failed to get current directory");
-
-    let codeowners_path = match find_codeowners(&cwd) {
-        Some(p) => p,
-        None => {
-            eprintln!("No CODEOWNERS file found");
-            return ExitCode::from(1);
-        }
-    };
-
-    let content = match fs::read_to_string(&codeowners_path) {
-        Ok(c) => c,
+    let source = match Source::load(rev.as_deref()) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            eprintln!("{}", e);
             return ExitCode::from(1);
         }
     };
@@ -67,35 +84,18 @@ pub fn check(
     };
 
     if json {
-        output_json(&content, &all_files)
+        output_json(&source.content, &all_files, explain)
     } else {
-        output_human(&content, &all_files)
+        output_human(&source.content, &all_files, explain)
     }
 }
 
-fn output_json(content: &str, files: &[String]) -> ExitCode {
+fn output_json(content: &str, files: &[String], explain: bool) -> ExitCode {
     let parsed = parse_codeowners_file_with_positions(content);
     let mut results: HashMap<&str, CheckResultJson> = HashMap::new();
 
     for file_path in files {
-        let result = check_file_ownership_parsed(&parsed, file_path);
-        results.insert(
-            file_path,
-            match result {
-                Some(r) => CheckResultJson {
-                    owned: true,
-                    rule: Some(r.pattern),
-                    line: Some(r.line_number + 1),
-                    owners: r.owners,
-                },
-                None => CheckResultJson {
-                    owned: false,
-                    rule: None,
-                    line: None,
-                    owners: vec![],
-                },
-            },
-        );
+        results.insert(file_path, CheckResultJson::new(&parsed, file_path, explain));
     }
 
     println!(
@@ -105,7 +105,7 @@ fn output_json(content: &str, files: &[String]) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-fn output_human(content: &str, files: &[String]) -> ExitCode {
+fn output_human(content: &str, files: &[String], explain: bool) -> ExitCode {
     let parsed = parse_codeowners_file_with_positions(content);
     let mut any_unowned = false;
 
@@ -124,6 +124,9 @@ fn output_human(content: &str, files: &[String]) -> ExitCode {
                     format!("(line {})", result.line_number + 1).dimmed()
                 );
                 println!("{} {}", "Owners:".bold(), result.owners.join(" ").green());
+                if explain {
+                    print_explanation(&parsed, file_path);
+                }
             }
             None => {
                 any_unowned = true;
@@ -133,6 +136,9 @@ fn output_human(content: &str, files: &[String]) -> ExitCode {
                     "✗".red(),
                     "No matching rule - file has no owners".yellow()
                 );
+                if explain {
+                    print_explanation(&parsed, file_path);
+                }
             }
         }
     }
@@ -145,3 +151,69 @@ fn output_human(content: &str, files: &[String]) -> ExitCode {
         ExitCode::SUCCESS
     }
 }
+
+/// Print every matching rule in order, marking the winner. When nothing
+/// matches, say so and list the rules that were tried.
+fn print_explanation(parsed: &[ParsedLine], file_path: &str) {
+    let matches = explain_ownership_parsed(parsed, file_path);
+    if matches.is_empty() {
+        let rules: Vec<(u32, &String, &Vec<String>)> = parsed
+            .iter()
+            .filter_map(|l| match &l.content {
+                CodeownersLine::Rule { pattern, owners } => {
+                    Some((l.line_number + 1, pattern, owners))
+                }
+                _ => None,
+            })
+            .collect();
+        if rules.is_empty() {
+            println!("No rule matches {} (CODEOWNERS has no rules)", file_path);
+            return;
+        }
+        println!("No rule matches {}", file_path);
+        println!("{}", "Rules considered:".bold());
+        for (line, pattern, owners) in rules {
+            let owners = if owners.is_empty() {
+                "(no owners)".to_string()
+            } else {
+                owners.join(" ")
+            };
+            println!(
+                "  {} {} {} {}",
+                "✗".dimmed(),
+                format!("line {:<4}", line).dimmed(),
+                pattern.dimmed(),
+                owners.dimmed()
+            );
+        }
+        return;
+    }
+
+    println!("{}", "Matching rules (last match wins):".bold());
+    for m in &matches {
+        let owners = if m.owners.is_empty() {
+            "(no owners)".to_string()
+        } else {
+            m.owners.join(" ")
+        };
+        if m.winner {
+            println!(
+                "  {} {} {} {} {}",
+                "✓".green(),
+                format!("line {:<4}", m.line).dimmed(),
+                m.pattern.cyan(),
+                owners.green(),
+                format!("- {}", m.reason).dimmed()
+            );
+        } else {
+            println!(
+                "  {} {} {} {} {}",
+                "✗".dimmed(),
+                format!("line {:<4}", m.line).dimmed(),
+                m.pattern.dimmed(),
+                owners.dimmed(),
+                format!("- {}", m.reason).dimmed()
+            );
+        }
+    }
+}
@@ -0,0 +1,602 @@
+//! CI reporters - how `gha` and `ci` surface annotations, output variables
+//! and the summary on each CI provider.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use colored::Colorize;
+
+use crate::report::{self, Finding, Level, ReportFormat};
+
+/// GitLab Code Quality artifact, relative to the working directory
+pub const GITLAB_CODE_QUALITY_FILE: &str = "gl-code-quality-report.json";
+/// GitLab dotenv artifact with the output variables
+pub const GITLAB_DOTENV_FILE: &str = "codeowners.env";
+/// Markdown for `buildkite-agent annotate`
+pub const BUILDKITE_ANNOTATION_FILE: &str = "codeowners-annotation.md";
+
+/// Supported CI providers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Provider {
+    GitHub,
+    GitLab,
+    Buildkite,
+    Azure,
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "github" | "gha" => Ok(Provider::GitHub),
+            "gitlab" => Ok(Provider::GitLab),
+            "buildkite" => Ok(Provider::Buildkite),
+            "azure" => Ok(Provider::Azure),
+            _ => Err(format!(
+                "Invalid provider '{}' (expected github, gitlab, buildkite or azure)",
+                s
+            )),
+        }
+    }
+}
+
+impl Provider {
+    /// Detect the provider from the environment variables each one sets
+    pub fn detect() -> Option<Provider> {
+        Self::detect_from(|name| env::var(name).ok())
+    }
+
+    fn detect_from(var: impl Fn(&str) -> Option<String>) -> Option<Provider> {
+        let is_set = |name: &str| var(name).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        if is_set("GITHUB_ACTIONS") {
+            Some(Provider::GitHub)
+        } else if is_set("GITLAB_CI") {
+            Some(Provider::GitLab)
+        } else if is_set("BUILDKITE") {
+            Some(Provider::Buildkite)
+        } else if is_set("TF_BUILD") {
+            Some(Provider::Azure)
+        } else {
+            None
+        }
+    }
+
+    /// Target branch of the pull/merge request being built, if any
+    pub fn base_branch(self) -> Option<String> {
+        let var = match self {
+            Provider::GitHub => "GITHUB_BASE_REF",
+            Provider::GitLab => "CI_MERGE_REQUEST_TARGET_BRANCH_NAME",
+            Provider::Buildkite => "BUILDKITE_PULL_REQUEST_BASE_BRANCH",
+            Provider::Azure => "SYSTEM_PULLREQUEST_TARGETBRANCH",
+        };
+        env::var(var)
+            .ok()
+            .map(|branch| branch.trim_start_matches("refs/heads/").to_string())
+            .filter(|branch| !branch.is_empty())
+    }
+
+    /// Reporter for this provider. `stderr` sends its log lines to stderr,
+    /// keeping stdout free for a `--format` report.
+    pub fn reporter(self, stderr: bool) -> Box<dyn Reporter> {
+        let log = Log { stderr };
+        let dir = env::current_dir().expect("Failed to get current directory");
+        match self {
+            Provider::GitHub => Box::new(GitHubReporter { log }),
+            Provider::GitLab => Box::new(GitLabReporter {
+                log,
+                dir,
+                sections: Vec::new(),
+                next_section: 0,
+            }),
+            Provider::Buildkite => Box::new(BuildkiteReporter {
+                log,
+                dir,
+                notes: Vec::new(),
+            }),
+            Provider::Azure => Box::new(AzureReporter { log }),
+        }
+    }
+}
+
+/// How a CI provider shows check results
+pub trait Reporter {
+    /// Message about the run as a whole
+    fn annotate(&mut self, level: Level, message: &str);
+    /// Problem at a location in the repository
+    fn annotate_finding(&mut self, finding: &Finding);
+    /// Every finding of the run, for providers that take a report file
+    fn findings(&mut self, _findings: &[Finding]) {}
+    /// Start a log section; `collapsed` hides it by default where supported
+    fn begin_section(&mut self, _title: &str, _collapsed: bool) {}
+    fn end_section(&mut self) {}
+    /// Values for later pipeline steps, e.g. ("has-coverage-issues", "true")
+    fn set_outputs(&mut self, outputs: &[(&str, String)]);
+    /// Markdown summary of the whole run
+    fn summary(&mut self, markdown: &str, failed: bool);
+}
+
+/// Where log lines go
+#[derive(Clone, Copy)]
+struct Log {
+    stderr: bool,
+}
+
+impl Log {
+    fn line(self, line: &str) {
+        if self.stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+fn append_lines(path: &str, lines: &[String]) {
+    if let Ok(mut file) = fs::OpenOptions::new().append(true).open(path) {
+        for line in lines {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// "path:line:col" for log output
+fn location(finding: &Finding) -> String {
+    match (finding.line, finding.column) {
+        (Some(line), Some(col)) => format!("{}:{}:{}", finding.path, line, col),
+        (Some(line), None) => format!("{}:{}", finding.path, line),
+        _ => finding.path.clone(),
+    }
+}
+
+/// Workflow commands, `GITHUB_OUTPUT` and `GITHUB_STEP_SUMMARY`
+struct GitHubReporter {
+    log: Log,
+}
+
+fn github_escape(message: &str) -> String {
+    message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn github_level(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note => "notice",
+    }
+}
+
+fn github_annotation(finding: &Finding) -> String {
+    let mut props = vec![format!("file={}", finding.path)];
+    if let Some(line) = finding.line {
+        props.push(format!("line={}", line));
+    }
+    if let Some(col) = finding.column {
+        props.push(format!("col={}", col));
+    }
+    props.push(format!("title={}", finding.rule_id));
+    format!(
+        "::{} {}::{}",
+        github_level(finding.level),
+        props.join(","),
+        github_escape(&finding.message)
+    )
+}
+
+impl Reporter for GitHubReporter {
+    fn annotate(&mut self, level: Level, message: &str) {
+        self.log.line(&format!(
+            "::{}::{}",
+            github_level(level),
+            github_escape(message)
+        ));
+    }
+
+    fn annotate_finding(&mut self, finding: &Finding) {
+        self.log.line(&github_annotation(finding));
+    }
+
+    fn set_outputs(&mut self, outputs: &[(&str, String)]) {
+        if let Ok(path) = env::var("GITHUB_OUTPUT") {
+            let lines: Vec<String> = outputs
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            append_lines(&path, &lines);
+        }
+    }
+
+    fn summary(&mut self, markdown: &str, _failed: bool) {
+        if let Ok(path) = env::var("GITHUB_STEP_SUMMARY") {
+            let _ = fs::write(path, markdown);
+        }
+    }
+}
+
+/// Code Quality artifact, collapsible log sections and a dotenv artifact
+struct GitLabReporter {
+    log: Log,
+    dir: PathBuf,
+    /// Open section ids, innermost last
+    sections: Vec<String>,
+    next_section: usize,
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn gitlab_section_start(id: &str, title: &str, collapsed: bool, time: u64) -> String {
+    let options = if collapsed { "[collapsed=true]" } else { "" };
+    format!("\x1b[0Ksection_start:{time}:{id}{options}\r\x1b[0K{title}")
+}
+
+fn gitlab_section_end(id: &str, time: u64) -> String {
+    format!("\x1b[0Ksection_end:{time}:{id}\r\x1b[0K")
+}
+
+/// `has-coverage-issues` -> `CODEOWNERS_HAS_COVERAGE_ISSUES`
+fn dotenv_name(name: &str) -> String {
+    format!("CODEOWNERS_{}", name.to_uppercase().replace('-', "_"))
+}
+
+impl Reporter for GitLabReporter {
+    fn annotate(&mut self, level: Level, message: &str) {
+        let label = match level {
+            Level::Error => "ERROR".red().bold(),
+            Level::Warning => "WARNING".yellow().bold(),
+            Level::Note => "NOTE".cyan().bold(),
+        };
+        self.log.line(&format!("{}: {}", label, message));
+    }
+
+    fn annotate_finding(&mut self, finding: &Finding) {
+        self.log.line(&format!(
+            "{}: {} [{}] {}",
+            location(finding),
+            finding.level.as_str(),
+            finding.rule_id,
+            finding.message
+        ));
+    }
+
+    fn findings(&mut self, findings: &[Finding]) {
+        let path = self.dir.join(GITLAB_CODE_QUALITY_FILE);
+        let report = report::render(ReportFormat::Gitlab, "ci", findings);
+        match fs::write(&path, report) {
+            Ok(_) => self.log.line(&format!(
+                "Wrote code quality report to {}",
+                GITLAB_CODE_QUALITY_FILE
+            )),
+            Err(e) => self.annotate(
+                Level::Warning,
+                &format!("Failed to write {}: {}", path.display(), e),
+            ),
+        }
+    }
+
+    fn begin_section(&mut self, title: &str, collapsed: bool) {
+        self.next_section += 1;
+        let id = format!("codeowners_{}", self.next_section);
+        self.log
+            .line(&gitlab_section_start(&id, title, collapsed, unix_time()));
+        self.sections.push(id);
+    }
+
+    fn end_section(&mut self) {
+        if let Some(id) = self.sections.pop() {
+            self.log.line(&gitlab_section_end(&id, unix_time()));
+        }
+    }
+
+    fn set_outputs(&mut self, outputs: &[(&str, String)]) {
+        let content: String = outputs
+            .iter()
+            .map(|(name, value)| format!("{}={}\n", dotenv_name(name), value))
+            .collect();
+        let _ = fs::write(self.dir.join(GITLAB_DOTENV_FILE), content);
+    }
+
+    fn summary(&mut self, _markdown: &str, _failed: bool) {
+        // GitLab has no job summary; the code quality report shows in the MR
+    }
+}
+
+/// Annotation file for `buildkite-agent annotate` and build meta-data
+struct BuildkiteReporter {
+    log: Log,
+    dir: PathBuf,
+    /// Annotations for the annotation file
+    notes: Vec<String>,
+}
+
+fn buildkite_annotation(markdown: &str, notes: &[String]) -> String {
+    let mut content = markdown.to_string();
+    if !notes.is_empty() {
+        content.push_str("\n<details><summary>Annotations</summary>\n\n");
+        for note in notes {
+            content.push_str(&format!("- {}\n", note));
+        }
+        content.push_str("\n</details>\n");
+    }
+    content
+}
+
+impl Reporter for BuildkiteReporter {
+    fn annotate(&mut self, level: Level, message: &str) {
+        self.log
+            .line(&format!("{}: {}", level_label(level), message));
+        self.notes
+            .push(format!("**{}**: {}", level_label(level), message));
+    }
+
+    fn annotate_finding(&mut self, finding: &Finding) {
+        self.log.line(&format!(
+            "{}: {} [{}] {}",
+            location(finding),
+            finding.level.as_str(),
+            finding.rule_id,
+            finding.message
+        ));
+        self.notes.push(format!(
+            "`{}` **{}** `{}`: {}",
+            location(finding),
+            finding.level.as_str(),
+            finding.rule_id,
+            finding.message
+        ));
+    }
+
+    fn begin_section(&mut self, title: &str, collapsed: bool) {
+        let marker = if collapsed { "---" } else { "+++" };
+        self.log.line(&format!("{} {}", marker, title));
+    }
+
+    fn set_outputs(&mut self, outputs: &[(&str, String)]) {
+        // Ignored outside an agent, where buildkite-agent isn't available
+        for (name, value) in outputs {
+            let _ = Command::new("buildkite-agent")
+                .args(["meta-data", "set", &format!("codeowners-{}", name), value])
+                .output();
+        }
+    }
+
+    fn summary(&mut self, markdown: &str, failed: bool) {
+        let path = self.dir.join(BUILDKITE_ANNOTATION_FILE);
+        let content = buildkite_annotation(markdown, &self.notes);
+        match fs::write(&path, content) {
+            Ok(_) => self.log.line(&format!(
+                "Wrote annotation to {} (buildkite-agent annotate --context codeowners --style {} < {})",
+                BUILDKITE_ANNOTATION_FILE,
+                if failed { "error" } else { "success" },
+                BUILDKITE_ANNOTATION_FILE
+            )),
+            Err(e) => self.annotate(
+                Level::Warning,
+                &format!("Failed to write {}: {}", path.display(), e),
+            ),
+        }
+    }
+}
+
+fn level_label(level: Level) -> &'static str {
+    match level {
+        Level::Error => "Error",
+        Level::Warning => "Warning",
+        Level::Note => "Note",
+    }
+}
+
+/// Azure Pipelines logging commands
+struct AzureReporter {
+    log: Log,
+}
+
+fn azure_escape(value: &str) -> String {
+    value
+        .replace('%', "%AZP25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn azure_property(value: &str) -> String {
+    azure_escape(value).replace(';', "%3B").replace(']', "%5D")
+}
+
+fn azure_issue(level: Level, message: &str, finding: Option<&Finding>) -> String {
+    let mut props = vec![format!(
+        "type={}",
+        if level == Level::Error {
+            "error"
+        } else {
+            "warning"
+        }
+    )];
+    if let Some(finding) = finding {
+        props.push(format!("sourcepath={}", azure_property(&finding.path)));
+        if let Some(line) = finding.line {
+            props.push(format!("linenumber={}", line));
+        }
+        if let Some(col) = finding.column {
+            props.push(format!("columnnumber={}", col));
+        }
+        props.push(format!("code={}", azure_property(&finding.rule_id)));
+    }
+    format!(
+        "##vso[task.logissue {}]{}",
+        props.join(";"),
+        azure_escape(message)
+    )
+}
+
+impl Reporter for AzureReporter {
+    fn annotate(&mut self, level: Level, message: &str) {
+        if level == Level::Note {
+            self.log.line(message);
+        } else {
+            self.log.line(&azure_issue(level, message, None));
+        }
+    }
+
+    fn annotate_finding(&mut self, finding: &Finding) {
+        if finding.level == Level::Note {
+            self.log
+                .line(&format!("{}: {}", location(finding), finding.message));
+        } else {
+            self.log
+                .line(&azure_issue(finding.level, &finding.message, Some(finding)));
+        }
+    }
+
+    fn begin_section(&mut self, title: &str, _collapsed: bool) {
+        self.log.line(&format!("##[group]{}", title));
+    }
+
+    fn end_section(&mut self) {
+        self.log.line("##[endgroup]");
+    }
+
+    fn set_outputs(&mut self, outputs: &[(&str, String)]) {
+        // Azure variable names allow letters, digits, '.' and '_'
+        for (name, value) in outputs {
+            self.log.line(&format!(
+                "##vso[task.setvariable variable={};isOutput=true]{}",
+                name.replace('-', "_"),
+                azure_escape(value)
+            ));
+        }
+    }
+
+    fn summary(&mut self, markdown: &str, _failed: bool) {
+        let dir = env::var("AGENT_TEMPDIRECTORY")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| env::temp_dir());
+        let path = dir.join("codeowners-summary.md");
+        match fs::write(&path, markdown) {
+            Ok(_) => self
+                .log
+                .line(&format!("##vso[task.uploadsummary]{}", path.display())),
+            Err(e) => self.annotate(
+                Level::Warning,
+                &format!("Failed to write {}: {}", path.display(), e),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn finding() -> Finding {
+        Finding {
+            line: Some(3),
+            column: Some(1),
+            ..Finding::file(
+                "shadowed-rule",
+                Level::Warning,
+                ".github/CODEOWNERS",
+                "Rule is shadowed\nby line 5; 100%".to_string(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_detect_provider() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            Provider::detect_from(|name| vars.get(name).cloned())
+        };
+        assert_eq!(
+            detect(&[("GITHUB_ACTIONS", "true")]),
+            Some(Provider::GitHub)
+        );
+        assert_eq!(detect(&[("GITLAB_CI", "true")]), Some(Provider::GitLab));
+        assert_eq!(detect(&[("BUILDKITE", "true")]), Some(Provider::Buildkite));
+        assert_eq!(detect(&[("TF_BUILD", "True")]), Some(Provider::Azure));
+        assert_eq!(detect(&[("GITLAB_CI", "false")]), None);
+        assert_eq!(detect(&[]), None);
+
+        assert_eq!("GitLab".parse(), Ok(Provider::GitLab));
+        assert!("jenkins".parse::<Provider>().is_err());
+    }
+
+    #[test]
+    fn test_github_annotation() {
+        assert_eq!(
+            github_annotation(&finding()),
+            "::warning file=.github/CODEOWNERS,line=3,col=1,title=shadowed-rule::Rule is shadowed%0Aby line 5; 100%25"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_sections_and_dotenv() {
+        assert_eq!(
+            gitlab_section_start("codeowners_1", "Lint", true, 42),
+            "\x1b[0Ksection_start:42:codeowners_1[collapsed=true]\r\x1b[0KLint"
+        );
+        assert_eq!(
+            gitlab_section_end("codeowners_1", 43),
+            "\x1b[0Ksection_end:43:codeowners_1\r\x1b[0K"
+        );
+        assert_eq!(
+            dotenv_name("has-coverage-issues"),
+            "CODEOWNERS_HAS_COVERAGE_ISSUES"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_writes_code_quality_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut reporter = GitLabReporter {
+            log: Log { stderr: true },
+            dir: dir.path().to_path_buf(),
+            sections: Vec::new(),
+            next_section: 0,
+        };
+        reporter.findings(&[finding()]);
+        reporter.set_outputs(&[("has-coverage-issues", "false".to_string())]);
+
+        let report = fs::read_to_string(dir.path().join(GITLAB_CODE_QUALITY_FILE)).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(report[0]["check_name"], "shadowed-rule");
+        let dotenv = fs::read_to_string(dir.path().join(GITLAB_DOTENV_FILE)).unwrap();
+        assert_eq!(dotenv, "CODEOWNERS_HAS_COVERAGE_ISSUES=false\n");
+    }
+
+    #[test]
+    fn test_buildkite_annotation_lists_notes() {
+        let content = buildkite_annotation("## Results\n", &["**Error**: bad".to_string()]);
+        assert!(content.starts_with("## Results\n"));
+        assert!(content.contains("<details><summary>Annotations</summary>"));
+        assert!(content.contains("- **Error**: bad\n"));
+        assert_eq!(buildkite_annotation("## Results\n", &[]), "## Results\n");
+    }
+
+    #[test]
+    fn test_azure_issue() {
+        assert_eq!(
+            azure_issue(Level::Warning, &finding().message, Some(&finding())),
+            "##vso[task.logissue type=warning;sourcepath=.github/CODEOWNERS;linenumber=3;columnnumber=1;code=shadowed-rule]Rule is shadowed%0Aby line 5; 100%AZP25"
+        );
+        assert_eq!(
+            azure_issue(Level::Error, "Changed files lack coverage", None),
+            "##vso[task.logissue type=error]Changed files lack coverage"
+        );
+    }
+}
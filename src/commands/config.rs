@@ -129,6 +129,9 @@ fn print_settings_brief(settings: &Settings) {
     if settings.github_token.is_some() {
         println!("           {} {}", "github_token:".dimmed(), "(set)".cyan());
     }
+    if let Some(ref github_url) = settings.github_url {
+        println!("           {} {}", "github_url:".dimmed(), github_url);
+    }
     if settings.validate_owners {
         println!("           {} true", "validate_owners:".dimmed());
     }
@@ -148,6 +151,38 @@ fn print_settings_brief(settings: &Settings) {
             println!("             {} true", "anchored:".dimmed());
         }
     }
+    let format = &settings.format;
+    if format.align_owners
+        || format.sort_owners
+        || format.comment_style.is_some()
+        || format.max_blank_lines.is_some()
+        || format.line_endings.is_some()
+    {
+        println!("           {} ", "[format]".dimmed());
+        if format.align_owners {
+            println!("             {} true", "align_owners:".dimmed());
+        }
+        if format.sort_owners {
+            println!("             {} true", "sort_owners:".dimmed());
+        }
+        if let Some(style) = format.comment_style {
+            println!(
+                "             {} {}",
+                "comment_style:".dimmed(),
+                style.as_str()
+            );
+        }
+        if let Some(max) = format.max_blank_lines {
+            println!("             {} {}", "max_blank_lines:".dimmed(), max);
+        }
+        if let Some(endings) = format.line_endings {
+            println!(
+                "             {} {}",
+                "line_endings:".dimmed(),
+                endings.as_str()
+            );
+        }
+    }
 }
 
 fn print_settings(settings: &Settings) {
@@ -185,6 +220,13 @@ fn print_settings(settings: &Settings) {
         None => println!("{}", "(not set)".dimmed()),
     }
 
+    // github_url
+    print!("  {:<18} ", "github_url:".cyan());
+    match &settings.github_url {
+        Some(url) => println!("{}", url.green()),
+        None => println!("{}", "(default)".dimmed()),
+    }
+
     // validate_owners
     print!("  {:<18} ", "validate_owners:".cyan());
     if settings.validate_owners {
@@ -227,4 +269,34 @@ fn print_settings(settings: &Settings) {
     } else {
         println!("{}", "false".dimmed());
     }
+
+    // format section
+    let format = settings.format_options();
+    println!("  {}", "[format]".cyan());
+    for (key, enabled) in [
+        ("align_owners:", format.align_owners),
+        ("sort_owners:", format.sort_owners),
+    ] {
+        print!("    {:<16} ", key.cyan());
+        if enabled {
+            println!("{}", "true".green());
+        } else {
+            println!("{}", "false".dimmed());
+        }
+    }
+    println!(
+        "    {:<16} {}",
+        "comment_style:".cyan(),
+        format.comment_style.as_str()
+    );
+    println!(
+        "    {:<16} {}",
+        "max_blank_lines:".cyan(),
+        format.max_blank_lines
+    );
+    println!(
+        "    {:<16} {}",
+        "line_endings:".cyan(),
+        format.line_endings.as_str()
+    );
 }
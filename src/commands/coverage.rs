@@ -1,18 +1,18 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::process::ExitCode;
-use std::{env, fs};
 
 use colored::Colorize;
 use serde::Serialize;
 
 use super::files::collect_files;
-use crate::file_cache::FileCache;
-use crate::ownership::{find_codeowners, get_repo_root};
+use super::source::Source;
+use crate::diagnostics::codes;
 use crate::parser;
+use crate::report::{self, Finding, Level, ReportFormat};
 
 #[derive(Serialize)]
-struct CoverageJson {
+pub(super) struct CoverageJson {
     total: usize,
     owned: usize,
     unowned: usize,
@@ -20,6 +20,28 @@ struct CoverageJson {
     unowned_files: Vec<String>,
 }
 
+impl CoverageJson {
+    pub(super) fn new(total: usize, unowned_files: Vec<String>) -> Self {
+        CoverageJson {
+            total,
+            owned: total.saturating_sub(unowned_files.len()),
+            unowned: unowned_files.len(),
+            coverage_percent: coverage_percent(total, unowned_files.len()),
+            unowned_files,
+        }
+    }
+}
+
+/// Percentage of `total` files that are owned, rounded to one decimal.
+/// Nothing to own counts as fully covered.
+pub(super) fn coverage_percent(total: usize, unowned: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    let owned = total.saturating_sub(unowned);
+    (owned as f64 / total as f64 * 1000.0).round() / 10.0
+}
+
 /// Generate a visual progress bar
 fn progress_bar(percentage: f64, width: usize) -> String {
     let filled = ((percentage / 100.0) * width as f64).round() as usize;
@@ -136,28 +158,19 @@ pub fn coverage(
     stdin: bool,
     tree: bool,
     json: bool,
+    format: Option<ReportFormat>,
+    rev: Option<String>,
 ) -> ExitCode {
-    let cwd = env::current_dir().expect("Failed to get current directory");
-
-    let codeowners_path = match find_codeowners(&cwd) {
-        Some(p) => p,
-        None => {
-            eprintln!("No CODEOWNERS file found");
-            return ExitCode::from(1);
-        }
-    };
-
-    let content = match fs::read_to_string(&codeowners_path) {
-        Ok(c) => c,
+    let source = match Source::load(rev.as_deref()) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            eprintln!("{}", e);
             return ExitCode::from(1);
         }
     };
 
-    let repo_root = get_repo_root(&codeowners_path, &cwd);
-    let file_cache = FileCache::new(&repo_root);
-    let lines = parser::parse_codeowners_file_with_positions(&content);
+    let file_cache = source.file_cache();
+    let lines = parser::parse_codeowners_file_with_positions(&source.content);
 
     // Collect files to check (if specified)
     let files_to_check = match collect_files(files, files_from, stdin) {
@@ -190,21 +203,28 @@ pub fn coverage(
         };
 
     let owned_count = total_files.saturating_sub(unowned.len());
-    let coverage_pct = if total_files > 0 {
-        (owned_count as f64 / total_files as f64) * 100.0
-    } else {
-        100.0
-    };
+    let coverage_pct = coverage_percent(total_files, unowned.len());
+
+    if let Some(format) = format {
+        let findings: Vec<Finding> = unowned
+            .iter()
+            .map(|file| {
+                let message = format!("{} has no CODEOWNERS owner", file);
+                Finding::file(codes::FILE_NOT_OWNED, Level::Error, file, message)
+            })
+            .collect();
+        println!("{}", report::render(format, "coverage", &findings));
+        return if unowned.is_empty() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::from(1)
+        };
+    }
 
     // JSON output
     if json {
-        let output = CoverageJson {
-            total: total_files,
-            owned: owned_count,
-            unowned: unowned.len(),
-            coverage_percent: (coverage_pct * 10.0).round() / 10.0, // 1 decimal place
-            unowned_files: unowned.iter().map(|s| s.to_string()).collect(),
-        };
+        let output =
+            CoverageJson::new(total_files, unowned.iter().map(|s| s.to_string()).collect());
         println!(
             "{}",
             serde_json::to_string(&output).expect("Failed to serialize JSON")
@@ -289,6 +309,13 @@ pub fn coverage(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_coverage_percent() {
+        assert_eq!(coverage_percent(3, 1), 66.7);
+        assert_eq!(coverage_percent(2, 2), 0.0);
+        assert_eq!(coverage_percent(0, 0), 100.0);
+    }
+
     #[test]
     fn test_tree_node_count() {
         let mut root = TreeNode::default();
@@ -0,0 +1,280 @@
+//! Diff command - which files change owners between two revisions.
+//!
+//! Both CODEOWNERS versions are evaluated against the files present in both
+//! revisions, so reviewers see the effect of a CODEOWNERS edit rather than
+//! the textual change. Files added or deleted in between have no owners on
+//! one side and are not reported as ownership changes.
+
+use std::collections::HashSet;
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::file_cache::FileCache;
+use crate::git::{repo_root, Snapshot};
+use crate::ownership::{ownership_impact, OwnerTransition};
+use crate::settings::load_settings_from_path;
+
+/// Output format for the diff report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Human,
+    Json,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!(
+                "Invalid format '{}' (expected human, json or markdown)",
+                s
+            )),
+        }
+    }
+}
+
+/// Options for the diff command
+pub struct DiffOptions {
+    pub base: String,
+    /// Head revision; None compares against the working tree
+    pub head: Option<String>,
+    pub format: OutputFormat,
+}
+
+/// Ownership changes between two revisions
+#[derive(Debug, Serialize)]
+pub struct OwnershipDiff {
+    pub base: String,
+    pub head: String,
+    /// Number of files whose owners change
+    pub changed: usize,
+    pub transitions: Vec<OwnerTransition>,
+}
+
+/// Compare effective owners between `base` and `head` (None = working tree)
+pub fn ownership_diff(
+    root: &Path,
+    base: &str,
+    head: Option<&str>,
+) -> Result<OwnershipDiff, String> {
+    let preferred = load_settings_from_path(root).path;
+    let old = Snapshot::at_revision(root, base, preferred.as_deref())?;
+    let new = Snapshot::load(root, head, preferred.as_deref())?;
+
+    let present: HashSet<&String> = new.files.iter().collect();
+    let files: Vec<String> = old
+        .files
+        .into_iter()
+        .filter(|file| present.contains(file))
+        .collect();
+
+    let impact = ownership_impact(
+        &old.codeowners,
+        &new.codeowners,
+        &FileCache::from_files(files),
+    );
+    Ok(OwnershipDiff {
+        base: old.label,
+        head: new.label,
+        changed: impact.changes.len(),
+        transitions: impact.transitions(),
+    })
+}
+
+pub fn diff(options: DiffOptions) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+    let result = repo_root(&cwd)
+        .and_then(|root| ownership_diff(&root, &options.base, options.head.as_deref()));
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    match options.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+        OutputFormat::Markdown => print!("{}", markdown_report(&report)),
+        OutputFormat::Human => print_human(&report),
+    }
+    ExitCode::SUCCESS
+}
+
+fn owner_set(owners: &[String]) -> String {
+    if owners.is_empty() {
+        "unowned".to_string()
+    } else {
+        owners.join(" ")
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        "file"
+    } else {
+        "files"
+    }
+}
+
+fn changes(count: usize) -> &'static str {
+    if count == 1 {
+        "file changes"
+    } else {
+        "files change"
+    }
+}
+
+fn print_human(report: &OwnershipDiff) {
+    if report.transitions.is_empty() {
+        println!(
+            "{} No files change owners between {} and {}",
+            "✓".green(),
+            report.base.cyan(),
+            report.head.cyan()
+        );
+        return;
+    }
+
+    println!(
+        "{} {} owners between {} and {}",
+        report.changed.to_string().yellow().bold(),
+        changes(report.changed),
+        report.base.cyan(),
+        report.head.cyan()
+    );
+    for transition in &report.transitions {
+        println!();
+        println!(
+            "  {} → {} {}",
+            owner_set(&transition.before).red(),
+            owner_set(&transition.after).green(),
+            format!(
+                "({} {})",
+                transition.files.len(),
+                plural(transition.files.len())
+            )
+            .dimmed()
+        );
+        for file in &transition.files {
+            println!("      {}", file);
+        }
+    }
+}
+
+/// Markdown report, also used for the `gha` step summary
+pub fn markdown_report(report: &OwnershipDiff) -> String {
+    let mut md = String::from("### CODEOWNERS ownership changes\n\n");
+    if report.transitions.is_empty() {
+        md.push_str(&format!(
+            "No files change owners between `{}` and `{}`.\n\n",
+            report.base, report.head
+        ));
+        return md;
+    }
+
+    md.push_str(&format!(
+        "{} {} owners between `{}` and `{}`.\n\n",
+        report.changed,
+        changes(report.changed),
+        report.base,
+        report.head
+    ));
+    md.push_str("| Before | After | Files |\n");
+    md.push_str("|--------|-------|-------|\n");
+    for transition in &report.transitions {
+        md.push_str(&format!(
+            "| {} | {} | {} |\n",
+            owner_set(&transition.before),
+            owner_set(&transition.after),
+            transition.files.len()
+        ));
+    }
+    md.push('\n');
+
+    for transition in &report.transitions {
+        md.push_str(&format!(
+            "<details><summary>{} → {} ({} {})</summary>\n\n",
+            owner_set(&transition.before),
+            owner_set(&transition.after),
+            transition.files.len(),
+            plural(transition.files.len())
+        ));
+        for file in &transition.files {
+            md.push_str(&format!("- `{}`\n", file));
+        }
+        md.push_str("\n</details>\n\n");
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_ownership_diff_against_working_tree() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("src/main.rs"), "").unwrap();
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::write(root.join("docs/a.md"), "").unwrap();
+        fs::write(root.join("CODEOWNERS"), "* @core\n/docs/ @docs\n").unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        fs::write(root.join("CODEOWNERS"), "/src/ @core @rust\n/docs/ @docs\n").unwrap();
+        // Added and deleted files only exist on one side
+        fs::write(root.join("new.txt"), "").unwrap();
+        git(root, &["add", "new.txt"]);
+        git(root, &["rm", "-q", "docs/a.md"]);
+
+        let report = ownership_diff(root, "HEAD", None).unwrap();
+        assert_eq!(report.base, "HEAD");
+        assert_eq!(report.head, "working tree");
+        assert_eq!(report.changed, 3);
+        assert_eq!(report.transitions.len(), 2);
+        assert_eq!(report.transitions[0].before, vec!["@core"]);
+        assert_eq!(report.transitions[0].after, vec!["@core", "@rust"]);
+        assert_eq!(
+            report.transitions[0].files,
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+        assert!(report.transitions[1].after.is_empty());
+        assert_eq!(report.transitions[1].files, vec!["CODEOWNERS"]);
+
+        let md = markdown_report(&report);
+        assert!(md.contains("3 files change owners between `HEAD` and `working tree`"));
+        assert!(md.contains("| @core | @core @rust | 2 |"));
+        assert!(md.contains("| @core | unowned | 1 |"));
+
+        assert!(ownership_diff(root, "missing", None).is_err());
+    }
+}
@@ -4,13 +4,29 @@ use std::{env, fs};
 
 use colored::Colorize;
 
-use crate::ownership::find_codeowners;
-use crate::parser::format_codeowners;
+use crate::file_cache::FileCache;
+use crate::formatter::{format_with_options, unified_diff, FormatOptions};
+use crate::ownership::{find_codeowners, get_repo_root};
+use crate::rule_sort::sort_rules;
+use crate::settings::load_settings_from_path;
 
-pub fn fmt(path: Option<PathBuf>, write: bool) -> ExitCode {
+/// Options for the fmt command. Formatter flags override `[format]` settings.
+pub struct FmtOptions {
+    pub path: Option<PathBuf>,
+    pub write: bool,
+    pub check: bool,
+    pub align_owners: bool,
+    pub sort_owners: bool,
+    pub sort_rules: bool,
+    pub comment_style: Option<String>,
+    pub max_blank_lines: Option<usize>,
+    pub line_endings: Option<String>,
+}
+
+pub fn fmt(options: FmtOptions) -> ExitCode {
     let cwd = env::current_dir().expect("Failed to get current directory");
 
-    let codeowners_path = path.unwrap_or_else(|| {
+    let codeowners_path = options.path.clone().unwrap_or_else(|| {
         find_codeowners(&cwd).unwrap_or_else(|| {
             eprintln!("No CODEOWNERS file found");
             std::process::exit(1);
@@ -30,57 +46,88 @@ pub fn fmt(path: Option<PathBuf>, write: bool) -> ExitCode {
         }
     };
 
-    let formatted = format_codeowners(&content);
+    let repo_root = get_repo_root(&codeowners_path, &cwd);
+    let settings = load_settings_from_path(&repo_root);
+    let format_options = match resolve_format_options(settings.format_options(), &options) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{} {}", "✗".red(), e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let sorted = if options.sort_rules {
+        let result = sort_rules(&content, &FileCache::new(&repo_root));
+        for block in &result.refused {
+            eprintln!(
+                "{} Kept lines {}-{} partly unsorted to preserve ownership:",
+                "!".yellow(),
+                block.start_line + 1,
+                block.end_line + 1
+            );
+            for reason in &block.reasons {
+                eprintln!("  {} {}", "-".yellow(), reason);
+            }
+        }
+        result.content
+    } else {
+        content.clone()
+    };
+
+    let formatted = format_with_options(&sorted, &format_options);
+    let display_path = codeowners_path.display().to_string();
 
     if formatted == content {
-        println!(
-            "{} {} is already formatted",
-            "✓".green(),
-            codeowners_path.display()
-        );
+        if !options.check {
+            println!("{} {} is already formatted", "✓".green(), display_path);
+        }
         return ExitCode::SUCCESS;
     }
 
-    if write {
+    if options.check {
+        // Plain unified diff so CI logs can be applied with `patch`
+        let label = codeowners_path
+            .strip_prefix(&cwd)
+            .unwrap_or(&codeowners_path)
+            .display()
+            .to_string();
+        print!(
+            "{}",
+            unified_diff(
+                &content,
+                &formatted,
+                &format!("a/{}", label),
+                &format!("b/{}", label)
+            )
+        );
+        eprintln!("{} {} is not formatted", "✗".red(), display_path);
+        return ExitCode::from(1);
+    }
+
+    if options.write {
         match fs::write(&codeowners_path, &formatted) {
             Ok(_) => {
-                println!("{} Formatted {}", "✓".green(), codeowners_path.display());
+                println!("{} Formatted {}", "✓".green(), display_path);
                 ExitCode::SUCCESS
             }
             Err(e) => {
-                eprintln!(
-                    "{} Failed to write {}: {}",
-                    "✗".red(),
-                    codeowners_path.display(),
-                    e
-                );
+                eprintln!("{} Failed to write {}: {}", "✗".red(), display_path, e);
                 ExitCode::from(1)
             }
         }
     } else {
-        println!(
-            "Would format {}:\n",
-            codeowners_path.display().to_string().bold()
-        );
-        println!("{}", "--- original".red());
-        println!("{}\n", "+++ formatted".green());
-
-        // Simple diff: show lines that differ
-        let old_lines: Vec<&str> = content.lines().collect();
-        let new_lines: Vec<&str> = formatted.lines().collect();
-
-        let max_lines = old_lines.len().max(new_lines.len());
-        for i in 0..max_lines {
-            let old = old_lines.get(i).copied().unwrap_or("");
-            let new = new_lines.get(i).copied().unwrap_or("");
-
-            if old != new {
-                if !old.is_empty() {
-                    println!("{}", format!("-{}", old).red());
-                }
-                if !new.is_empty() {
-                    println!("{}", format!("+{}", new).green());
-                }
+        println!("Would format {}:\n", display_path.bold());
+
+        let diff = unified_diff(&content, &formatted, "original", "formatted");
+        for line in diff.lines() {
+            if line.starts_with('-') {
+                println!("{}", line.red());
+            } else if line.starts_with('+') {
+                println!("{}", line.green());
+            } else if line.starts_with("@@") {
+                println!("{}", line.cyan());
+            } else {
+                println!("{}", line);
             }
         }
 
@@ -92,3 +139,21 @@ pub fn fmt(path: Option<PathBuf>, write: bool) -> ExitCode {
         ExitCode::from(1)
     }
 }
+
+fn resolve_format_options(
+    mut format: FormatOptions,
+    options: &FmtOptions,
+) -> Result<FormatOptions, String> {
+    format.align_owners |= options.align_owners;
+    format.sort_owners |= options.sort_owners;
+    if let Some(style) = &options.comment_style {
+        format.comment_style = style.parse()?;
+    }
+    if let Some(max) = options.max_blank_lines {
+        format.max_blank_lines = max;
+    }
+    if let Some(endings) = &options.line_endings {
+        format.line_endings = endings.parse()?;
+    }
+    Ok(format)
+}
@@ -1,7 +1,9 @@
 //! GitHub Actions integration command - runs all checks and outputs GHA-formatted results.
+//! The `ci` command runs the same checks through another provider's [`Reporter`].
 
 use std::collections::HashSet;
 
+use std::path::PathBuf;
 use std::process::ExitCode;
 use std::sync::Arc;
 use std::{env, fs};
@@ -11,12 +13,18 @@ use futures::stream::{self, StreamExt};
 use serde::Serialize;
 use tower_lsp::lsp_types::{DiagnosticSeverity, NumberOrString};
 
+use super::ci::{Provider, Reporter};
+use super::coverage::coverage_percent;
+use super::diff::{markdown_report, ownership_diff, OwnershipDiff};
+use crate::baseline::{self, Baseline, BaselineEntry};
 use crate::diagnostics;
 use crate::file_cache::FileCache;
 use crate::github::{GitHubClient, PersistentCache};
 use crate::ownership::{find_codeowners, get_repo_root};
 use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
 use crate::pattern::pattern_matches;
+use crate::report::{self, Finding, Level, ReportFormat};
+use crate::reviewers::{fetch_team_members, review_plan, ReviewPlan};
 use crate::settings::load_settings_from_path;
 
 const CONCURRENCY: usize = 5;
@@ -25,16 +33,29 @@ const CONCURRENCY: usize = 5;
 pub struct GhaOptions {
     pub token: String,
     pub changed_files: Option<Vec<String>>,
+    /// Base revision for the ownership diff when CODEOWNERS changed
+    /// (default: origin/$GITHUB_BASE_REF)
+    pub diff_base: Option<String>,
     pub check_coverage_changed: bool,
     pub check_coverage_all: bool,
     pub check_owners_changed: bool,
     pub check_owners_all: bool,
     pub check_lint: bool,
+    /// Lint fails only on diagnostics missing from this baseline
+    pub baseline: Option<PathBuf>,
+    /// Report required reviewers for the changed files
+    pub check_reviewers: bool,
+    /// Also suggest the fewest individual reviewers (expands teams)
+    pub expand_teams: bool,
     pub strict: bool, // Fail on warnings too (like lint --strict)
     // Output options
     pub output_annotations: bool,
     pub output_summary: bool,
     pub output_vars: bool,
+    /// Where annotations, outputs and the summary go
+    pub provider: Provider,
+    /// Print a report in this format on stdout instead of the human summary
+    pub format: Option<ReportFormat>,
 }
 
 /// Results from all checks
@@ -45,6 +66,8 @@ struct GhaResults {
     owners_changed: Option<OwnersResult>,
     owners_all: Option<OwnersResult>,
     lint: Option<LintResult>,
+    ownership_diff: Option<OwnershipDiff>,
+    reviewers: Option<ReviewPlan>,
 }
 
 #[derive(Serialize)]
@@ -72,7 +95,18 @@ struct InvalidOwner {
 #[derive(Serialize)]
 struct LintResult {
     file: String,
+    /// New diagnostics only when a baseline is used
     diagnostics: Vec<LintDiagnostic>,
+    /// Diagnostics accepted by the baseline
+    #[serde(skip_serializing_if = "is_zero")]
+    baselined: usize,
+    /// Baseline entries that no longer occur
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixed: Vec<BaselineEntry>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 #[derive(Serialize)]
@@ -86,15 +120,19 @@ struct LintDiagnostic {
     pattern: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     owners: Option<Vec<String>>,
+    /// Line-independent key for report fingerprints (see `baseline::key`)
+    #[serde(skip)]
+    key: String,
 }
 
 pub async fn gha(opts: GhaOptions) -> ExitCode {
     let cwd = env::current_dir().expect("Failed to get current directory");
+    let mut reporter = opts.provider.reporter(opts.format.is_some());
 
     let codeowners_path = match find_codeowners(&cwd) {
         Some(p) => p,
         None => {
-            eprintln!("::error::No CODEOWNERS file found");
+            reporter.annotate(Level::Error, "No CODEOWNERS file found");
             return ExitCode::from(1);
         }
     };
@@ -102,16 +140,16 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
     let content = match fs::read_to_string(&codeowners_path) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!(
-                "::error::Failed to read {}: {}",
-                codeowners_path.display(),
-                e
+            reporter.annotate(
+                Level::Error,
+                &format!("Failed to read {}: {}", codeowners_path.display(), e),
             );
             return ExitCode::from(1);
         }
     };
 
     let repo_root = get_repo_root(&codeowners_path, &cwd);
+    let codeowners_rel = report::repo_relative(&codeowners_path, &repo_root);
     let file_cache = FileCache::new(&repo_root);
     let lines = parse_codeowners_file_with_positions(&content);
 
@@ -138,15 +176,10 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
                     .collect();
 
                 let owned = changed.len() - unowned.len();
-                let pct = if changed.is_empty() {
-                    100.0
-                } else {
-                    (owned as f64 / changed.len() as f64) * 100.0
-                };
 
                 if !unowned.is_empty() {
                     if opts.output_annotations {
-                        eprintln!("::error::Changed files lack CODEOWNERS coverage");
+                        reporter.annotate(Level::Error, "Changed files lack CODEOWNERS coverage");
                     }
                     failed = true;
                 }
@@ -155,7 +188,7 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
                     total: changed.len(),
                     owned,
                     unowned: unowned.len(),
-                    coverage_percent: (pct * 10.0).round() / 10.0,
+                    coverage_percent: coverage_percent(changed.len(), unowned.len()),
                     unowned_files: unowned,
                 });
             }
@@ -164,16 +197,14 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
         // All files coverage (warning only)
         if opts.check_coverage_all {
             let owned = total_files - all_unowned.len();
-            let pct = if total_files == 0 {
-                100.0
-            } else {
-                (owned as f64 / total_files as f64) * 100.0
-            };
 
             if !all_unowned.is_empty() && opts.output_annotations {
-                eprintln!(
-                    "::warning::Some files lack CODEOWNERS coverage ({} unowned)",
-                    all_unowned.len()
+                reporter.annotate(
+                    Level::Warning,
+                    &format!(
+                        "Some files lack CODEOWNERS coverage ({} unowned)",
+                        all_unowned.len()
+                    ),
                 );
             }
 
@@ -181,7 +212,7 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
                 total: total_files,
                 owned,
                 unowned: all_unowned.len(),
-                coverage_percent: (pct * 10.0).round() / 10.0,
+                coverage_percent: coverage_percent(total_files, all_unowned.len()),
                 unowned_files: all_unowned,
             });
         }
@@ -254,13 +285,19 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
             let result = build_owners_result(&changed_owners);
             if !result.invalid.is_empty() {
                 if opts.output_annotations {
-                    eprintln!("::error::Invalid teams found in CODEOWNERS for changed files");
+                    reporter.annotate(
+                        Level::Error,
+                        "Invalid teams found in CODEOWNERS for changed files",
+                    );
                 }
                 failed = true;
             } else if !result.unknown.is_empty() && opts.output_annotations {
-                eprintln!(
-                    "::warning::Could not verify {} team(s) in CODEOWNERS for changed files - check token permissions",
-                    result.unknown.len()
+                reporter.annotate(
+                    Level::Warning,
+                    &format!(
+                        "Could not verify {} team(s) in CODEOWNERS for changed files - check token permissions",
+                        result.unknown.len()
+                    ),
                 );
             }
             results.owners_changed = Some(result);
@@ -270,14 +307,20 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
         if opts.check_owners_all {
             let result = build_owners_result(&all_owners);
             if !result.invalid.is_empty() && opts.output_annotations {
-                eprintln!(
-                    "::warning::Invalid teams found in CODEOWNERS ({} invalid)",
-                    result.invalid.len()
+                reporter.annotate(
+                    Level::Warning,
+                    &format!(
+                        "Invalid teams found in CODEOWNERS ({} invalid)",
+                        result.invalid.len()
+                    ),
                 );
             } else if !result.unknown.is_empty() && opts.output_annotations {
-                eprintln!(
-                    "::warning::Could not verify {} team(s) in CODEOWNERS - check token permissions",
-                    result.unknown.len()
+                reporter.annotate(
+                    Level::Warning,
+                    &format!(
+                        "Could not verify {} team(s) in CODEOWNERS - check token permissions",
+                        result.unknown.len()
+                    ),
                 );
             }
             results.owners_all = Some(result);
@@ -293,6 +336,23 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
 
         diagnostics.sort_by_key(|d| d.range.start.line);
 
+        let mut baselined = 0;
+        let mut fixed = Vec::new();
+        if let Some(ref path) = opts.baseline {
+            match Baseline::load(path) {
+                Ok(baseline) => {
+                    let comparison = baseline.compare(&content, diagnostics);
+                    diagnostics = comparison.new;
+                    baselined = comparison.known;
+                    fixed = comparison.fixed;
+                }
+                Err(e) => {
+                    reporter.annotate(Level::Error, &e);
+                    failed = true;
+                }
+            }
+        }
+
         // Parse content for pattern/owners info
         let line_data: std::collections::HashMap<u32, (&str, &[String])> = lines
             .iter()
@@ -305,30 +365,12 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
             })
             .collect();
 
-        // Output GHA annotations
-        if opts.output_annotations {
-            let file_path = codeowners_path.display();
+        if opts.output_annotations && !diagnostics.is_empty() {
+            reporter.begin_section("CODEOWNERS lint", true);
             for d in &diagnostics {
-                let level = match d.severity {
-                    Some(DiagnosticSeverity::ERROR) => "error",
-                    Some(DiagnosticSeverity::WARNING) => "warning",
-                    _ => "notice",
-                };
-                let line = d.range.start.line + 1;
-                let col = d.range.start.character + 1;
-                let title = d
-                    .code
-                    .as_ref()
-                    .map(|c| match c {
-                        NumberOrString::String(s) => s.clone(),
-                        NumberOrString::Number(n) => n.to_string(),
-                    })
-                    .unwrap_or_default();
-                let message = d.message.replace('\n', "%0A").replace('\r', "%0D");
-                println!(
-                    "::{level} file={file_path},line={line},col={col},title={title}::{message}"
-                );
+                reporter.annotate_finding(&Finding::from_diagnostic(&codeowners_rel, &content, d));
             }
+            reporter.end_section();
         }
 
         // Check if lint should cause failure (errors always, warnings if strict)
@@ -379,6 +421,7 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
                     } else {
                         Some(owners.to_vec())
                     },
+                    key: baseline::key(&content, d).1,
                 }
             })
             .collect();
@@ -386,11 +429,69 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
         results.lint = Some(LintResult {
             file: codeowners_path.display().to_string(),
             diagnostics: lint_diagnostics,
+            baselined,
+            fixed,
         });
     }
 
+    // === Required reviewers (informational) ===
+    if opts.check_reviewers {
+        if let Some(ref changed) = opts.changed_files {
+            let mut plan = review_plan(&content, changed);
+            if opts.expand_teams {
+                let (members, failed_teams) =
+                    fetch_team_members(&GitHubClient::new(), plan.teams(), &opts.token).await;
+                if opts.output_annotations {
+                    for failed in &failed_teams {
+                        reporter.annotate(
+                            Level::Warning,
+                            &format!("Could not expand {}: {}", failed.team, failed.reason),
+                        );
+                    }
+                }
+                plan.expand_teams(&members);
+            }
+            results.reviewers = Some(plan);
+        }
+    }
+
+    // === Ownership diff (when the PR edits CODEOWNERS) ===
+    let codeowners_changed = opts
+        .changed_files
+        .as_ref()
+        .is_some_and(|changed| changed.contains(&codeowners_rel));
+    if codeowners_changed {
+        let base = opts.diff_base.clone().or_else(|| {
+            opts.provider
+                .base_branch()
+                .map(|branch| format!("origin/{}", branch))
+        });
+        match base {
+            Some(base) => match ownership_diff(&repo_root, &base, None) {
+                Ok(diff) => results.ownership_diff = Some(diff),
+                Err(e) if opts.output_annotations => reporter.annotate(
+                    Level::Warning,
+                    &format!("Could not diff CODEOWNERS ownership: {}", e),
+                ),
+                Err(_) => {}
+            },
+            None if opts.output_annotations => reporter.annotate(
+                Level::Warning,
+                "CODEOWNERS changed but no base revision is known (set --diff-base)",
+            ),
+            None => {}
+        }
+    }
+
     // === Output results ===
-    output_results(&results, &opts, failed);
+    let findings = report_findings(&results, &codeowners_rel);
+    if let Some(format) = opts.format {
+        println!("{}", report::render(format, "gha", &findings));
+    }
+    if opts.output_annotations {
+        reporter.findings(&findings);
+    }
+    output_results(&results, &opts, reporter.as_mut(), failed);
 
     if failed {
         ExitCode::from(1)
@@ -399,73 +500,144 @@ pub async fn gha(opts: GhaOptions) -> ExitCode {
     }
 }
 
-fn output_results(results: &GhaResults, opts: &GhaOptions, failed: bool) {
-    // Human-readable terminal output
-    print_human_summary(results);
+fn output_results(
+    results: &GhaResults,
+    opts: &GhaOptions,
+    reporter: &mut dyn Reporter,
+    failed: bool,
+) {
+    // Human-readable terminal output (replaced by the report when --format is set)
+    if opts.format.is_none() {
+        print_human_summary(results);
+    }
 
-    // Write GitHub Actions outputs
     if opts.output_vars {
-        if let Ok(output_file) = env::var("GITHUB_OUTPUT") {
-            let mut outputs = Vec::new();
-
-            // Coverage changed
-            if let Some(ref cov) = results.coverage_changed {
-                outputs.push(format!(
-                    "has-coverage-issues={}",
-                    if cov.unowned > 0 { "true" } else { "false" }
-                ));
-                outputs.push(format!(
-                    "coverage-issues={}",
-                    serde_json::to_string(&cov.unowned_files).unwrap()
-                ));
-            }
+        reporter.set_outputs(&outputs(results));
+    }
 
-            // Lint
-            if let Some(ref lint) = results.lint {
-                outputs.push(format!(
-                    "has-dead-entries={}",
-                    if lint.diagnostics.is_empty() {
-                        "false"
-                    } else {
-                        "true"
-                    }
-                ));
-                outputs.push(format!(
-                    "dead-entries={}",
-                    serde_json::to_string(&lint.diagnostics).unwrap()
+    if opts.output_summary {
+        reporter.summary(&build_step_summary(results, failed), failed);
+    }
+}
+
+/// Output variables for later steps
+fn outputs(results: &GhaResults) -> Vec<(&'static str, String)> {
+    let flag = |set: bool| if set { "true" } else { "false" }.to_string();
+    let mut outputs = Vec::new();
+
+    // Coverage changed
+    if let Some(ref cov) = results.coverage_changed {
+        outputs.push(("has-coverage-issues", flag(cov.unowned > 0)));
+        outputs.push((
+            "coverage-issues",
+            serde_json::to_string(&cov.unowned_files).unwrap(),
+        ));
+    }
+
+    // Lint
+    if let Some(ref lint) = results.lint {
+        outputs.push(("has-dead-entries", flag(!lint.diagnostics.is_empty())));
+        outputs.push((
+            "dead-entries",
+            serde_json::to_string(&lint.diagnostics).unwrap(),
+        ));
+    }
+
+    // Invalid teams (changed)
+    if let Some(ref owners) = results.owners_changed {
+        let issues: Vec<_> = owners.invalid.iter().chain(owners.unknown.iter()).collect();
+        outputs.push(("has-invalid-teams", flag(!issues.is_empty())));
+        outputs.push(("invalid-teams", serde_json::to_string(&issues).unwrap()));
+    }
+
+    // Required reviewers
+    if let Some(ref plan) = results.reviewers {
+        outputs.push((
+            "required-reviewers",
+            serde_json::to_string(&plan.reviewers).unwrap(),
+        ));
+        if let Some(ref individuals) = plan.individuals {
+            outputs.push((
+                "required-individuals",
+                serde_json::to_string(individuals).unwrap(),
+            ));
+        }
+    }
+
+    outputs
+}
+
+/// Findings for `--format`: issues in changed files are errors, issues
+/// elsewhere are warnings, and lint keeps its own severities
+fn report_findings(results: &GhaResults, codeowners_path: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    let coverage = [
+        (&results.coverage_changed, Level::Error),
+        (&results.coverage_all, Level::Warning),
+    ];
+    let mut seen: HashSet<&str> = HashSet::new();
+    for (result, level) in coverage {
+        for file in result.iter().flat_map(|r| &r.unowned_files) {
+            if seen.insert(file) {
+                let message = format!("{} has no CODEOWNERS owner", file);
+                findings.push(Finding::file(
+                    diagnostics::codes::FILE_NOT_OWNED,
+                    level,
+                    file,
+                    message,
                 ));
             }
+        }
+    }
 
-            // Invalid teams (changed)
-            if let Some(ref owners) = results.owners_changed {
-                let has_issues = !owners.invalid.is_empty() || !owners.unknown.is_empty();
-                outputs.push(format!(
-                    "has-invalid-teams={}",
-                    if has_issues { "true" } else { "false" }
-                ));
-                let issues: Vec<_> = owners.invalid.iter().chain(owners.unknown.iter()).collect();
-                outputs.push(format!(
-                    "invalid-teams={}",
-                    serde_json::to_string(&issues).unwrap()
+    let owners = [
+        (&results.owners_changed, Level::Error),
+        (&results.owners_all, Level::Warning),
+    ];
+    let mut seen: HashSet<&str> = HashSet::new();
+    for (result, level) in owners {
+        let Some(result) = result else { continue };
+        for owner in &result.invalid {
+            if seen.insert(&owner.owner) {
+                findings.push(Finding::file(
+                    diagnostics::codes::GITHUB_OWNER_NOT_FOUND,
+                    level,
+                    codeowners_path,
+                    format!("Owner '{}' {}", owner.owner, owner.reason),
                 ));
             }
-
-            if let Ok(mut file) = fs::OpenOptions::new().append(true).open(&output_file) {
-                use std::io::Write;
-                for output in outputs {
-                    let _ = writeln!(file, "{}", output);
-                }
+        }
+        for owner in &result.unknown {
+            if seen.insert(&owner.owner) {
+                findings.push(Finding::file(
+                    diagnostics::codes::GITHUB_OWNER_UNVERIFIED,
+                    Level::Warning,
+                    codeowners_path,
+                    format!(
+                        "Owner '{}' could not be verified ({})",
+                        owner.owner, owner.reason
+                    ),
+                ));
             }
         }
     }
 
-    // Write GitHub Actions step summary
-    if opts.output_summary {
-        if let Ok(summary_file) = env::var("GITHUB_STEP_SUMMARY") {
-            let summary = build_step_summary(results, failed);
-            let _ = fs::write(&summary_file, summary);
-        }
+    for d in results.lint.iter().flat_map(|l| &l.diagnostics) {
+        let level = match d.severity.as_str() {
+            "error" => Level::Error,
+            "warning" => Level::Warning,
+            _ => Level::Note,
+        };
+        findings.push(Finding {
+            line: Some(d.line),
+            column: Some(d.column),
+            key: Some(d.key.clone()),
+            ..Finding::file(&d.code, level, codeowners_path, d.message.clone())
+        });
     }
+
+    findings
 }
 
 fn print_human_summary(results: &GhaResults) {
@@ -621,6 +793,37 @@ fn print_human_summary(results: &GhaResults) {
                 );
             }
         }
+        if lint.baselined > 0 {
+            println!(
+                "      {}",
+                format!("{} known issues in baseline", lint.baselined).dimmed()
+            );
+        }
+        if !lint.fixed.is_empty() {
+            println!(
+                "      {} {} baseline entries fixed (run lint --write-baseline to shrink it)",
+                "✓".green(),
+                lint.fixed.iter().map(|e| e.count).sum::<usize>()
+            );
+        }
+    }
+
+    // Required reviewers
+    if let Some(ref plan) = results.reviewers {
+        if !plan.reviewers.is_empty() {
+            println!(
+                "  {} Reviewers: {}",
+                "•".cyan(),
+                plan.reviewers.join(" ").cyan()
+            );
+        }
+        if let Some(ref individuals) = plan.individuals {
+            println!(
+                "  {} Individuals: {}",
+                "•".cyan(),
+                individuals.join(" ").cyan()
+            );
+        }
     }
 
     println!();
@@ -679,10 +882,26 @@ fn build_step_summary(results: &GhaResults, failed: bool) -> String {
                 ));
             }
             md.push('\n');
+        } else if lint.baselined > 0 {
+            md.push_str("### :white_check_mark: No New Lint Issues\n");
+            md.push_str(&format!(
+                "{} known issues are accepted by the baseline.\n\n",
+                lint.baselined
+            ));
         } else {
             md.push_str("### :white_check_mark: No Lint Issues\n");
             md.push_str("CODEOWNERS file is clean.\n\n");
         }
+        if !lint.fixed.is_empty() {
+            md.push_str("### :tada: Fixed Baseline Entries\n");
+            md.push_str(
+                "These baselined issues are gone; run `codeowners-cli lint --write-baseline` to shrink the baseline:\n\n",
+            );
+            for entry in &lint.fixed {
+                md.push_str(&format!("- `{}` `{}`\n", entry.code, entry.rule));
+            }
+            md.push('\n');
+        }
     }
 
     // Invalid teams (changed files)
@@ -723,6 +942,39 @@ fn build_step_summary(results: &GhaResults, failed: bool) -> String {
         }
     }
 
+    // Required reviewers (informational)
+    if let Some(ref plan) = results.reviewers {
+        if !plan.groups.is_empty() {
+            md.push_str("### :busts_in_silhouette: Required Reviewers\n");
+            md.push_str(&format!(
+                "Fewest reviewers that cover every changed file: {}\n\n",
+                code_list(&plan.reviewers)
+            ));
+            if let Some(ref individuals) = plan.individuals {
+                md.push_str(&format!(
+                    "Fewest individual reviewers: {}\n\n",
+                    code_list(individuals)
+                ));
+            }
+            md.push_str("<details><summary>Approvals by file</summary>\n\n");
+            md.push_str("| Any one of | Files |\n");
+            md.push_str("|------------|-------|\n");
+            for group in &plan.groups {
+                md.push_str(&format!(
+                    "| {} | {} |\n",
+                    code_list(&group.owners),
+                    code_list(&group.files)
+                ));
+            }
+            md.push_str("\n</details>\n\n");
+        }
+    }
+
+    // Ownership changes
+    if let Some(ref diff) = results.ownership_diff {
+        md.push_str(&markdown_report(diff));
+    }
+
     // Final status
     if failed {
         md.push_str("---\n\n:x: **Check failed**\n");
@@ -733,6 +985,15 @@ fn build_step_summary(results: &GhaResults, failed: bool) -> String {
     md
 }
 
+/// Inline-code list for markdown, e.g. "`@a`, `@b`"
+fn code_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("`{}`", item))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Classify a set of owners into valid/invalid/unknown based on cached GitHub info.
 fn classify_owners(owners: &HashSet<String>, client: &GitHubClient) -> OwnersResult {
     let mut valid = Vec::new();
@@ -981,4 +1242,103 @@ mod tests {
         );
         assert_eq!(result.unknown.len(), 1, "Unknown should be separate");
     }
+
+    #[test]
+    fn test_step_summary_lists_required_reviewers() {
+        let changed = vec!["src/main.rs".to_string(), "docs/a.md".to_string()];
+        let results = GhaResults {
+            reviewers: Some(review_plan("* @org/core\n/docs/ @docs\n", &changed)),
+            ..Default::default()
+        };
+        let md = build_step_summary(&results, false);
+        assert!(md.contains("### :busts_in_silhouette: Required Reviewers"));
+        assert!(md.contains("cover every changed file: `@docs`, `@org/core`"));
+        assert!(md.contains("| `@docs` | `docs/a.md` |"));
+    }
+
+    #[test]
+    fn test_report_findings() {
+        let unowned = |files: &[&str]| CoverageResult {
+            total: 10,
+            owned: 10 - files.len(),
+            unowned: files.len(),
+            coverage_percent: 0.0,
+            unowned_files: files.iter().map(|f| f.to_string()).collect(),
+        };
+        let results = GhaResults {
+            coverage_changed: Some(unowned(&["src/new.rs"])),
+            coverage_all: Some(unowned(&["src/new.rs", "old.txt"])),
+            owners_all: Some(OwnersResult {
+                valid: vec![],
+                invalid: vec![InvalidOwner {
+                    owner: "@ghost".to_string(),
+                    reason: "not found on GitHub".to_string(),
+                }],
+                unknown: vec![],
+            }),
+            lint: Some(LintResult {
+                file: "/repo/.github/CODEOWNERS".to_string(),
+                diagnostics: vec![LintDiagnostic {
+                    line: 4,
+                    column: 1,
+                    severity: "hint".to_string(),
+                    code: "pattern-no-match".to_string(),
+                    message: "Pattern matches no files".to_string(),
+                    pattern: Some("/gone/".to_string()),
+                    owners: None,
+                    key: String::new(),
+                }],
+                baselined: 0,
+                fixed: vec![],
+            }),
+            ..Default::default()
+        };
+
+        let findings = report_findings(&results, ".github/CODEOWNERS");
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|f| (f.rule_id.as_str(), f.level, f.path.as_str(), f.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("file-not-owned", Level::Error, "src/new.rs", None),
+                ("file-not-owned", Level::Warning, "old.txt", None),
+                (
+                    "github-owner-not-found",
+                    Level::Warning,
+                    ".github/CODEOWNERS",
+                    None
+                ),
+                (
+                    "pattern-no-match",
+                    Level::Note,
+                    ".github/CODEOWNERS",
+                    Some(4)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_summary_reports_baseline() {
+        let results = GhaResults {
+            lint: Some(LintResult {
+                file: "CODEOWNERS".to_string(),
+                diagnostics: vec![],
+                baselined: 3,
+                fixed: vec![BaselineEntry {
+                    code: "pattern-no-match".to_string(),
+                    fingerprint: "0123456789abcdef".to_string(),
+                    rule: "/gone/ @a".to_string(),
+                    count: 1,
+                }],
+            }),
+            ..Default::default()
+        };
+        let md = build_step_summary(&results, false);
+        assert!(md.contains("### :white_check_mark: No New Lint Issues"));
+        assert!(md.contains("3 known issues are accepted by the baseline."));
+        assert!(md.contains("- `pattern-no-match` `/gone/ @a`"));
+    }
 }
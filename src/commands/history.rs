@@ -0,0 +1,110 @@
+//! History command - who owned a path over time, from git history.
+
+use std::env;
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
+
+use colored::Colorize;
+
+use crate::git::repo_root;
+use crate::history::ownership_history;
+use crate::settings::load_settings_from_path;
+
+/// `path` as given on the command line (relative to `cwd`, or absolute),
+/// relative to the repository root. The path need not exist any more.
+fn repo_path(root: &Path, cwd: &Path, path: &str) -> Result<String, String> {
+    // Resolve `..` lexically, since deleted files have history too
+    let mut resolved = PathBuf::new();
+    for component in cwd.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved
+        .strip_prefix(root)
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| format!("{} is outside the repository", path))
+}
+
+pub fn history(path: String, json: bool) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+    // git reports the root with symlinks resolved
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let result = repo_root(&cwd).and_then(|root| {
+        let preferred = load_settings_from_path(&root).path;
+        let relative = repo_path(&root, &cwd, &path)?;
+        ownership_history(&root, &relative, preferred.as_deref()).map(|events| (relative, events))
+    });
+    let (path, events) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events).unwrap());
+        return ExitCode::SUCCESS;
+    }
+
+    if events.is_empty() {
+        println!("No CODEOWNERS history found");
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Ownership history of {}", path.bold());
+    for event in &events {
+        println!();
+        let owners = if event.owners.is_empty() {
+            "unowned".red().to_string()
+        } else {
+            event.owners.join(" ").green().to_string()
+        };
+        println!(
+            "  {}  {}  {}",
+            event.date.get(..10).unwrap_or(&event.date),
+            event.commit.get(..8).unwrap_or(&event.commit).yellow(),
+            owners
+        );
+        let rule = match (&event.rule, event.line) {
+            (Some(rule), Some(line)) => format!("{} (line {})", rule, line),
+            _ => "no matching rule".to_string(),
+        };
+        println!(
+            "      {} {}",
+            rule.cyan(),
+            format!("- {}: {}", event.author, event.subject).dimmed()
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_path_resolves_against_cwd() {
+        let root = Path::new("/repo");
+        let cwd = Path::new("/repo/src/api");
+        assert_eq!(repo_path(root, cwd, "mod.rs").unwrap(), "src/api/mod.rs");
+        assert_eq!(
+            repo_path(root, cwd, "./v1/../v2.rs").unwrap(),
+            "src/api/v2.rs"
+        );
+        assert_eq!(
+            repo_path(root, cwd, "../../README.md").unwrap(),
+            "README.md"
+        );
+        assert_eq!(
+            repo_path(root, cwd, "/repo/docs/a.md").unwrap(),
+            "docs/a.md"
+        );
+        assert!(repo_path(root, cwd, "../../../etc/passwd").is_err());
+    }
+}
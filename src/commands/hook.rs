@@ -0,0 +1,264 @@
+//! Git hooks - install hook scripts and check newly added files for owners
+
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::{env, fs};
+
+use colored::Colorize;
+
+use crate::file_cache;
+use crate::git;
+use crate::parser::{parse_codeowners_file_with_positions, ParsedLine};
+use crate::settings::load_settings_from_path;
+
+/// Marks hooks we wrote, so reinstalling may overwrite them
+const MARKER: &str = "# Installed by codeowners-cli";
+
+/// Placeholder owner when neither `individual` nor `team` is configured
+const PLACEHOLDER_OWNER: &str = "@owner";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookKind {
+    PreCommit,
+    PrePush,
+}
+
+impl FromStr for HookKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pre-commit" => Ok(HookKind::PreCommit),
+            "pre-push" => Ok(HookKind::PrePush),
+            _ => Err(format!(
+                "Unknown hook '{}' (expected pre-commit or pre-push)",
+                s
+            )),
+        }
+    }
+}
+
+impl HookKind {
+    fn name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+
+    fn script(self) -> String {
+        let body = match self {
+            HookKind::PreCommit => "exec codeowners-cli check-staged\n",
+            // New branches have no upstream yet; compare with the remote's
+            // default branch, or skip if there is none
+            HookKind::PrePush => concat!(
+                "base=$(git rev-parse --verify --quiet '@{upstream}') ||\n",
+                "  base=$(git rev-parse --verify --quiet origin/HEAD) ||\n",
+                "  exit 0\n",
+                "exec codeowners-cli check-staged --base \"$base\"\n",
+            ),
+        };
+        format!("#!/bin/sh\n{} ({})\n{}", MARKER, self.name(), body)
+    }
+}
+
+/// Write `kind` into `hooks_dir`, refusing to replace a hook we didn't write
+/// unless `force` is set
+fn write_hook(hooks_dir: &Path, kind: HookKind, force: bool) -> Result<(), String> {
+    let path = hooks_dir.join(kind.name());
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !force && !existing.contains(MARKER) {
+            return Err(format!(
+                "{} already exists (use --force to replace it)",
+                path.display()
+            ));
+        }
+    }
+
+    fs::create_dir_all(hooks_dir)
+        .map_err(|e| format!("Failed to create {}: {}", hooks_dir.display(), e))?;
+    fs::write(&path, kind.script())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+pub fn install_hook(kind: HookKind, force: bool) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+    let result = git::repo_root(&cwd).and_then(|root| {
+        let hooks_dir = git::hooks_dir(&root)?;
+        write_hook(&hooks_dir, kind, force)?;
+        Ok(hooks_dir.join(kind.name()))
+    });
+
+    match result {
+        Ok(path) => {
+            println!(
+                "{} Installed {} hook at {}",
+                "✓".green(),
+                kind.name(),
+                path.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// The files in `files` without a code owner, sorted, judged the same way
+/// as `coverage`
+fn unowned_files<'a>(lines: &[ParsedLine], files: &'a [String]) -> Vec<&'a str> {
+    let mut unowned: Vec<&str> = file_cache::unowned_files(lines, files)
+        .into_iter()
+        .map(|file| file.as_str())
+        .collect();
+    unowned.sort_unstable();
+    unowned
+}
+
+/// Rules that would give each unowned file an owner, as the editor's
+/// "Take ownership" action writes them
+fn ownership_lines(unowned: &[&str], owner: &str) -> Vec<String> {
+    unowned
+        .iter()
+        .map(|file| format!("/{} {}", file, owner))
+        .collect()
+}
+
+/// Fail when files added in the index (or since `base`) have no owner
+pub fn check_staged(base: Option<String>) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let root = match git::repo_root(&cwd) {
+        Ok(root) => root,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+    let added = match git::added_files(&root, base.as_deref()) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+    if added.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    // Judge what is being committed (the index) or pushed (HEAD), not
+    // unstaged edits in the working tree
+    let settings = load_settings_from_path(&root);
+    let codeowners = match base {
+        Some(_) => git::resolve_revision(&root, "HEAD")
+            .map(|head| git::codeowners_at(&root, &head, settings.path.as_deref())),
+        None => Ok(git::staged_codeowners(&root, settings.path.as_deref())),
+    };
+    let (relative, content) = match codeowners {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            eprintln!("No CODEOWNERS file found");
+            return ExitCode::from(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let lines = parse_codeowners_file_with_positions(&content);
+    let unowned = unowned_files(&lines, &added);
+
+    if unowned.is_empty() {
+        println!(
+            "{} {} new file{} owned",
+            "✓".green(),
+            added.len(),
+            if added.len() == 1 { "" } else { "s" }
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    let owner = settings.individual.or(settings.team);
+
+    println!(
+        "{} {} new file{} without a CODEOWNERS owner:",
+        "✗".red(),
+        unowned.len(),
+        if unowned.len() == 1 { "" } else { "s" }
+    );
+    println!();
+    for file in &unowned {
+        println!("    {} {}", "•".red(), file);
+    }
+    println!();
+    println!("  Add to {}:", relative);
+    println!();
+    for line in ownership_lines(&unowned, owner.as_deref().unwrap_or(PLACEHOLDER_OWNER)) {
+        println!("    {}", line.green());
+    }
+    if owner.is_none() {
+        println!();
+        println!(
+            "  {}",
+            "Set `individual` or `team` in .codeowners-lsp.toml to fill in the owner".dimmed()
+        );
+    }
+    println!();
+    ExitCode::from(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_hook_keeps_foreign_hooks() {
+        let dir = tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+
+        write_hook(&hooks, HookKind::PrePush, false).unwrap();
+        let script = fs::read_to_string(hooks.join("pre-push")).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n# Installed by codeowners-cli (pre-push)\n"));
+        assert!(script.contains("check-staged --base \"$base\""));
+        // Reinstalling our own hook is fine
+        write_hook(&hooks, HookKind::PrePush, false).unwrap();
+
+        fs::write(hooks.join("pre-commit"), "#!/bin/sh\nmake lint\n").unwrap();
+        let err = write_hook(&hooks, HookKind::PreCommit, false).unwrap_err();
+        assert!(err.contains("--force"));
+        write_hook(&hooks, HookKind::PreCommit, true).unwrap();
+        let script = fs::read_to_string(hooks.join("pre-commit")).unwrap();
+        assert!(script.ends_with("exec codeowners-cli check-staged\n"));
+
+        assert!("post-merge".parse::<HookKind>().is_err());
+    }
+
+    #[test]
+    fn test_ownership_lines_for_unowned_added_files() {
+        let lines = parse_codeowners_file_with_positions("/src/ @src\n/vendor/\n");
+        let added = vec![
+            "src/main.rs".to_string(),
+            "docs/new.md".to_string(),
+            "vendor/lib.c".to_string(),
+        ];
+
+        let unowned = unowned_files(&lines, &added);
+        assert_eq!(
+            ownership_lines(&unowned, "@alice"),
+            vec!["/docs/new.md @alice", "/vendor/lib.c @alice"]
+        );
+    }
+}
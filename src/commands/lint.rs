@@ -7,22 +7,42 @@ use colored::Colorize;
 use futures::stream::{self, StreamExt};
 use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 
+use crate::baseline::{Baseline, BaselineComparison, DEFAULT_BASELINE};
 use crate::diagnostics;
 use crate::file_cache::FileCache;
 use crate::github::{GitHubClient, PersistentCache};
 use crate::ownership::{apply_safe_fixes, find_codeowners, get_repo_root};
 use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
+use crate::report::{self, Finding, ReportFormat};
 use crate::settings::load_settings_from_path;
 
 const CONCURRENCY: usize = 5;
 
-pub async fn lint(
-    path: Option<PathBuf>,
-    json_output: bool,
-    fix: bool,
-    strict: bool,
-    github_actions: bool,
-) -> ExitCode {
+/// Options for the lint command
+pub struct LintOptions {
+    pub path: Option<PathBuf>,
+    pub json: bool,
+    pub fix: bool,
+    pub strict: bool,
+    pub github_actions: bool,
+    pub format: Option<ReportFormat>,
+    /// Only diagnostics missing from this baseline count
+    pub baseline: Option<PathBuf>,
+    /// Record the current diagnostics to the baseline (default `.codeowners-baseline.json`)
+    pub write_baseline: bool,
+}
+
+pub async fn lint(opts: LintOptions) -> ExitCode {
+    let LintOptions {
+        path,
+        json: json_output,
+        fix,
+        strict,
+        github_actions,
+        format,
+        baseline,
+        write_baseline,
+    } = opts;
     let cwd = env::current_dir().expect("Failed to get current directory");
 
     let codeowners_path = path.unwrap_or_else(|| {
@@ -102,7 +122,53 @@ pub async fn lint(
     // Sort diagnostics by line number
     diagnostics.sort_by_key(|d| d.range.start.line);
 
-    if github_actions {
+    if write_baseline {
+        let path = baseline.unwrap_or_else(|| PathBuf::from(DEFAULT_BASELINE));
+        let baseline = Baseline::from_diagnostics(&content, &diagnostics);
+        return match baseline.save(&path) {
+            Ok(()) => {
+                println!(
+                    "{} Wrote {} ({} diagnostics)",
+                    "✓".green(),
+                    path.display(),
+                    diagnostics.len().to_string().cyan()
+                );
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{} {}", "✗".red(), e);
+                ExitCode::from(1)
+            }
+        };
+    }
+
+    // With a baseline, only new diagnostics are reported and can fail
+    let comparison = match baseline {
+        Some(path) => match Baseline::load(&path) {
+            Ok(baseline) => {
+                let comparison = baseline.compare(&content, diagnostics);
+                diagnostics = comparison.new.clone();
+                Some(comparison)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(1);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(format) = format {
+        let path = report::repo_relative(&codeowners_path, &repo_root);
+        let findings: Vec<Finding> = diagnostics
+            .iter()
+            .map(|d| Finding::from_diagnostic(&path, &content, d))
+            .collect();
+        println!("{}", report::render(format, "lint", &findings));
+        if let Some(ref comparison) = comparison {
+            eprint!("{}", baseline_summary(comparison));
+        }
+    } else if github_actions {
         // GitHub Actions annotations only (no human output)
         let file_path = codeowners_path.display();
         for d in &diagnostics {
@@ -125,6 +191,12 @@ pub async fn lint(
             let message = d.message.replace('\n', "%0A").replace('\r', "%0D");
             println!("::{level} file={file_path},line={line},col={col},title={title}::{message}");
         }
+        if let Some(fixed) = comparison.as_ref().filter(|c| !c.fixed.is_empty()) {
+            println!(
+                "::notice::{} baseline entries are fixed; run lint --write-baseline to shrink the baseline",
+                fixed.fixed.iter().map(|e| e.count).sum::<usize>()
+            );
+        }
     } else if json_output {
         // Parse content to get pattern/owners for each line
         let parsed_lines = parse_codeowners_file_with_positions(&content);
@@ -139,7 +211,7 @@ pub async fn lint(
             })
             .collect();
 
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "file": codeowners_path.display().to_string(),
             "diagnostics": diagnostics.iter().map(|d| {
                 let line_num = d.range.start.line;
@@ -168,14 +240,24 @@ pub async fn lint(
                 obj
             }).collect::<Vec<_>>(),
         });
+        if let Some(ref comparison) = comparison {
+            json["baseline"] = serde_json::json!({
+                "known": comparison.known,
+                "fixed": comparison.fixed,
+            });
+        }
         println!("{}", serde_json::to_string_pretty(&json).unwrap());
     } else {
         if diagnostics.is_empty() {
-            println!(
-                "{} {} - no issues found",
-                "✓".green(),
-                codeowners_path.display()
-            );
+            let what = if comparison.is_some() {
+                "no new issues found"
+            } else {
+                "no issues found"
+            };
+            println!("{} {} - {}", "✓".green(), codeowners_path.display(), what);
+            if let Some(ref comparison) = comparison {
+                print!("{}", baseline_summary(comparison));
+            }
             return ExitCode::SUCCESS;
         }
 
@@ -204,6 +286,9 @@ pub async fn lint(
                 d.message
             );
         }
+        if let Some(ref comparison) = comparison {
+            print!("{}", baseline_summary(comparison));
+        }
     }
 
     if should_fail(&diagnostics, strict) {
@@ -311,7 +396,9 @@ fn owner_diagnostics_from_cache(
                     },
                 },
                 severity: Some(DiagnosticSeverity::WARNING),
-                code: Some(NumberOrString::String("github-owner-not-found".to_string())),
+                code: Some(NumberOrString::String(
+                    diagnostics::codes::GITHUB_OWNER_NOT_FOUND.to_string(),
+                )),
                 source: Some("codeowners".to_string()),
                 message: format!("Owner '{}' not found on GitHub", owner),
                 ..Default::default()
@@ -322,6 +409,38 @@ fn owner_diagnostics_from_cache(
     diagnostics
 }
 
+/// Known and fixed baseline entries, for human output
+fn baseline_summary(comparison: &BaselineComparison) -> String {
+    let mut summary = String::new();
+    if comparison.known > 0 {
+        summary.push_str(&format!(
+            "  {}\n",
+            format!("{} known issues in baseline", comparison.known).dimmed()
+        ));
+    }
+    if !comparison.fixed.is_empty() {
+        summary.push_str(&format!(
+            "  {} Fixed since the baseline (run lint --write-baseline to shrink it):\n",
+            "✓".green()
+        ));
+        for entry in &comparison.fixed {
+            let count = if entry.count > 1 {
+                format!(" (x{})", entry.count)
+            } else {
+                String::new()
+            };
+            summary.push_str(&format!(
+                "    {} {} {}{}\n",
+                "-".green(),
+                entry.code.bold(),
+                entry.rule,
+                count
+            ));
+        }
+    }
+    summary
+}
+
 /// Determine exit code based on diagnostics and strict mode
 fn should_fail(diagnostics: &[Diagnostic], strict: bool) -> bool {
     let has_errors = diagnostics
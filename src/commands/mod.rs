@@ -1,22 +1,43 @@
+mod approvals;
 mod check;
+mod ci;
 mod config;
 mod coverage;
+mod diff;
 pub mod files;
 mod fmt;
 mod gha;
+mod history;
+mod hook;
 mod lint;
 mod optimize;
+mod query;
+mod reviewers;
+mod serve;
+mod source;
+mod stats;
 mod suggest;
 mod tree;
 mod validate_owners;
+mod watch;
 
+pub use approvals::{approvals, ApprovalsOptions};
 pub use check::check;
+pub use ci::Provider as CiProvider;
 pub use config::config;
 pub use coverage::coverage;
-pub use fmt::fmt;
+pub use diff::{diff, DiffOptions, OutputFormat as DiffFormat};
+pub use fmt::{fmt, FmtOptions};
 pub use gha::{gha, GhaOptions};
-pub use lint::lint;
+pub use history::history;
+pub use hook::{check_staged, install_hook, HookKind};
+pub use lint::{lint, LintOptions};
 pub use optimize::{optimize, OptimizeOptions, OutputFormat as OptimizeFormat};
+pub use query::{query, OutputFormat as QueryFormat, QueryOptions};
+pub use reviewers::{reviewers, ReviewersOptions};
+pub use serve::{serve, ServeOptions};
+pub use stats::{stats, OutputFormat as StatsFormat};
 pub use suggest::{suggest, OutputFormat as SuggestFormat, SuggestOptions};
 pub use tree::tree;
 pub use validate_owners::validate_owners;
+pub use watch::{watch, WatchReport};
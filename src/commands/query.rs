@@ -0,0 +1,96 @@
+//! Query command - list files matching an ownership query
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use super::source::Source;
+use crate::parser::parse_codeowners_file_with_positions;
+use crate::query::{file_ownership, FileOwnership, Query};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// One path per line
+    Lines,
+    /// NUL-separated paths, for `xargs -0`
+    Null,
+    Json,
+}
+
+pub struct QueryOptions {
+    pub query: String,
+    pub format: OutputFormat,
+    pub rev: Option<String>,
+}
+
+fn render(files: &[&FileOwnership], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Lines => files.iter().map(|f| format!("{}\n", f.path)).collect(),
+        OutputFormat::Null => files.iter().map(|f| format!("{}\0", f.path)).collect(),
+        OutputFormat::Json => {
+            serde_json::to_string(files).expect("Failed to serialize JSON") + "\n"
+        }
+    }
+}
+
+/// Print matching files; exits 1 when nothing matches, like grep
+pub fn query(opts: QueryOptions) -> ExitCode {
+    let query = match Query::parse(&opts.query) {
+        Ok(query) => query,
+        Err(e) => {
+            eprintln!("Invalid query: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let source = match Source::load(opts.rev.as_deref()) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let rules = parse_codeowners_file_with_positions(&source.content);
+    let files = file_ownership(&rules, &source.file_cache());
+    let matched: Vec<&FileOwnership> = files.iter().filter(|f| query.matches(f)).collect();
+
+    let mut stdout = io::stdout().lock();
+    if stdout
+        .write_all(render(&matched, opts.format).as_bytes())
+        .is_err()
+    {
+        // Closed pipe (e.g. `| head`)
+        return ExitCode::SUCCESS;
+    }
+
+    if matched.is_empty() {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_formats() {
+        let file = |path: &str| FileOwnership {
+            path: path.to_string(),
+            rule: Some("*".to_string()),
+            line: Some(1),
+            owners: vec!["@org/core".to_string()],
+        };
+        let (a, b) = (file("a b.rs"), file("c.rs"));
+        let files = vec![&a, &b];
+
+        assert_eq!(render(&files, OutputFormat::Lines), "a b.rs\nc.rs\n");
+        assert_eq!(render(&files, OutputFormat::Null), "a b.rs\0c.rs\0");
+        assert_eq!(
+            render(&files[..1], OutputFormat::Json),
+            "[{\"path\":\"a b.rs\",\"rule\":\"*\",\"line\":1,\"owners\":[\"@org/core\"]}]\n"
+        );
+        assert_eq!(render(&[], OutputFormat::Json), "[]\n");
+    }
+}
@@ -0,0 +1,125 @@
+//! Reviewers command - which approvals a change needs, and the fewest
+//! reviewers that satisfy them.
+
+use std::process::ExitCode;
+use std::{env, fs};
+
+use colored::Colorize;
+
+use crate::github::GitHubClient;
+use crate::ownership::find_codeowners;
+use crate::reviewers::{fetch_team_members, review_plan, ReviewPlan, UnexpandedTeam};
+
+/// Options for the reviewers command
+pub struct ReviewersOptions {
+    pub files: Vec<String>,
+    pub json: bool,
+    /// Expand teams to members and suggest the fewest individual reviewers
+    pub expand_teams: bool,
+    pub token: Option<String>,
+}
+
+pub async fn reviewers(opts: ReviewersOptions) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let codeowners_path = match find_codeowners(&cwd) {
+        Some(p) => p,
+        None => {
+            eprintln!("No CODEOWNERS file found");
+            return ExitCode::from(1);
+        }
+    };
+
+    let content = match fs::read_to_string(&codeowners_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let mut plan = review_plan(&content, &opts.files);
+
+    let mut failed_teams = Vec::new();
+    if opts.expand_teams {
+        let Some(token) = opts.token.as_deref() else {
+            eprintln!("--expand-teams needs a GitHub token (--token or GITHUB_TOKEN)");
+            return ExitCode::from(2);
+        };
+        let (members, failed) = fetch_team_members(&GitHubClient::new(), plan.teams(), token).await;
+        plan.expand_teams(&members);
+        failed_teams = failed;
+    }
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&plan).unwrap());
+    } else {
+        print_human(&plan, &failed_teams);
+    }
+    ExitCode::SUCCESS
+}
+
+fn print_human(plan: &ReviewPlan, failed_teams: &[UnexpandedTeam]) {
+    println!(
+        "{} {} need approval",
+        plan.files.len().to_string().bold(),
+        if plan.files.len() == 1 {
+            "file"
+        } else {
+            "files"
+        }
+    );
+    for group in &plan.groups {
+        println!();
+        let label = if group.owners.len() > 1 {
+            format!("{} (any one)", group.owners.join(" "))
+        } else {
+            group.owners.join(" ")
+        };
+        println!("  {}", label.cyan());
+        for file in &group.files {
+            println!("      {}", file);
+        }
+    }
+
+    if !plan.unowned.is_empty() {
+        println!();
+        println!(
+            "  {} {} {} without owners (no approval required)",
+            "⚠".yellow(),
+            plan.unowned.len(),
+            if plan.unowned.len() == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        );
+        for file in &plan.unowned {
+            println!("      {}", file.dimmed());
+        }
+    }
+
+    if !plan.reviewers.is_empty() {
+        println!();
+        println!(
+            "{} {}",
+            "Fewest reviewers:".bold(),
+            plan.reviewers.join(" ").green()
+        );
+    }
+    if let Some(ref individuals) = plan.individuals {
+        println!(
+            "{} {}",
+            "Fewest individuals:".bold(),
+            individuals.join(" ").green()
+        );
+    }
+    for failed in failed_teams {
+        eprintln!(
+            "{} Could not expand {}: {}",
+            "⚠".yellow(),
+            failed.team.cyan(),
+            failed.reason
+        );
+    }
+}
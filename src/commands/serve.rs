@@ -0,0 +1,570 @@
+//! Serve command - answer ownership queries over local HTTP
+//!
+//! Keeps the file cache and parsed rules in memory, reloading them when
+//! CODEOWNERS or the file tree changes. Each connection carries one request
+//! and gets a JSON response.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::{env, thread};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::TcpListener;
+
+use super::check::CheckResultJson;
+use super::coverage::CoverageJson;
+use super::watch::{read_codeowners, Changes, State};
+use crate::file_cache::FileCache;
+use crate::parser::ParsedLine;
+
+/// Largest request body accepted by `POST /owners`
+const MAX_BODY: usize = 8 * 1024 * 1024;
+
+/// Longest request line or header line accepted
+const MAX_LINE: usize = 8 * 1024;
+
+/// Most headers accepted in one request
+const MAX_HEADERS: usize = 100;
+
+/// Default TCP port when neither `--port` nor `--socket` is given
+const DEFAULT_PORT: u16 = 7878;
+
+/// How long a connection may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ServeOptions {
+    /// TCP port on 127.0.0.1
+    pub port: Option<u16>,
+    /// Unix socket path
+    pub socket: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// A parsed HTTP request
+struct Request {
+    method: String,
+    target: String,
+    body: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct BatchRequest {
+    paths: Vec<String>,
+}
+
+/// The loaded state, with the unowned files and coverage computed once per
+/// reload instead of on every request
+struct Served {
+    state: State,
+    unowned: Value,
+    coverage: Value,
+}
+
+impl Served {
+    fn new(state: State) -> Self {
+        let (unowned, coverage) = summarize(&state.cache, &state.parsed);
+        Served {
+            state,
+            unowned,
+            coverage,
+        }
+    }
+}
+
+/// Unowned files and coverage as served by `/unowned` and `/coverage`
+fn summarize(cache: &FileCache, parsed: &[ParsedLine]) -> (Value, Value) {
+    let unowned: Vec<String> = cache
+        .get_unowned_files(parsed)
+        .into_iter()
+        .cloned()
+        .collect();
+    let coverage = json!(CoverageJson::new(cache.all_files().len(), unowned.clone()));
+    (json!(unowned), coverage)
+}
+
+/// Answer one request against the current state
+fn route(served: &Served, method: &str, target: &str, body: &[u8]) -> Response {
+    let state = &served.state;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let file = || {
+        params
+            .get("path")
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| Response::error(400, "missing ?path="))
+    };
+
+    match (method, path) {
+        ("GET", "/owners") => match file() {
+            Ok(file) => Response::ok(json!(CheckResultJson::new(&state.parsed, file, false))),
+            Err(response) => response,
+        },
+        ("POST", "/owners") => match serde_json::from_slice::<BatchRequest>(body) {
+            Ok(batch) => {
+                let results: HashMap<&str, CheckResultJson> = batch
+                    .paths
+                    .iter()
+                    .map(|file| {
+                        (
+                            file.as_str(),
+                            CheckResultJson::new(&state.parsed, file, false),
+                        )
+                    })
+                    .collect();
+                Response::ok(json!(results))
+            }
+            Err(e) => Response::error(400, format!("expected {{\"paths\": [...]}}: {}", e)),
+        },
+        ("GET", "/explain") => match file() {
+            Ok(file) => Response::ok(json!(CheckResultJson::new(&state.parsed, file, true))),
+            Err(response) => response,
+        },
+        ("GET", "/coverage") => Response::ok(served.coverage.clone()),
+        ("GET", "/unowned") => Response::ok(served.unowned.clone()),
+        (_, "/owners" | "/explain" | "/coverage" | "/unowned") => {
+            Response::error(405, format!("{} not allowed on {}", method, path))
+        }
+        _ => Response::error(404, format!("no route for {}", path)),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Read one line of at most `MAX_LINE` bytes into `line`. Returns false when
+/// the line is longer.
+async fn read_limited_line<R>(reader: &mut R, line: &mut String) -> std::io::Result<bool>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let read = reader.take(MAX_LINE as u64 + 1).read_line(line).await?;
+    Ok(read <= MAX_LINE)
+}
+
+/// Read one HTTP/1.1 request from `stream`, route it and write the response.
+/// Clients that don't send a full request within `timeout` are answered 408.
+async fn handle<S>(stream: S, state: Arc<RwLock<Served>>, timeout: Duration) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let request = match tokio::time::timeout(timeout, read_request(&mut reader)).await {
+        Ok(request) => request?,
+        Err(_) => Some(Err(Response::error(408, "timed out reading the request"))),
+    };
+    let response = match request {
+        Some(Ok(request)) => {
+            let served = state.read().unwrap();
+            route(&served, &request.method, &request.target, &request.body)
+        }
+        Some(Err(response)) => response,
+        None => return Ok(()),
+    };
+
+    let body = response.body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        body.len()
+    );
+    let stream = reader.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read the request line, headers and body. None when the connection closed
+/// before sending anything; an error response when the request is too large.
+async fn read_request<R>(reader: &mut R) -> std::io::Result<Option<Result<Request, Response>>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut request_line = String::new();
+    if !read_limited_line(reader, &mut request_line).await? {
+        return Ok(Some(Err(Response::error(
+            414,
+            format!("request line longer than {} bytes", MAX_LINE),
+        ))));
+    }
+    if request_line.is_empty() {
+        return Ok(None);
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or("").to_string(),
+        parts.next().unwrap_or("").to_string(),
+    );
+
+    let mut content_length = 0;
+    let mut headers = 0;
+    loop {
+        let mut header = String::new();
+        if !read_limited_line(reader, &mut header).await? {
+            return Ok(Some(Err(Response::error(
+                431,
+                format!("header longer than {} bytes", MAX_LINE),
+            ))));
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Ok(Some(Err(Response::error(
+                431,
+                format!("more than {} headers", MAX_HEADERS),
+            ))));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    if content_length > MAX_BODY {
+        return Ok(Some(Err(Response::error(
+            413,
+            format!("body larger than {} bytes", MAX_BODY),
+        ))));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(Ok(Request {
+        method,
+        target,
+        body,
+    })))
+}
+
+async fn serve_tcp(listener: TcpListener, state: Arc<RwLock<Served>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle(stream, state.clone(), READ_TIMEOUT));
+            }
+            Err(e) => eprintln!("Failed to accept connection: {}", e),
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn serve_unix(listener: tokio::net::UnixListener, state: Arc<RwLock<Served>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle(stream, state.clone(), READ_TIMEOUT));
+            }
+            Err(e) => eprintln!("Failed to accept connection: {}", e),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn bind_unix(path: &std::path::Path) -> Result<tokio::net::UnixListener, String> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    // A socket left behind by a previous run would make bind fail; one that
+    // still accepts connections belongs to a running server
+    if std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        if UnixStream::connect(path).is_ok() {
+            return Err(format!("{} is in use by another server", path.display()));
+        }
+        let _ = std::fs::remove_file(path);
+    }
+    tokio::net::UnixListener::bind(path)
+        .map_err(|e| format!("Failed to listen on {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn bind_unix(_path: &std::path::Path) -> Result<(), String> {
+    Err("--socket is only supported on Unix".to_string())
+}
+
+pub async fn serve(opts: ServeOptions) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let state = match State::load(&cwd) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+    let changes = match Changes::watch(&state.root) {
+        Ok(changes) => changes,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+    let state = Arc::new(RwLock::new(Served::new(state)));
+
+    let reloading = state.clone();
+    thread::spawn(move || {
+        while let Some(touched) = changes.next() {
+            // Build the new cache, rules and answers before taking the write
+            // lock, so queries are answered from the old state meanwhile
+            let root = reloading.read().unwrap().state.root.clone();
+            let cache = touched.tree.then(|| FileCache::new(&root));
+            let (content, parsed) = read_codeowners(&root);
+            let (unowned, coverage) = {
+                let served = reloading.read().unwrap();
+                summarize(cache.as_ref().unwrap_or(&served.state.cache), &parsed)
+            };
+
+            let mut served = reloading.write().unwrap();
+            if let Some(cache) = cache {
+                served.state.cache = cache;
+            }
+            served.state.content = content;
+            served.state.parsed = parsed;
+            served.unowned = unowned;
+            served.coverage = coverage;
+            drop(served);
+            eprintln!(
+                "Reloaded ({})",
+                if touched.tree {
+                    "files changed"
+                } else {
+                    "CODEOWNERS changed"
+                }
+            );
+        }
+    });
+
+    let port = match (opts.port, &opts.socket) {
+        (None, None) => Some(DEFAULT_PORT),
+        (port, _) => port,
+    };
+
+    let tcp = match port {
+        Some(port) => {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+            match TcpListener::bind(addr).await {
+                Ok(listener) => {
+                    eprintln!("Serving ownership queries on http://{}", addr);
+                    Some(listener)
+                }
+                Err(e) => {
+                    eprintln!("Failed to listen on {}: {}", addr, e);
+                    return ExitCode::from(1);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let unix = match &opts.socket {
+        Some(path) => match bind_unix(path) {
+            Ok(listener) => {
+                eprintln!("Serving ownership queries on unix:{}", path.display());
+                Some(listener)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(1);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(listener) = tcp {
+        tokio::spawn(serve_tcp(listener, state.clone()));
+    }
+    #[cfg(unix)]
+    if let Some(listener) = unix {
+        tokio::spawn(serve_unix(listener, state.clone()));
+    }
+    #[cfg(not(unix))]
+    let _ = unix;
+
+    let _ = tokio::signal::ctrl_c().await;
+    if let Some(path) = &opts.socket {
+        let _ = std::fs::remove_file(path);
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_codeowners_file_with_positions;
+
+    fn state() -> Served {
+        let content = "*.md @docs\n/src/ @src\n/src/*.rs @rust\n";
+        Served::new(State {
+            root: PathBuf::from("/repo"),
+            content: content.to_string(),
+            parsed: parse_codeowners_file_with_positions(content),
+            cache: FileCache::from_files(vec![
+                "README.md".to_string(),
+                "src/main.rs".to_string(),
+                "notes/a b.txt".to_string(),
+            ]),
+        })
+    }
+
+    #[test]
+    fn test_route_owners_and_explain() {
+        let state = state();
+
+        let response = route(&state, "GET", "/owners?path=src%2Fmain.rs", b"");
+        assert_eq!(
+            response,
+            Response::ok(json!({
+                "owned": true,
+                "rule": "/src/*.rs",
+                "line": 3,
+                "owners": ["@rust"],
+            }))
+        );
+
+        let body = br#"{"paths": ["src/main.rs", "README.md"]}"#;
+        let response = route(&state, "POST", "/owners", body);
+        assert_eq!(response.body["README.md"]["owners"], json!(["@docs"]));
+        assert_eq!(response.body["src/main.rs"]["line"], 3);
+
+        let response = route(&state, "GET", "/explain?path=src/main.rs", b"");
+        let matches = response.body["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[1]["winner"], true);
+    }
+
+    #[test]
+    fn test_route_coverage_and_unowned() {
+        let state = state();
+
+        let response = route(&state, "GET", "/unowned", b"");
+        assert_eq!(response, Response::ok(json!(["notes/a b.txt"])));
+
+        let response = route(&state, "GET", "/coverage", b"");
+        assert_eq!(response.body["total"], 3);
+        assert_eq!(response.body["owned"], 2);
+        assert_eq!(response.body["coverage_percent"], 66.7);
+
+        // Paths with spaces arrive encoded
+        let response = route(&state, "GET", "/owners?path=notes/a+b.txt", b"");
+        assert_eq!(response.body["owned"], false);
+    }
+
+    #[test]
+    fn test_route_errors() {
+        let state = state();
+        assert_eq!(route(&state, "GET", "/owners", b"").status, 400);
+        assert_eq!(route(&state, "POST", "/owners", b"[1]").status, 400);
+        assert_eq!(route(&state, "DELETE", "/unowned", b"").status, 405);
+        assert_eq!(route(&state, "GET", "/nope", b"").status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_handle_http_request() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        let task = tokio::spawn(handle(server, Arc::new(RwLock::new(state())), READ_TIMEOUT));
+
+        let body = r#"{"paths": ["README.md"]}"#;
+        let request = format!(
+            "POST /owners HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        client.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        task.await.unwrap().unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        let (_, json) = response.split_once("\r\n\r\n").unwrap();
+        let json: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["README.md"]["owners"], json!(["@docs"]));
+    }
+
+    async fn send(request: &[u8]) -> String {
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let task = tokio::spawn(handle(server, Arc::new(RwLock::new(state())), READ_TIMEOUT));
+        // The server may answer before reading everything we send
+        let _ = client.write_all(request).await;
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        task.await.unwrap().unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_handle_rejects_oversized_requests() {
+        let target = format!("/owners?path={}", "a".repeat(MAX_LINE));
+        let response = send(format!("GET {} HTTP/1.1\r\n\r\n", target).as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 414 URI Too Long\r\n"));
+
+        let header = format!("X-Big: {}\r\n", "a".repeat(MAX_LINE));
+        let response = send(format!("GET /unowned HTTP/1.1\r\n{}\r\n", header).as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 431 "));
+
+        let headers = "X-A: 1\r\n".repeat(MAX_HEADERS + 1);
+        let response = send(format!("GET /unowned HTTP/1.1\r\n{}\r\n", headers).as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 431 "));
+
+        let headers = "X-A: 1\r\n".repeat(MAX_HEADERS);
+        let response = send(format!("GET /unowned HTTP/1.1\r\n{}\r\n", headers).as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_times_out_idle_clients() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        let served = Arc::new(RwLock::new(state()));
+        let task = tokio::spawn(handle(server, served, Duration::from_millis(50)));
+
+        // Send part of a request and then nothing more
+        client
+            .write_all(b"GET /unowned HTTP/1.1\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        task.await.unwrap().unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Where commands read CODEOWNERS and the file list from: the working tree,
+//! or a git revision given with `--rev`
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::file_cache::FileCache;
+use crate::git::{self, Snapshot};
+use crate::ownership::{find_codeowners, get_repo_root};
+use crate::settings::load_settings_from_path;
+
+pub(super) struct Source {
+    /// CODEOWNERS location (for a revision, where it would be checked out)
+    pub path: PathBuf,
+    pub root: PathBuf,
+    pub content: String,
+    /// Files at the revision; None lists the working tree
+    files: Option<Vec<String>>,
+}
+
+impl Source {
+    /// Read the working tree, or `rev` without checking it out
+    pub(super) fn load(rev: Option<&str>) -> Result<Self, String> {
+        let cwd = env::current_dir().expect("Failed to get current directory");
+        Self::load_from(&cwd, rev)
+    }
+
+    fn load_from(cwd: &Path, rev: Option<&str>) -> Result<Self, String> {
+        let Some(rev) = rev else {
+            // Outside git, the repository is wherever CODEOWNERS is
+            let root = git::repo_root(cwd).unwrap_or_else(|_| {
+                find_codeowners(cwd)
+                    .map_or_else(|| cwd.to_path_buf(), |path| get_repo_root(&path, cwd))
+            });
+            let preferred = load_settings_from_path(&root).path;
+            let (relative, content) = git::codeowners_in_working_tree(&root, preferred.as_deref())
+                .ok_or("No CODEOWNERS file found")?;
+            return Ok(Source {
+                path: root.join(relative),
+                root,
+                content,
+                files: None,
+            });
+        };
+
+        let root = git::repo_root(cwd)?;
+        let preferred = load_settings_from_path(&root).path;
+        let snapshot = Snapshot::at_revision(&root, rev, preferred.as_deref())?;
+        let relative = snapshot
+            .codeowners_path
+            .ok_or_else(|| format!("No CODEOWNERS file found at {}", rev))?;
+        Ok(Source {
+            path: root.join(relative),
+            root,
+            content: snapshot.codeowners,
+            files: Some(snapshot.files),
+        })
+    }
+
+    pub(super) fn file_cache(&self) -> FileCache {
+        match &self.files {
+            Some(files) => FileCache::from_files(files.clone()),
+            None => FileCache::new(&self.root),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_source_at_revision() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        git(&root, &["init", "-q"]);
+        fs::create_dir_all(root.join(".github")).unwrap();
+        fs::write(root.join(".github/CODEOWNERS"), "* @old\n").unwrap();
+        fs::write(root.join("a.rs"), "").unwrap();
+        git(&root, &["add", "-A"]);
+        git(&root, &["commit", "-q", "-m", "first"]);
+        git(&root, &["tag", "v1"]);
+
+        fs::write(root.join(".github/CODEOWNERS"), "* @new\n").unwrap();
+        fs::write(root.join("b.rs"), "").unwrap();
+
+        let tagged = Source::load_from(&root, Some("v1")).unwrap();
+        assert_eq!(tagged.content, "* @old\n");
+        assert_eq!(tagged.path, root.join(".github/CODEOWNERS"));
+        assert_eq!(
+            tagged.file_cache().all_files(),
+            [".github/CODEOWNERS", "a.rs"]
+        );
+
+        let work = Source::load_from(&root, None).unwrap();
+        assert_eq!(work.content, "* @new\n");
+        assert!(work.file_cache().all_files().contains(&"b.rs".to_string()));
+
+        let err = Source::load_from(&root, Some("v2")).err().unwrap();
+        assert_eq!(err, "Unknown revision 'v2'");
+
+        // A configured path wins in the working tree too
+        fs::write(root.join(".codeowners-lsp.toml"), "path = \"OWNERS\"\n").unwrap();
+        fs::write(root.join("OWNERS"), "* @custom\n").unwrap();
+        let custom = Source::load_from(&root, None).unwrap();
+        assert_eq!(custom.content, "* @custom\n");
+        assert_eq!(custom.path, root.join("OWNERS"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Stats command - files, lines and rules per owner and owner set.
+
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::{env, fs};
+
+use colored::Colorize;
+
+use crate::file_cache::FileCache;
+use crate::ownership::{find_codeowners, get_repo_root};
+use crate::stats::{ownership_stats, percent, OwnerStats, OwnershipStats};
+
+/// Output format for stats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!(
+                "Invalid format '{}' (expected table, csv or json)",
+                s
+            )),
+        }
+    }
+}
+
+pub fn stats(format: OutputFormat) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let codeowners_path = match find_codeowners(&cwd) {
+        Some(p) => p,
+        None => {
+            eprintln!("No CODEOWNERS file found");
+            return ExitCode::from(1);
+        }
+    };
+
+    let content = match fs::read_to_string(&codeowners_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let repo_root = get_repo_root(&codeowners_path, &cwd);
+    let file_cache = FileCache::new(&repo_root);
+    let stats = ownership_stats(&content, &file_cache, |file| {
+        fs::read(repo_root.join(file))
+            .map(|bytes| bytecount_lines(&bytes))
+            .unwrap_or(0)
+    });
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+        OutputFormat::Csv => print!("{}", csv_report(&stats)),
+        OutputFormat::Table => print_table(&stats),
+    }
+    ExitCode::SUCCESS
+}
+
+/// Lines in a file, counting a final line without a trailing newline
+fn bytecount_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    if bytes.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
+/// One row per owner and per owner set; the `kind` column tells them apart
+fn csv_report(stats: &OwnershipStats) -> String {
+    let mut csv = String::from("kind,owners,files,lines,file_share,line_share,rules\n");
+    let rows = stats
+        .owners
+        .iter()
+        .map(|s| ("owner", s))
+        .chain(stats.owner_sets.iter().map(|s| ("set", s)));
+    for (kind, s) in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            kind,
+            csv_field(&s.owners.join(" ")),
+            s.files,
+            s.lines,
+            s.file_share,
+            s.line_share,
+            s.rules
+        ));
+    }
+    csv.push_str(&format!(
+        "unowned,,{},{},{},{},0\n",
+        stats.unowned_files,
+        stats.unowned_lines,
+        percent(stats.unowned_files, stats.total_files),
+        percent(stats.unowned_lines, stats.total_lines)
+    ));
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn print_rows(title: &str, rows: &[OwnerStats]) {
+    let width = rows
+        .iter()
+        .map(|s| s.owners.join(" ").len())
+        .max()
+        .unwrap_or(0)
+        .max(title.len());
+    println!(
+        "{:<width$}  {:>7}  {:>9}  {:>7}  {:>7}  {:>5}",
+        title.bold(),
+        "files",
+        "lines",
+        "files%",
+        "lines%",
+        "rules",
+        width = width
+    );
+    for s in rows {
+        println!(
+            "{:<width$}  {:>7}  {:>9}  {:>6.1}%  {:>6.1}%  {:>5}",
+            s.owners.join(" ").cyan(),
+            s.files,
+            s.lines,
+            s.file_share,
+            s.line_share,
+            s.rules,
+            width = width
+        );
+    }
+}
+
+fn print_table(stats: &OwnershipStats) {
+    print_rows("Owner", &stats.owners);
+    println!();
+    print_rows("Owner set", &stats.owner_sets);
+    println!();
+    println!(
+        "{} files, {} lines; {} unowned files ({:.1}%), {} unowned lines ({:.1}%)",
+        stats.total_files,
+        stats.total_lines,
+        stats.unowned_files.to_string().yellow(),
+        percent(stats.unowned_files, stats.total_files),
+        stats.unowned_lines.to_string().yellow(),
+        percent(stats.unowned_lines, stats.total_lines)
+    );
+    if !stats.shadowed_only.is_empty() {
+        println!(
+            "{} Only on shadowed rules: {}",
+            "⚠".yellow(),
+            stats.shadowed_only.join(" ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytecount_lines() {
+        assert_eq!(bytecount_lines(b""), 0);
+        assert_eq!(bytecount_lines(b"a\nb\n"), 2);
+        assert_eq!(bytecount_lines(b"a\nb"), 2);
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let cache = FileCache::from_files(vec!["src/a.rs".to_string(), "b.md".to_string()]);
+        let stats = ownership_stats("/src/ @core a@example.com\n", &cache, |_| 4);
+        let csv = csv_report(&stats);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "kind,owners,files,lines,file_share,line_share,rules"
+        );
+        assert!(lines.contains(&"owner,@core,1,4,50,50,1"));
+        assert!(lines.contains(&"set,@core a@example.com,1,4,50,50,1"));
+        assert_eq!(lines.last(), Some(&"unowned,,1,4,50,50,0"));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
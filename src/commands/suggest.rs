@@ -276,7 +276,7 @@ fn apply_suggestions(content: &str, suggestions: &[OwnerSuggestion]) -> String {
         .collect();
 
     // Sort by insertion point descending so we insert from bottom to top
-    insertions.sort_by(|a, b| b.0.cmp(&a.0));
+    insertions.sort_by_key(|b| std::cmp::Reverse(b.0));
 
     for (idx, line) in insertions {
         lines.insert(idx, line);
@@ -1,11 +1,10 @@
 use std::collections::HashSet;
 use std::process::ExitCode;
-use std::{env, fs};
 
 use colored::{Color, Colorize};
 
-use crate::file_cache::FileCache;
-use crate::ownership::{check_file_ownership_parsed, find_codeowners, get_repo_root};
+use super::source::Source;
+use crate::ownership::check_file_ownership_parsed;
 use crate::parser::parse_codeowners_file_with_positions;
 
 /// Generate a consistent color from a string
@@ -32,28 +31,17 @@ fn owner_color(owner: &str) -> Color {
     colors[(hash as usize) % colors.len()]
 }
 
-pub fn tree() -> ExitCode {
-    let cwd = env::current_dir().expect("Failed to get current directory");
-
-    let codeowners_path = match find_codeowners(&cwd) {
-        Some(p) => p,
-        None => {
-            eprintln!("No CODEOWNERS file found");
-            return ExitCode::from(1);
-        }
-    };
-
-    let content = match fs::read_to_string(&codeowners_path) {
-        Ok(c) => c,
+pub fn tree(rev: Option<String>) -> ExitCode {
+    let source = match Source::load(rev.as_deref()) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            eprintln!("{}", e);
             return ExitCode::from(1);
         }
     };
 
-    let repo_root = get_repo_root(&codeowners_path, &cwd);
-    let file_cache = FileCache::new(&repo_root);
-    let parsed_lines = parse_codeowners_file_with_positions(&content);
+    let file_cache = source.file_cache();
+    let parsed_lines = parse_codeowners_file_with_positions(&source.content);
 
     // Collect all files with their owners
     let mut files_with_owners: Vec<(String, Option<String>)> = Vec::new();
@@ -1,8 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::process::ExitCode;
 use std::sync::Arc;
-use std::{env, fs};
 
 use colored::Colorize;
 use futures::stream::{self, StreamExt};
@@ -10,10 +9,12 @@ use indicatif::{ProgressBar, ProgressStyle};
 use serde::Serialize;
 
 use super::files::collect_files;
+use super::source::Source;
+use crate::diagnostics::codes;
 use crate::github::GitHubClient;
-use crate::ownership::find_codeowners;
 use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
 use crate::pattern::pattern_matches;
+use crate::report::{self, Finding, Level, ReportFormat};
 
 const CONCURRENCY: usize = 5;
 
@@ -37,30 +38,26 @@ struct InvalidOwner {
     reason: String,
 }
 
+/// Where an owner appears: (line, column, length), 0-indexed
+type OwnerPositions = HashMap<String, Vec<(u32, u32, u32)>>;
+
 pub async fn validate_owners(
     token: &str,
     files: Option<Vec<String>>,
     files_from: Option<PathBuf>,
     stdin: bool,
     json: bool,
+    format: Option<ReportFormat>,
+    rev: Option<String>,
 ) -> ExitCode {
-    let cwd = env::current_dir().expect("Failed to get current directory");
-
-    let codeowners_path = match find_codeowners(&cwd) {
-        Some(p) => p,
-        None => {
-            eprintln!("No CODEOWNERS file found");
-            return ExitCode::from(1);
-        }
-    };
-
-    let content = match fs::read_to_string(&codeowners_path) {
-        Ok(c) => c,
+    let source = match Source::load(rev.as_deref()) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("Failed to read {}: {}", codeowners_path.display(), e);
+            eprintln!("{}", e);
             return ExitCode::from(1);
         }
     };
+    let content = source.content;
 
     // Collect files to filter by (if specified)
     let files_filter = match collect_files(files, files_from, stdin) {
@@ -74,6 +71,7 @@ pub async fn validate_owners(
     // Collect owners (optionally filtered by which rules match specified files)
     let lines = parse_codeowners_file_with_positions(&content);
     let mut owners: HashSet<String> = HashSet::new();
+    let mut positions: OwnerPositions = HashMap::new();
 
     for line in &lines {
         if let CodeownersLine::Rule {
@@ -88,15 +86,30 @@ pub async fn validate_owners(
             };
 
             if should_include {
+                let line_text = content.lines().nth(line.line_number as usize).unwrap_or("");
+                let mut search_start = line.owners_start as usize;
                 for owner in line_owners {
                     owners.insert(owner.clone());
+                    if let Some(pos) = line_text[search_start..].find(owner.as_str()) {
+                        let column = search_start + pos;
+                        positions.entry(owner.clone()).or_default().push((
+                            line.line_number,
+                            column as u32,
+                            owner.len() as u32,
+                        ));
+                        search_start = column + owner.len();
+                    }
                 }
             }
         }
     }
 
+    let report_path = report::repo_relative(&source.path, &source.root);
+
     if owners.is_empty() {
-        if files_filter.is_some() {
+        if let Some(format) = format {
+            println!("{}", report::render(format, "validate-owners", &[]));
+        } else if files_filter.is_some() {
             println!("{}", "No owners found for the specified files".yellow());
         } else {
             println!("{}", "No owners found in CODEOWNERS".yellow());
@@ -114,11 +127,13 @@ pub async fn validate_owners(
     } else {
         ""
     };
-    println!(
-        "Validating {} unique owners against GitHub{}...\n",
-        total.to_string().cyan(),
-        filter_msg
-    );
+    if format.is_none() {
+        println!(
+            "Validating {} unique owners against GitHub{}...\n",
+            total.to_string().cyan(),
+            filter_msg
+        );
+    }
 
     // Progress bar
     let pb = ProgressBar::new(total as u64);
@@ -167,6 +182,16 @@ pub async fn validate_owners(
     invalid.sort_by_key(|(o, _)| *o);
     unknown.sort_by_key(|(o, _)| *o);
 
+    if let Some(format) = format {
+        let findings = owner_findings(&report_path, &content, &positions, &invalid, &unknown);
+        println!("{}", report::render(format, "validate-owners", &findings));
+        return if !invalid.is_empty() {
+            ExitCode::from(1)
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+
     // JSON output
     if json {
         let output = ValidateOwnersJson {
@@ -225,6 +250,48 @@ pub async fn validate_owners(
     }
 }
 
+/// One finding per occurrence of each invalid or unverifiable owner
+fn owner_findings(
+    path: &str,
+    content: &str,
+    positions: &OwnerPositions,
+    invalid: &[(&str, &str)],
+    unknown: &[(&str, &str)],
+) -> Vec<Finding> {
+    let flagged = invalid
+        .iter()
+        .map(|(owner, reason)| {
+            let message = format!("Owner '{}' {}", owner, reason);
+            (*owner, message, codes::GITHUB_OWNER_NOT_FOUND, Level::Error)
+        })
+        .chain(unknown.iter().map(|(owner, reason)| {
+            let message = format!("Owner '{}' could not be verified {}", owner, reason);
+            (
+                *owner,
+                message,
+                codes::GITHUB_OWNER_UNVERIFIED,
+                Level::Warning,
+            )
+        }));
+
+    let mut findings = Vec::new();
+    for (owner, message, code, level) in flagged {
+        for &(line, column, len) in positions.get(owner).into_iter().flatten() {
+            findings.push(
+                Finding {
+                    line: Some(line + 1),
+                    column: Some(column + 1),
+                    end_column: Some(column + len + 1),
+                    ..Finding::file(code, level, path, message.clone())
+                }
+                .keyed(content),
+            );
+        }
+    }
+    findings.sort_by_key(|f| (f.line, f.column));
+    findings
+}
+
 async fn validate_single(client: &GitHubClient, owner: &str, token: &str) -> ValidationResult {
     let result = client.validate_owner(owner, token).await;
 
@@ -248,6 +315,42 @@ mod tests {
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    #[test]
+    fn test_owner_findings() {
+        let mut positions = OwnerPositions::new();
+        positions.insert("@ghost".to_string(), vec![(3, 6, 6), (0, 2, 6)]);
+        positions.insert("@org/hidden".to_string(), vec![(1, 7, 11)]);
+
+        let content = "* @org/a @ghost\n/docs/ @org/hidden\n/lib/ @b\n/src/ @ghost\n";
+        let findings = owner_findings(
+            ".github/CODEOWNERS",
+            content,
+            &positions,
+            &[("@ghost", "not found on GitHub")],
+            &[("@org/hidden", "(couldn't validate - check permissions)")],
+        );
+        let located: Vec<_> = findings
+            .iter()
+            .map(|f| (f.rule_id.as_str(), f.line, f.column, f.end_column))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                ("github-owner-not-found", Some(1), Some(3), Some(9)),
+                ("github-owner-unverified", Some(2), Some(8), Some(19)),
+                ("github-owner-not-found", Some(4), Some(7), Some(13)),
+            ]
+        );
+        assert_eq!(findings[0].message, "Owner '@ghost' not found on GitHub");
+        // Each occurrence is keyed by its own rule
+        assert_ne!(findings[0].key, findings[2].key);
+        assert_eq!(findings[1].level, Level::Warning);
+        assert_eq!(
+            findings[1].message,
+            "Owner '@org/hidden' could not be verified (couldn't validate - check permissions)"
+        );
+    }
+
     #[tokio::test]
     async fn test_validate_single_valid_user() {
         let mock_server = MockServer::start().await;
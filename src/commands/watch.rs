@@ -0,0 +1,549 @@
+//! Watch command - re-run lint, coverage or check as CODEOWNERS and the file
+//! tree change, printing only what changed since the last run.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{env, fs};
+
+use colored::Colorize;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tower_lsp::lsp_types::DiagnosticSeverity;
+
+use super::coverage::coverage_percent;
+use super::files::collect_files;
+use crate::baseline;
+use crate::diagnostics;
+use crate::file_cache::FileCache;
+use crate::git;
+use crate::ownership::{check_file_ownership_parsed, find_codeowners, get_repo_root};
+use crate::parser::{parse_codeowners_file_with_positions, ParsedLine};
+use crate::settings::{load_settings_from_path, CONFIG_FILE, CONFIG_FILE_LOCAL};
+
+/// Quiet period before re-running, so a save or checkout triggers one run
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Locate and parse the CODEOWNERS file under `root`; a missing file counts
+/// as empty
+pub(super) fn read_codeowners(root: &Path) -> (String, Vec<ParsedLine>) {
+    let content = find_codeowners(root)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let parsed = parse_codeowners_file_with_positions(&content);
+    (content, parsed)
+}
+
+/// What to keep re-running
+pub enum WatchReport {
+    Lint,
+    Coverage,
+    Check(Vec<String>),
+}
+
+/// One line of a report. Entries with the same key are compared by `value`;
+/// `display` may change (e.g. a line number) without counting as a change.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    value: String,
+    display: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Snapshot {
+    entries: BTreeMap<String, Entry>,
+    /// Headline printed whenever it changes
+    summary: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Change<'a> {
+    Added(&'a Entry),
+    Removed(&'a Entry),
+    Changed(&'a Entry),
+}
+
+fn delta<'a>(old: &'a Snapshot, new: &'a Snapshot) -> Vec<Change<'a>> {
+    let mut changes = Vec::new();
+    for (key, entry) in &old.entries {
+        if !new.entries.contains_key(key) {
+            changes.push(Change::Removed(entry));
+        }
+    }
+    for (key, entry) in &new.entries {
+        match old.entries.get(key) {
+            None => changes.push(Change::Added(entry)),
+            Some(previous) if previous.value != entry.value => changes.push(Change::Changed(entry)),
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+/// CODEOWNERS, its parsed rules and the file tree, kept between runs
+pub(super) struct State {
+    pub(super) root: PathBuf,
+    pub(super) content: String,
+    pub(super) parsed: Vec<ParsedLine>,
+    pub(super) cache: FileCache,
+}
+
+impl State {
+    pub(super) fn load(cwd: &Path) -> Result<Self, String> {
+        let codeowners_path = find_codeowners(cwd).ok_or("No CODEOWNERS file found")?;
+        let root = get_repo_root(&codeowners_path, cwd);
+        let cache = FileCache::new(&root);
+        let mut state = State {
+            root,
+            content: String::new(),
+            parsed: Vec::new(),
+            cache,
+        };
+        state.reload_codeowners();
+        Ok(state)
+    }
+
+    /// Re-locate and re-parse CODEOWNERS
+    fn reload_codeowners(&mut self) {
+        (self.content, self.parsed) = read_codeowners(&self.root);
+    }
+
+    fn reload_files(&mut self) {
+        self.cache = FileCache::new(&self.root);
+    }
+
+    fn snapshot(&self, report: &WatchReport) -> Snapshot {
+        match report {
+            WatchReport::Lint => self.lint_snapshot(),
+            WatchReport::Coverage => self.coverage_snapshot(),
+            WatchReport::Check(paths) => self.check_snapshot(paths),
+        }
+    }
+
+    fn lint_snapshot(&self) -> Snapshot {
+        let settings = load_settings_from_path(&self.root);
+        let (diagnostics, _) = diagnostics::compute_diagnostics_sync(
+            &self.content,
+            Some(&self.cache),
+            &settings.diagnostic_config(),
+        );
+
+        let mut entries = BTreeMap::new();
+        for d in &diagnostics {
+            let severity = match d.severity {
+                Some(DiagnosticSeverity::ERROR) => "error".red(),
+                Some(DiagnosticSeverity::WARNING) => "warning".yellow(),
+                Some(DiagnosticSeverity::HINT) => "hint".cyan(),
+                _ => "info".blue(),
+            };
+            let (code, fingerprint, _) = baseline::key(&self.content, d);
+            let entry = Entry {
+                value: format!("{:?}", d.severity),
+                display: format!(
+                    "{} [{}] {} {}",
+                    format!("line {}:", d.range.start.line + 1).dimmed(),
+                    severity,
+                    code.bold(),
+                    d.message
+                ),
+            };
+            // Identical rules get distinct keys
+            let mut key = format!("{}:{}", code, fingerprint);
+            let mut n = 1;
+            while entries.contains_key(&key) {
+                n += 1;
+                key = format!("{}:{}#{}", code, fingerprint, n);
+            }
+            entries.insert(key, entry);
+        }
+
+        Snapshot {
+            summary: match diagnostics.len() {
+                0 => "no issues".to_string(),
+                1 => "1 issue".to_string(),
+                n => format!("{} issues", n),
+            },
+            entries,
+        }
+    }
+
+    fn coverage_snapshot(&self) -> Snapshot {
+        let unowned = self.cache.get_unowned_files(&self.parsed);
+        let total = self.cache.all_files().len();
+        let owned = total - unowned.len();
+        Snapshot {
+            summary: format!(
+                "coverage {}% ({}/{} files owned)",
+                coverage_percent(total, unowned.len()),
+                owned,
+                total
+            ),
+            entries: unowned
+                .into_iter()
+                .map(|file| {
+                    let entry = Entry {
+                        value: String::new(),
+                        display: format!("{} unowned", file),
+                    };
+                    (file.clone(), entry)
+                })
+                .collect(),
+        }
+    }
+
+    fn check_snapshot(&self, paths: &[String]) -> Snapshot {
+        let entries = paths
+            .iter()
+            .map(|path| {
+                let entry = match check_file_ownership_parsed(&self.parsed, path) {
+                    Some(result) => Entry {
+                        value: format!("{} {}", result.pattern, result.owners.join(" ")),
+                        display: format!(
+                            "{} {} {}",
+                            path,
+                            result.owners.join(" ").green(),
+                            format!("({}, line {})", result.pattern, result.line_number + 1)
+                                .dimmed()
+                        ),
+                    },
+                    None => Entry {
+                        value: String::new(),
+                        display: format!("{} {}", path, "unowned".red()),
+                    },
+                };
+                (path.clone(), entry)
+            })
+            .collect();
+        Snapshot {
+            entries,
+            summary: String::new(),
+        }
+    }
+}
+
+fn print_snapshot(snapshot: &Snapshot) {
+    for entry in snapshot.entries.values() {
+        println!("  {}", entry.display);
+    }
+    if !snapshot.summary.is_empty() {
+        println!("{}", snapshot.summary.bold());
+    }
+}
+
+fn print_delta(old: &Snapshot, new: &Snapshot) {
+    let changes = delta(old, new);
+    if changes.is_empty() && old.summary == new.summary {
+        println!("{}", "  no changes".dimmed());
+        return;
+    }
+    for change in changes {
+        match change {
+            Change::Added(entry) => println!("{} {}", "+".green(), entry.display),
+            Change::Removed(entry) => {
+                println!("{} {}", "-".red(), entry.display.dimmed())
+            }
+            Change::Changed(entry) => println!("{} {}", "~".yellow(), entry.display),
+        }
+    }
+    if old.summary != new.summary {
+        println!("{}", new.summary.bold());
+    }
+}
+
+/// What a batch of filesystem events touched
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Touched {
+    /// CODEOWNERS or a settings file
+    pub(super) codeowners: bool,
+    /// Files were added, removed or renamed
+    pub(super) tree: bool,
+}
+
+/// Debounced filesystem events under a repository root
+pub(super) struct Changes {
+    root: PathBuf,
+    rx: mpsc::Receiver<notify::Result<notify::Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Changes {
+    pub(super) fn watch(root: &Path) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)
+            .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+        Ok(Changes {
+            root: root.to_path_buf(),
+            rx,
+            _watcher: watcher,
+        })
+    }
+
+    /// Block until a batch of events touches CODEOWNERS or the file tree.
+    /// Returns None once the watcher stops.
+    pub(super) fn next(&self) -> Option<Touched> {
+        loop {
+            let first = self.rx.recv().ok()?;
+            let mut events: Vec<notify::Event> = first.into_iter().collect();
+            while let Ok(next) = self.rx.recv_timeout(DEBOUNCE) {
+                events.extend(next);
+            }
+            let touched = classify(&events, &self.root, |paths| {
+                git::ignored_paths(&self.root, paths)
+            });
+            if touched != Touched::default() {
+                return Some(touched);
+            }
+        }
+    }
+}
+
+/// Sort events into CODEOWNERS and file tree changes. Paths `ignored`
+/// reports (as .gitignore excludes them) don't count as tree changes, so
+/// builds and package installs don't trigger rescans.
+fn classify(
+    events: &[notify::Event],
+    root: &Path,
+    ignored: impl FnOnce(&[String]) -> HashSet<String>,
+) -> Touched {
+    let mut touched = Touched::default();
+    let mut tree_paths: Vec<String> = Vec::new();
+    for event in events {
+        // Our own reads of CODEOWNERS show up as access events
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
+        }
+        let relevant: Vec<&Path> = event
+            .paths
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap_or(p))
+            .filter(|rel| !rel.as_os_str().is_empty() && !rel.starts_with(".git"))
+            .collect();
+        if relevant.is_empty() {
+            continue;
+        }
+        let is_config = |p: &&Path| {
+            p.file_name().is_some_and(|name| {
+                name == "CODEOWNERS" || name == CONFIG_FILE || name == CONFIG_FILE_LOCAL
+            })
+        };
+        if relevant.iter().any(is_config) {
+            touched.codeowners = true;
+        }
+        if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+        ) {
+            tree_paths.extend(relevant.iter().map(|p| p.to_string_lossy().into_owned()));
+        }
+    }
+
+    tree_paths.sort();
+    tree_paths.dedup();
+    if !tree_paths.is_empty() {
+        let ignored = ignored(&tree_paths);
+        touched.tree = tree_paths.iter().any(|p| !ignored.contains(p));
+    }
+    touched
+}
+
+pub fn watch(report: WatchReport) -> ExitCode {
+    let cwd = env::current_dir().expect("Failed to get current directory");
+
+    let report = match report {
+        WatchReport::Check(paths) => match collect_files(Some(paths), None, false) {
+            Ok(Some(files)) => {
+                let mut files: Vec<String> = files.into_iter().collect();
+                files.sort();
+                WatchReport::Check(files)
+            }
+            Ok(None) => {
+                eprintln!("No files specified");
+                return ExitCode::from(1);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(1);
+            }
+        },
+        report => report,
+    };
+
+    let mut state = match State::load(&cwd) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let changes = match Changes::watch(&state.root) {
+        Ok(changes) => changes,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    let mut last = state.snapshot(&report);
+    print_snapshot(&last);
+    eprintln!(
+        "{}",
+        format!("Watching {} (Ctrl-C to stop)", state.root.display()).dimmed()
+    );
+
+    while let Some(touched) = changes.next() {
+        if touched.tree {
+            state.reload_files();
+        }
+        state.reload_codeowners();
+
+        let what = match (touched.codeowners, touched.tree) {
+            (true, true) => "CODEOWNERS and files changed",
+            (true, false) => "CODEOWNERS changed",
+            _ => "files changed",
+        };
+        println!();
+        println!("{}", format!("↻ {}", what).dimmed());
+        let snapshot = state.snapshot(&report);
+        print_delta(&last, &snapshot);
+        last = snapshot;
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, AccessMode, CreateKind, DataChange};
+
+    fn state(content: &str, files: &[&str]) -> State {
+        State {
+            root: PathBuf::from("/repo"),
+            content: content.to_string(),
+            parsed: parse_codeowners_file_with_positions(content),
+            cache: FileCache::from_files(files.iter().map(|f| f.to_string()).collect()),
+        }
+    }
+
+    fn displays(changes: &[Change]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| match change {
+                Change::Added(e) => format!("+{}", e.display),
+                Change::Removed(e) => format!("-{}", e.display),
+                Change::Changed(e) => format!("~{}", e.display),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_delta_ignores_moved_lines() {
+        colored::control::set_override(false);
+        let files = ["src/main.rs"];
+        let before = state("/src/ @a\n/gone/ @b\n", &files);
+        let after = state("# moved\n/src/ @a\n/gone/ @b\n/old/ @c\n", &files);
+
+        let old = before.lint_snapshot();
+        let new = after.lint_snapshot();
+        assert_eq!(old.summary, "1 issue");
+        let changes = delta(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert!(displays(&changes)[0].starts_with("+line 4: [warning] pattern-no-match"));
+    }
+
+    #[test]
+    fn test_coverage_and_check_delta() {
+        colored::control::set_override(false);
+        let files = ["src/main.rs", "docs/a.md"];
+        let before = state("/src/ @a\n", &files);
+        let after = state("/src/ @b\n/docs/ @docs\n", &files);
+
+        let (old, new) = (before.coverage_snapshot(), after.coverage_snapshot());
+        assert_eq!(displays(&delta(&old, &new)), vec!["-docs/a.md unowned"]);
+        assert_eq!(new.summary, "coverage 100% (2/2 files owned)");
+        let empty = state("* @all\n", &[]);
+        assert_eq!(
+            empty.coverage_snapshot().summary,
+            "coverage 100% (0/0 files owned)"
+        );
+
+        let paths = vec!["src/main.rs".to_string(), "docs/a.md".to_string()];
+        let (old, new) = (before.check_snapshot(&paths), after.check_snapshot(&paths));
+        assert_eq!(
+            displays(&delta(&old, &new)),
+            vec![
+                "~docs/a.md @docs (/docs/, line 2)",
+                "~src/main.rs @b (/src/, line 1)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_events() {
+        let root = Path::new("/repo");
+        let event = |kind, path: &str| notify::Event::new(kind).add_path(PathBuf::from(path));
+        let edit = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        let create = EventKind::Create(CreateKind::File);
+
+        let none = |_: &[String]| HashSet::new();
+
+        let touched = classify(&[event(edit, "/repo/.github/CODEOWNERS")], root, none);
+        assert_eq!(
+            touched,
+            Touched {
+                codeowners: true,
+                tree: false
+            }
+        );
+
+        let touched = classify(&[event(create, "/repo/src/new.rs")], root, none);
+        assert_eq!(
+            touched,
+            Touched {
+                codeowners: false,
+                tree: true
+            }
+        );
+
+        // Editing a file's content, reading CODEOWNERS or anything in .git
+        // changes nothing
+        let read = EventKind::Access(AccessKind::Close(AccessMode::Read));
+        let touched = classify(
+            &[
+                event(edit, "/repo/src/main.rs"),
+                event(read, "/repo/.github/CODEOWNERS"),
+                event(create, "/repo/.git/index.lock"),
+            ],
+            root,
+            none,
+        );
+        assert_eq!(touched, Touched::default());
+
+        // Build output and other ignored paths aren't tree changes
+        let built = [
+            event(create, "/repo/target/debug/app"),
+            event(create, "/repo/target/debug/app.d"),
+        ];
+        let all_ignored = |paths: &[String]| paths.iter().cloned().collect();
+        assert_eq!(classify(&built, root, all_ignored), Touched::default());
+
+        let target_only = |paths: &[String]| {
+            paths
+                .iter()
+                .filter(|p| p.starts_with("target/"))
+                .cloned()
+                .collect()
+        };
+        let mixed = [
+            event(create, "/repo/target/debug/app"),
+            event(create, "/repo/src/lib.rs"),
+        ];
+        assert!(classify(&mixed, root, target_only).tree);
+    }
+}
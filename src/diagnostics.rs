@@ -4,6 +4,7 @@ use tower_lsp::lsp_types::*;
 
 use crate::file_cache::FileCache;
 use crate::github::{GitHubClient, OwnerInfo};
+use crate::handlers::util::utf16_len;
 use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine, ParsedLine};
 use crate::pattern::pattern_subsumes;
 use crate::validation::{validate_owner, validate_pattern};
@@ -116,12 +117,18 @@ pub fn compute_diagnostics_sync(
                     });
                 }
             } else if file_cache.is_some() {
-                // Only check valid patterns for file matches
+                // Only check valid patterns for file matches. Their columns
+                // are UTF-16, as code actions read the pattern back from them.
+                let text = content.lines().nth(parsed_line.line_number as usize);
+                let column = |byte: u32| {
+                    text.and_then(|text| text.get(..byte as usize))
+                        .map_or(byte, utf16_len)
+                };
                 patterns_to_check.push((
                     pattern.as_str(),
                     parsed_line.line_number,
-                    parsed_line.pattern_start,
-                    parsed_line.pattern_end,
+                    column(parsed_line.pattern_start),
+                    column(parsed_line.pattern_end),
                 ));
             }
 
@@ -809,6 +816,21 @@ src/apps/foo/priv/gettext/ @team2
         assert_eq!(shadowed.len(), 1);
     }
 
+    #[test]
+    fn test_pattern_no_match_range_in_utf16() {
+        let cache = FileCache::from_files(vec!["src/main.rs".to_string()]);
+        let content = "/dócs/🚀/ @docs\n";
+        let (diags, _) =
+            compute_diagnostics_sync(content, Some(&cache), &DiagnosticConfig::default());
+        let no_match = diags
+            .iter()
+            .find(|d| d.code == Some(NumberOrString::String(codes::PATTERN_NO_MATCH.to_string())))
+            .unwrap();
+        assert_eq!(no_match.range.start.character, 0);
+        // 8 chars, 9 UTF-16 code units, 12 bytes
+        assert_eq!(no_match.range.end.character, 9);
+    }
+
     #[test]
     fn test_code_constants() {
         // Ensure code constants are what we expect
@@ -2,11 +2,12 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::RwLock;
+use std::sync::{Arc, OnceLock, RwLock};
 
 use rayon::prelude::*;
 
 use crate::parser::{CodeownersLine, ParsedLine};
+use crate::path_suggest::{load_renames, PathIndex};
 use crate::pattern::CompiledPattern;
 
 /// Check if characters in needle appear in order in haystack (fuzzy match)
@@ -32,6 +33,15 @@ pub struct FileCache {
     count_cache: RwLock<HashMap<String, usize>>,
     /// Cache of pattern -> has_match (lazily populated)
     has_match_cache: RwLock<HashSet<String>>,
+    /// Repository root, used to read git history on demand
+    root: Option<PathBuf>,
+    /// Directory tree for path suggestions (lazily built)
+    path_index: OnceLock<PathIndex>,
+    /// File renames from git history (lazily loaded, may be shared between scans)
+    renames: Arc<OnceLock<Vec<(String, String)>>>,
+    /// Whether `renames` is filled by a background task; lookups then use
+    /// whatever is loaded instead of reading git themselves
+    renames_in_background: bool,
 }
 
 impl FileCache {
@@ -41,9 +51,27 @@ impl FileCache {
             files,
             count_cache: RwLock::new(HashMap::new()),
             has_match_cache: RwLock::new(HashSet::new()),
+            root: None,
+            path_index: OnceLock::new(),
+            renames: Arc::new(OnceLock::new()),
+            renames_in_background: false,
         }
     }
 
+    /// Use a known rename history instead of reading git (for testing)
+    pub fn with_renames(self, renames: Vec<(String, String)>) -> Self {
+        let _ = self.renames.set(renames);
+        self
+    }
+
+    /// Share a rename history that is loaded in the background and outlives
+    /// this scan. Until it is loaded, `renames` returns nothing.
+    pub fn with_shared_renames(mut self, renames: Arc<OnceLock<Vec<(String, String)>>>) -> Self {
+        self.renames = renames;
+        self.renames_in_background = true;
+        self
+    }
+
     /// Create a new FileCache using git ls-files to get tracked files
     pub fn new(root: &PathBuf) -> Self {
         let files = Command::new("git")
@@ -62,7 +90,28 @@ impl FileCache {
             files,
             count_cache: RwLock::new(HashMap::new()),
             has_match_cache: RwLock::new(HashSet::new()),
+            root: Some(root.clone()),
+            path_index: OnceLock::new(),
+            renames: Arc::new(OnceLock::new()),
+            renames_in_background: false,
+        }
+    }
+
+    /// Directory tree of the cached files (built on first use)
+    pub fn path_index(&self) -> &PathIndex {
+        self.path_index.get_or_init(|| PathIndex::new(&self.files))
+    }
+
+    /// File renames from git history, newest first (loaded on first use,
+    /// unless they are shared and loaded in the background)
+    pub fn renames(&self) -> &[(String, String)] {
+        if self.renames_in_background {
+            return self.renames.get().map(Vec::as_slice).unwrap_or(&[]);
         }
+        self.renames.get_or_init(|| match &self.root {
+            Some(root) => load_renames(root),
+            None => Vec::new(),
+        })
     }
 
     /// Count files matching a pattern (blocking, computes and caches)
@@ -292,26 +341,38 @@ impl FileCache {
     /// Get files with no owners according to the given rules
     #[allow(dead_code)] // Used by CLI binary
     pub fn get_unowned_files(&self, rules: &[ParsedLine]) -> Vec<&String> {
-        // Extract and compile patterns once
-        let compiled: Vec<CompiledPattern> = rules
-            .iter()
-            .filter_map(|rule| {
-                if let CodeownersLine::Rule { pattern, .. } = &rule.content {
-                    Some(CompiledPattern::new(pattern))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        // Check files in parallel
-        self.files
-            .par_iter()
-            .filter(|file| !compiled.iter().any(|pattern| pattern.matches(file)))
-            .collect()
+        unowned_files(rules, &self.files)
     }
 }
 
+/// The files in `files` without a code owner: no rule matches them, or the
+/// last matching rule lists no owners (as on GitHub)
+pub fn unowned_files<'a>(rules: &[ParsedLine], files: &'a [String]) -> Vec<&'a String> {
+    // Extract and compile patterns once, last rule first
+    let compiled: Vec<(CompiledPattern, bool)> = rules
+        .iter()
+        .rev()
+        .filter_map(|rule| {
+            if let CodeownersLine::Rule { pattern, owners } = &rule.content {
+                Some((CompiledPattern::new(pattern), !owners.is_empty()))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Check files in parallel
+    files
+        .par_iter()
+        .filter(|file| {
+            !compiled
+                .iter()
+                .find(|(pattern, _)| pattern.matches(file))
+                .is_some_and(|(_, has_owners)| *has_owners)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -388,6 +449,19 @@ mod tests {
         assert_eq!(unowned.len(), 2); // docs/readme.md and Cargo.toml
     }
 
+    #[test]
+    fn test_unowned_files_without_owners_on_winning_rule() {
+        let rules = crate::parser::parse_codeowners_file_with_positions(
+            "* @all\n/vendor/\n/vendor/keep/ @keep\n",
+        );
+        let files = vec![
+            "src/main.rs".to_string(),
+            "vendor/lib.c".to_string(),
+            "vendor/keep/a.c".to_string(),
+        ];
+        assert_eq!(unowned_files(&rules, &files), vec!["vendor/lib.c"]);
+    }
+
     #[test]
     fn test_all_files_owned() {
         let dir = tempdir().unwrap();
@@ -790,4 +864,23 @@ mod tests {
         // Second call should hit the has_match_cache (line 100)
         assert!(cache.has_matches("*.rs"));
     }
+
+    #[test]
+    fn test_shared_renames_not_loaded_on_lookup() {
+        let dir = tempdir().unwrap();
+        create_test_repo(dir.path());
+
+        let shared = Arc::new(OnceLock::new());
+        let cache = FileCache::new(&dir.path().to_path_buf()).with_shared_renames(shared.clone());
+        assert!(cache.renames().is_empty());
+        assert!(shared.get().is_none());
+
+        let renames = vec![("old.rs".to_string(), "new.rs".to_string())];
+        shared.set(renames.clone()).unwrap();
+        assert_eq!(cache.renames(), renames.as_slice());
+
+        // A later scan sees the already loaded history
+        let rescan = FileCache::new(&dir.path().to_path_buf()).with_shared_renames(shared);
+        assert_eq!(rescan.renames(), renames.as_slice());
+    }
 }
@@ -0,0 +1,608 @@
+//! CODEOWNERS formatter shared by `codeowners-cli fmt` and the LSP
+//!
+//! The engine works on whole files; callers that only want part of the
+//! result (range and on-type formatting) diff the output against the input
+//! with [`diff_lines`] and keep the hunks they care about.
+
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::parser::find_inline_comment_start;
+
+/// How comment lines (and inline comments) are rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStyle {
+    /// Keep comments exactly as written
+    #[default]
+    Preserve,
+    /// Trim surrounding whitespace and put a space after the leading `#`s
+    Space,
+}
+
+/// Line endings of the formatted output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    /// Keep whatever the file uses (CRLF if its first line ends in CRLF)
+    #[default]
+    Auto,
+    Lf,
+    Crlf,
+}
+
+impl CommentStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentStyle::Preserve => "preserve",
+            CommentStyle::Space => "space",
+        }
+    }
+}
+
+impl LineEndings {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEndings::Auto => "auto",
+            LineEndings::Lf => "lf",
+            LineEndings::Crlf => "crlf",
+        }
+    }
+}
+
+impl FromStr for CommentStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "preserve" => Ok(CommentStyle::Preserve),
+            "space" => Ok(CommentStyle::Space),
+            _ => Err(format!(
+                "Invalid comment style '{}' (expected preserve or space)",
+                s
+            )),
+        }
+    }
+}
+
+impl FromStr for LineEndings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(LineEndings::Auto),
+            "lf" => Ok(LineEndings::Lf),
+            "crlf" => Ok(LineEndings::Crlf),
+            _ => Err(format!(
+                "Invalid line endings '{}' (expected auto, lf or crlf)",
+                s
+            )),
+        }
+    }
+}
+
+/// Formatter options. The defaults reproduce the plain spacing normalisation
+/// of [`crate::parser::format_codeowners`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Pad patterns so owners line up within each section
+    pub align_owners: bool,
+    /// Sort owners on each rule and drop duplicates
+    pub sort_owners: bool,
+    pub comment_style: CommentStyle,
+    /// Maximum number of consecutive blank lines to keep
+    pub max_blank_lines: usize,
+    pub line_endings: LineEndings,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            align_owners: false,
+            sort_owners: false,
+            comment_style: CommentStyle::Preserve,
+            max_blank_lines: 1,
+            line_endings: LineEndings::Auto,
+        }
+    }
+}
+
+enum FormatLine {
+    Blank,
+    Comment(String),
+    Rule {
+        pattern: String,
+        owners: Vec<String>,
+        comment: Option<String>,
+    },
+}
+
+/// Whether `content` uses CRLF line endings (judged by its first line)
+pub fn uses_crlf(content: &str) -> bool {
+    content
+        .find('\n')
+        .is_some_and(|idx| content[..idx].ends_with('\r'))
+}
+
+/// Format CODEOWNERS content with the given options
+pub fn format_with_options(content: &str, options: &FormatOptions) -> String {
+    let mut lines: Vec<FormatLine> = Vec::new();
+    let mut blank_run = 0;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            // Leading blank lines are dropped, runs are capped
+            if !lines.is_empty() && blank_run < options.max_blank_lines {
+                lines.push(FormatLine::Blank);
+            }
+            blank_run += 1;
+            continue;
+        }
+        blank_run = 0;
+
+        if trimmed.starts_with('#') {
+            let comment = match options.comment_style {
+                CommentStyle::Preserve => line.to_string(),
+                CommentStyle::Space => normalize_comment(trimmed),
+            };
+            lines.push(FormatLine::Comment(comment));
+            continue;
+        }
+
+        let parts: Vec<&str> = trimmed
+            .split_whitespace()
+            .take_while(|part| !part.starts_with('#'))
+            .collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        let mut owners: Vec<String> = parts[1..].iter().map(|o| o.to_string()).collect();
+        if options.sort_owners {
+            owners = sort_owners(owners);
+        }
+
+        let comment = find_inline_comment_start(line).map(|char_off| {
+            let comment: String = line.chars().skip(char_off).collect();
+            match options.comment_style {
+                CommentStyle::Preserve => comment.trim_end().to_string(),
+                CommentStyle::Space => normalize_comment(comment.trim()),
+            }
+        });
+
+        lines.push(FormatLine::Rule {
+            pattern: parts[0].to_string(),
+            owners,
+            comment,
+        });
+    }
+
+    while matches!(lines.last(), Some(FormatLine::Blank)) {
+        lines.pop();
+    }
+
+    let widths = if options.align_owners {
+        section_widths(&lines)
+    } else {
+        vec![0; lines.len()]
+    };
+
+    let rendered: Vec<String> = lines
+        .iter()
+        .zip(widths)
+        .map(|(line, width)| render_line(line, width))
+        .collect();
+
+    let newline = match options.line_endings {
+        LineEndings::Auto if uses_crlf(content) => "\r\n",
+        LineEndings::Crlf => "\r\n",
+        _ => "\n",
+    };
+
+    let mut output = rendered.join(newline);
+    if !output.is_empty() {
+        output.push_str(newline);
+    }
+    output
+}
+
+/// Ensure exactly one space between the leading `#`s and the comment text
+fn normalize_comment(comment: &str) -> String {
+    let text = comment.trim_start_matches('#');
+    let hashes = &comment[..comment.len() - text.len()];
+    let text = text.trim();
+    if text.is_empty() {
+        hashes.to_string()
+    } else {
+        format!("{} {}", hashes, text)
+    }
+}
+
+/// Sort owners case-insensitively, keeping the first spelling of duplicates
+/// (GitHub handles are case-insensitive)
+fn sort_owners(owners: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    let mut owners: Vec<String> = owners
+        .into_iter()
+        .filter(|o| seen.insert(o.to_lowercase()))
+        .collect();
+    owners.sort_by_key(|o| o.to_lowercase());
+    owners
+}
+
+/// Pattern column width for every line: the widest pattern among the rules
+/// of its section (a run of lines without blank lines in between)
+fn section_widths(lines: &[FormatLine]) -> Vec<usize> {
+    let mut widths = vec![0; lines.len()];
+    let mut start = 0;
+    while start < lines.len() {
+        let end = lines[start..]
+            .iter()
+            .position(|l| matches!(l, FormatLine::Blank))
+            .map_or(lines.len(), |offset| start + offset);
+
+        let width = lines[start..end]
+            .iter()
+            .filter_map(|l| match l {
+                FormatLine::Rule {
+                    pattern,
+                    owners,
+                    comment,
+                } if !owners.is_empty() || comment.is_some() => Some(pattern.chars().count()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        widths[start..end].fill(width);
+
+        start = end + 1;
+    }
+    widths
+}
+
+fn render_line(line: &FormatLine, width: usize) -> String {
+    match line {
+        FormatLine::Blank => String::new(),
+        FormatLine::Comment(comment) => comment.clone(),
+        FormatLine::Rule {
+            pattern,
+            owners,
+            comment,
+        } => {
+            let mut out = pattern.clone();
+            let tail: Vec<&str> = owners
+                .iter()
+                .map(|o| o.as_str())
+                .chain(comment.as_deref())
+                .collect();
+            if !tail.is_empty() {
+                let padding = width.saturating_sub(pattern.chars().count());
+                out.push_str(&" ".repeat(padding + 1));
+                out.push_str(&tail.join(" "));
+            }
+            out
+        }
+    }
+}
+
+/// One step of a line diff, holding indices into the old/new line slices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// A maximal run of changed lines: `old[old_start..old_end]` is replaced
+/// by `new[new_start..new_end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_end: usize,
+    pub new_start: usize,
+    pub new_end: usize,
+}
+
+/// Beyond this many LCS cells the changed middle is reported as one hunk
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Line diff (longest common subsequence after trimming the common prefix
+/// and suffix)
+pub fn diff_lines<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = (0..prefix).map(|i| DiffOp::Equal(i, i)).collect();
+
+    if old_mid.len() * new_mid.len() > MAX_DIFF_CELLS {
+        ops.extend((0..old_mid.len()).map(|i| DiffOp::Delete(prefix + i)));
+        ops.extend((0..new_mid.len()).map(|j| DiffOp::Insert(prefix + j)));
+    } else {
+        // lcs[i][j] = LCS length of old_mid[i..] and new_mid[j..]
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push(DiffOp::Equal(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                ops.push(DiffOp::Insert(prefix + j));
+                j += 1;
+            } else {
+                ops.push(DiffOp::Delete(prefix + i));
+                i += 1;
+            }
+        }
+    }
+
+    let old_suffix_start = old.len() - suffix;
+    let new_suffix_start = new.len() - suffix;
+    ops.extend((0..suffix).map(|k| DiffOp::Equal(old_suffix_start + k, new_suffix_start + k)));
+    ops
+}
+
+/// Group diff operations into hunks of changed lines
+pub fn hunks(ops: &[DiffOp]) -> Vec<Hunk> {
+    let mut result: Vec<Hunk> = Vec::new();
+    let mut current: Option<Hunk> = None;
+    let (mut old_pos, mut new_pos) = (0, 0);
+
+    for op in ops {
+        match *op {
+            DiffOp::Equal(i, j) => {
+                result.extend(current.take());
+                old_pos = i + 1;
+                new_pos = j + 1;
+            }
+            DiffOp::Delete(i) => {
+                let hunk = current.get_or_insert(Hunk {
+                    old_start: i,
+                    old_end: i,
+                    new_start: new_pos,
+                    new_end: new_pos,
+                });
+                hunk.old_end = i + 1;
+                old_pos = i + 1;
+            }
+            DiffOp::Insert(j) => {
+                let hunk = current.get_or_insert(Hunk {
+                    old_start: old_pos,
+                    old_end: old_pos,
+                    new_start: j,
+                    new_end: j,
+                });
+                hunk.new_end = j + 1;
+                new_pos = j + 1;
+            }
+        }
+    }
+    result.extend(current);
+    result
+}
+
+/// Render a unified diff (3 lines of context) between two texts.
+/// Returns an empty string when they are identical.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    const CONTEXT: usize = 3;
+
+    // Compare lines with their terminators so line-ending changes show up
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+    let changes = hunks(&ops);
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+    // Merge changes whose context would overlap into one hunk
+    let mut groups: Vec<Vec<Hunk>> = Vec::new();
+    for hunk in changes {
+        match groups.last_mut() {
+            Some(group) if hunk.old_start - group.last().unwrap().old_end <= 2 * CONTEXT => {
+                group.push(hunk)
+            }
+            _ => groups.push(vec![hunk]),
+        }
+    }
+
+    for group in groups {
+        let first = group[0];
+        let last = group[group.len() - 1];
+        let lead = first.old_start.min(CONTEXT);
+        let trail = (old_lines.len() - last.old_end).min(CONTEXT);
+        let old_start = first.old_start - lead;
+        let new_start = first.new_start - lead;
+        let old_len = last.old_end + trail - old_start;
+        let new_len = last.new_end + trail - new_start;
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_len),
+            hunk_range(new_start, new_len)
+        ));
+
+        let mut old_pos = old_start;
+        for hunk in &group {
+            for line in &old_lines[old_pos..hunk.old_start] {
+                push_diff_line(&mut out, ' ', line);
+            }
+            for line in &old_lines[hunk.old_start..hunk.old_end] {
+                push_diff_line(&mut out, '-', line);
+            }
+            for line in &new_lines[hunk.new_start..hunk.new_end] {
+                push_diff_line(&mut out, '+', line);
+            }
+            old_pos = hunk.old_end;
+        }
+        for line in &old_lines[old_pos..last.old_end + trail] {
+            push_diff_line(&mut out, ' ', line);
+        }
+    }
+
+    out
+}
+
+fn hunk_range(start: usize, len: usize) -> String {
+    // Empty ranges point at the line before them
+    let start = if len == 0 { start } else { start + 1 };
+    if len == 1 {
+        start.to_string()
+    } else {
+        format!("{},{}", start, len)
+    }
+}
+
+fn push_diff_line(out: &mut String, marker: char, line: &str) {
+    out.push(marker);
+    match line.strip_suffix('\n') {
+        Some(text) => {
+            out.push_str(text.strip_suffix('\r').unwrap_or(text));
+            out.push('\n');
+        }
+        None => {
+            out.push_str(line);
+            out.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_match_plain_formatting() {
+        let input = "\n\n*.rs    @a   @b\n\n\n\n#  keep   this\n/src/ @team # note\n\n";
+        assert_eq!(
+            format_with_options(input, &FormatOptions::default()),
+            crate::parser::format_codeowners(input)
+        );
+    }
+
+    #[test]
+    fn test_align_owners_per_section() {
+        let input = "*.rs @a\n/src/very/long/ @b\n# comment\n/x/\n\n/docs/ @c\n";
+        let options = FormatOptions {
+            align_owners: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with_options(input, &options),
+            "*.rs            @a\n/src/very/long/ @b\n# comment\n/x/\n\n/docs/ @c\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_and_dedupe_owners() {
+        let input = "*.rs @zed @Alice @org/team @alice\n";
+        let options = FormatOptions {
+            sort_owners: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with_options(input, &options),
+            "*.rs @Alice @org/team @zed\n"
+        );
+    }
+
+    #[test]
+    fn test_comment_and_blank_line_style() {
+        let input = "  #comment  \n\n\n\n## header\n*.rs @a #inline\n";
+        let options = FormatOptions {
+            comment_style: CommentStyle::Space,
+            max_blank_lines: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with_options(input, &options),
+            "# comment\n\n\n## header\n*.rs @a # inline\n"
+        );
+
+        let options = FormatOptions {
+            max_blank_lines: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with_options("*.rs @a\n\n/src/ @b\n", &options),
+            "*.rs @a\n/src/ @b\n"
+        );
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let crlf = "*.rs   @a\r\n/src/ @b\r\n";
+        let options = FormatOptions::default();
+        assert_eq!(
+            format_with_options(crlf, &options),
+            "*.rs @a\r\n/src/ @b\r\n"
+        );
+
+        let options = FormatOptions {
+            line_endings: LineEndings::Lf,
+            ..Default::default()
+        };
+        assert_eq!(format_with_options(crlf, &options), "*.rs @a\n/src/ @b\n");
+    }
+
+    #[test]
+    fn test_hunks() {
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "B", "c", "d", "e"];
+        let changes = hunks(&diff_lines(&old, &new));
+        assert_eq!(
+            changes,
+            vec![
+                Hunk {
+                    old_start: 1,
+                    old_end: 2,
+                    new_start: 1,
+                    new_end: 2
+                },
+                Hunk {
+                    old_start: 4,
+                    old_end: 4,
+                    new_start: 4,
+                    new_end: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\n";
+        assert_eq!(
+            unified_diff(old, new, "a/CODEOWNERS", "b/CODEOWNERS"),
+            "--- a/CODEOWNERS\n+++ b/CODEOWNERS\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+        assert_eq!(unified_diff(old, old, "a", "b"), "");
+
+        let diff = unified_diff("x", "x\n", "a", "b");
+        assert!(diff.contains("-x\n\\ No newline at end of file\n+x\n"));
+    }
+}
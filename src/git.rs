@@ -0,0 +1,378 @@
+//! Reading CODEOWNERS and the file list at a git revision
+//!
+//! Revisions are read with `git show <rev>:<path>` and `git ls-tree`, so
+//! nothing is checked out. The working tree is read from disk and
+//! `git ls-files`, the same way [`FileCache::new`] does.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::file_cache::FileCache;
+use crate::ownership::CODEOWNERS_LOCATIONS;
+
+/// Run git in `root` and return stdout
+fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Top level of the git repository containing `start`
+pub fn repo_root(start: &Path) -> Result<PathBuf, String> {
+    git(start, &["rev-parse", "--show-toplevel"])
+        .map(|root| PathBuf::from(root.trim()))
+        .map_err(|_| "Not inside a git repository".to_string())
+}
+
+/// Full commit hash of a revision (branch, tag, `HEAD~2`, ...)
+pub fn resolve_revision(root: &Path, rev: &str) -> Result<String, String> {
+    git(
+        root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ],
+    )
+    .map(|hash| hash.trim().to_string())
+    .map_err(|_| format!("Unknown revision '{}'", rev))
+}
+
+/// Contents of `path` at `commit`, or None if it doesn't exist there.
+/// `commit` is not checked; resolve user input with [`resolve_revision`].
+pub fn show_file(root: &Path, commit: &str, path: &str) -> Option<String> {
+    git(root, &["show", &format!("{}:{}", commit, path)]).ok()
+}
+
+/// Every file tracked at `commit`
+pub fn list_files(root: &Path, commit: &str) -> Result<Vec<String>, String> {
+    let output = git(root, &["ls-tree", "-r", "-z", "--name-only", commit])?;
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+        .collect())
+}
+
+/// Path and content of the CODEOWNERS file in the working tree, trying
+/// `preferred` before the standard locations
+pub fn codeowners_in_working_tree(
+    root: &Path,
+    preferred: Option<&str>,
+) -> Option<(String, String)> {
+    preferred
+        .into_iter()
+        .chain(CODEOWNERS_LOCATIONS)
+        .find_map(|path| {
+            fs::read_to_string(root.join(path))
+                .ok()
+                .map(|content| (path.to_string(), content))
+        })
+}
+
+/// Path and content of the CODEOWNERS file at `commit`, trying `preferred`
+/// before the standard locations
+pub fn codeowners_at(
+    root: &Path,
+    commit: &str,
+    preferred: Option<&str>,
+) -> Option<(String, String)> {
+    preferred
+        .into_iter()
+        .chain(CODEOWNERS_LOCATIONS)
+        .find_map(|path| show_file(root, commit, path).map(|content| (path.to_string(), content)))
+}
+
+/// Path and content of the staged CODEOWNERS file (`git show :<path>`),
+/// trying `preferred` before the standard locations
+pub fn staged_codeowners(root: &Path, preferred: Option<&str>) -> Option<(String, String)> {
+    preferred
+        .into_iter()
+        .chain(CODEOWNERS_LOCATIONS)
+        .find_map(|path| {
+            git(root, &["show", &format!(":{}", path)])
+                .ok()
+                .map(|content| (path.to_string(), content))
+        })
+}
+
+/// Files added in the index, or between `base` and HEAD when given
+pub fn added_files(root: &Path, base: Option<&str>) -> Result<Vec<String>, String> {
+    let range = base.map(|base| format!("{}...HEAD", base));
+    let mut args = vec!["diff", "--name-only", "-z", "--diff-filter=A"];
+    match &range {
+        Some(range) => args.push(range),
+        None => args.push("--cached"),
+    }
+    let output = git(root, &args)?;
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+        .collect())
+}
+
+/// Directory git runs hooks from (honours `core.hooksPath`)
+pub fn hooks_dir(root: &Path) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(git(root, &["rev-parse", "--git-path", "hooks"])?.trim());
+    Ok(root.join(dir))
+}
+
+/// The `paths` (relative to `root`) that .gitignore excludes; tracked files
+/// never are. If git can't be run, nothing counts as ignored.
+pub fn ignored_paths(root: &Path, paths: &[String]) -> HashSet<String> {
+    if paths.is_empty() {
+        return HashSet::new();
+    }
+    let child = Command::new("git")
+        .args(["check-ignore", "--stdin", "-z"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return HashSet::new();
+    };
+
+    // Write from another thread so a full stdout pipe can't stall git
+    let input: String = paths.iter().map(|path| format!("{}\0", path)).collect();
+    let writer = child.stdin.take().map(|mut stdin| {
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        })
+    });
+    let output = child.wait_with_output();
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+
+    // Exit status 1 just means nothing was ignored
+    output
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split('\0')
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A commit from `git log`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    /// Author date, ISO 8601
+    pub date: String,
+    pub author: String,
+    pub subject: String,
+}
+
+/// Commits touching any of `paths`, oldest first
+pub fn log(root: &Path, paths: &[&str]) -> Result<Vec<Commit>, String> {
+    let mut args = vec!["log", "--reverse", "--format=%H%x1f%aI%x1f%an%x1f%s", "--"];
+    args.extend(paths);
+    let output = git(root, &args)?;
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// CODEOWNERS content and file list at one point in history
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Revision as given, or "working tree"
+    pub label: String,
+    /// Repo-relative CODEOWNERS path, None when there is no CODEOWNERS
+    pub codeowners_path: Option<String>,
+    /// CODEOWNERS content (empty when there is none)
+    pub codeowners: String,
+    pub files: Vec<String>,
+}
+
+impl Snapshot {
+    /// Read a revision. `preferred` (e.g. a custom CODEOWNERS path) is tried
+    /// before the standard locations.
+    pub fn at_revision(root: &Path, rev: &str, preferred: Option<&str>) -> Result<Self, String> {
+        let commit = resolve_revision(root, rev)?;
+        let (codeowners_path, codeowners) = match codeowners_at(root, &commit, preferred) {
+            Some((path, content)) => (Some(path), content),
+            None => (None, String::new()),
+        };
+        Ok(Snapshot {
+            label: rev.to_string(),
+            codeowners_path,
+            codeowners,
+            files: list_files(root, &commit)?,
+        })
+    }
+
+    /// Read the working tree, including uncommitted changes
+    pub fn working_tree(root: &Path, preferred: Option<&str>) -> Self {
+        let (codeowners_path, codeowners) = match codeowners_in_working_tree(root, preferred) {
+            Some((path, content)) => (Some(path), content),
+            None => (None, String::new()),
+        };
+        Snapshot {
+            label: "working tree".to_string(),
+            codeowners_path,
+            codeowners,
+            files: FileCache::new(&PathBuf::from(root)).all_files().to_vec(),
+        }
+    }
+
+    /// A revision, or the working tree when `rev` is None
+    pub fn load(root: &Path, rev: Option<&str>, preferred: Option<&str>) -> Result<Self, String> {
+        match rev {
+            Some(rev) => Self::at_revision(root, rev, preferred),
+            None => Ok(Self::working_tree(root, preferred)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn commit_all(dir: &Path, message: &str) {
+        run(dir, &["add", "-A"]);
+        run(
+            dir,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-q",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        run(root, &["init", "-q"]);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), "").unwrap();
+        fs::write(root.join("CODEOWNERS"), "* @old\n").unwrap();
+        commit_all(root, "first");
+
+        fs::create_dir_all(root.join(".github")).unwrap();
+        fs::rename(root.join("CODEOWNERS"), root.join(".github/CODEOWNERS")).unwrap();
+        fs::write(root.join(".github/CODEOWNERS"), "* @new\n").unwrap();
+
+        let head = Snapshot::at_revision(root, "HEAD", None).unwrap();
+        assert_eq!(head.codeowners_path.as_deref(), Some("CODEOWNERS"));
+        assert_eq!(head.codeowners, "* @old\n");
+        assert_eq!(head.files, vec!["CODEOWNERS", "src/main.rs"]);
+
+        let work = Snapshot::working_tree(root, None);
+        assert_eq!(work.codeowners_path.as_deref(), Some(".github/CODEOWNERS"));
+        assert_eq!(work.codeowners, "* @new\n");
+
+        assert!(Snapshot::at_revision(root, "no-such-branch", None).is_err());
+        assert_eq!(show_file(root, "HEAD", "missing.txt"), None);
+    }
+
+    #[test]
+    fn test_added_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        run(root, &["init", "-q"]);
+        fs::write(root.join("a.txt"), "").unwrap();
+        commit_all(root, "first");
+
+        fs::write(root.join("a.txt"), "changed").unwrap();
+        fs::write(root.join("b c.txt"), "").unwrap();
+        fs::write(root.join("unstaged.txt"), "").unwrap();
+        run(root, &["add", "a.txt", "b c.txt"]);
+        assert_eq!(added_files(root, None).unwrap(), vec!["b c.txt"]);
+
+        commit_all(root, "second");
+        assert!(added_files(root, None).unwrap().is_empty());
+        assert_eq!(
+            added_files(root, Some("HEAD~1")).unwrap(),
+            vec!["b c.txt", "unstaged.txt"]
+        );
+        assert!(hooks_dir(root).unwrap().ends_with(".git/hooks"));
+    }
+
+    #[test]
+    fn test_staged_codeowners_ignores_unstaged_edits() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        run(root, &["init", "-q"]);
+        assert_eq!(staged_codeowners(root, None), None);
+
+        fs::write(root.join("CODEOWNERS"), "* @staged\n").unwrap();
+        run(root, &["add", "CODEOWNERS"]);
+        fs::write(root.join("CODEOWNERS"), "* @unstaged\n").unwrap();
+        assert_eq!(
+            staged_codeowners(root, None),
+            Some(("CODEOWNERS".to_string(), "* @staged\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ignored_paths() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        run(root, &["init", "-q"]);
+        fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::write(root.join("kept.log"), "").unwrap();
+        run(root, &["add", "-f", "kept.log"]);
+
+        let paths: Vec<String> = [
+            "target",
+            "target/debug/app",
+            "src/main.rs",
+            "new.log",
+            "kept.log",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+        let mut ignored: Vec<String> = ignored_paths(root, &paths).into_iter().collect();
+        ignored.sort();
+        assert_eq!(ignored, vec!["new.log", "target", "target/debug/app"]);
+        assert!(ignored_paths(root, &[]).is_empty());
+    }
+}
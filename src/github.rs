@@ -57,6 +57,8 @@ impl OwnerInfo {
 pub struct GitHubCache {
     /// Map from owner string to validation result with metadata
     pub owners: HashMap<String, OwnerInfo>,
+    /// Map from team (`@org/team`) to its members as `@login`
+    pub team_members: HashMap<String, Vec<String>>,
 }
 
 /// Persistent cache stored in .codeowners-lsp/cache.json
@@ -130,6 +132,15 @@ struct GitHubUserResponse {
     company: Option<String>,
 }
 
+/// One entry of the GitHub team members API
+#[derive(Debug, Deserialize)]
+struct GitHubMemberResponse {
+    login: String,
+}
+
+/// Page size for the team members API (its maximum)
+const MEMBERS_PER_PAGE: usize = 100;
+
 /// Response from GitHub team API (subset of fields we care about)
 #[derive(Debug, Deserialize)]
 struct GitHubTeamResponse {
@@ -288,6 +299,61 @@ impl GitHubClient {
         Some(OwnerInfo::Unknown(format!("HTTP {status}")))
     }
 
+    /// Members of a team (`@org/team`) as `@login`, following pagination (cached)
+    pub async fn team_members(&self, team: &str, token: &str) -> Result<Vec<String>, String> {
+        if let Some(members) = self.cache.read().unwrap().team_members.get(team) {
+            return Ok(members.clone());
+        }
+
+        let (org, slug) = team
+            .strip_prefix('@')
+            .and_then(|t| t.split_once('/'))
+            .ok_or_else(|| format!("{} is not a team", team))?;
+
+        let mut members = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/orgs/{}/teams/{}/members?per_page={}&page={}",
+                self.base_url, org, slug, MEMBERS_PER_PAGE, page
+            );
+            let response = self
+                .http_client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("User-Agent", "codeowners-lsp")
+                .header("Accept", "application/vnd.github+json")
+                .send()
+                .await
+                .map_err(|e| format!("network error: {e}"))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(match status.as_u16() {
+                    404 => "team not found or token lacks read:org scope".to_string(),
+                    403 => "forbidden - check token permissions".to_string(),
+                    429 => "rate limit exceeded".to_string(),
+                    _ => format!("HTTP {status}"),
+                });
+            }
+            let page: Vec<GitHubMemberResponse> = response
+                .json()
+                .await
+                .map_err(|e| format!("invalid response: {e}"))?;
+            let last = page.len() < MEMBERS_PER_PAGE;
+            members.extend(page.into_iter().map(|m| format!("@{}", m.login)));
+            if last {
+                break;
+            }
+        }
+
+        self.cache
+            .write()
+            .unwrap()
+            .team_members
+            .insert(team.to_string(), members.clone());
+        Ok(members)
+    }
+
     /// Validate a GitHub user exists (returns bool for backwards compat)
     #[allow(dead_code)] // Used by CLI
     pub async fn validate_user(&self, username: &str, token: &str) -> Option<bool> {
@@ -1150,4 +1216,49 @@ mod tests {
         // 403 on team = Unknown
         assert_eq!(result, None);
     }
+
+    #[tokio::test]
+    async fn test_team_members_paginates() {
+        use wiremock::matchers::query_param;
+
+        let mock_server = MockServer::start().await;
+        let first: Vec<_> = (0..MEMBERS_PER_PAGE)
+            .map(|i| serde_json::json!({ "login": format!("user{i}") }))
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/orgs/myorg/teams/big/members"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(first))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/orgs/myorg/teams/big/members"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{ "login": "last" }])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = GitHubClient::with_base_url(&mock_server.uri());
+        let members = client
+            .team_members("@myorg/big", "fake-token")
+            .await
+            .unwrap();
+        assert_eq!(members.len(), MEMBERS_PER_PAGE + 1);
+        assert_eq!(members[0], "@user0");
+        assert_eq!(members.last().unwrap(), "@last");
+
+        // Second call is served from the cache (each mock expects one hit)
+        let cached = client
+            .team_members("@myorg/big", "fake-token")
+            .await
+            .unwrap();
+        assert_eq!(cached, members);
+
+        assert!(client.team_members("@alice", "fake-token").await.is_err());
+    }
 }
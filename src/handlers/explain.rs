@@ -0,0 +1,67 @@
+//! Explain handler - the custom `codeowners/explain` request and the
+//! "why" section of source file hovers
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::Url;
+
+use crate::ownership::RuleMatch;
+
+/// Custom request returning every rule that matches a file
+pub const EXPLAIN_REQUEST: &str = "codeowners/explain";
+
+/// Params for `codeowners/explain`: a document URI or a repo-relative path
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExplainParams {
+    pub uri: Option<Url>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainResult {
+    /// Repo-relative path that was explained
+    pub path: String,
+    /// Owners of the file (empty when unowned)
+    pub owners: Vec<String>,
+    /// Matching rules in file order; the winner is last
+    pub matches: Vec<RuleMatch>,
+}
+
+/// Markdown listing of matching rules for hovers. Losing rules are struck
+/// through; `codeowners_uri` turns line numbers into links.
+pub fn explain_markdown(matches: &[RuleMatch], codeowners_uri: Option<&Url>) -> String {
+    let mut out = String::from("**Matching rules** (last match wins):");
+    for m in matches {
+        let line = match codeowners_uri {
+            Some(uri) => format!("[line {}]({}#L{})", m.line, uri, m.line),
+            None => format!("line {}", m.line),
+        };
+        let rule = if m.owners.is_empty() {
+            format!("`{}`", m.pattern)
+        } else {
+            format!("`{}` {}", m.pattern, m.owners.join(" "))
+        };
+        if m.winner {
+            out.push_str(&format!("\n- **{}** ({}) - {}", rule, line, m.reason));
+        } else {
+            out.push_str(&format!("\n- ~~{}~~ ({}) - {}", rule, line, m.reason));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ownership::explain_ownership;
+
+    #[test]
+    fn test_explain_markdown() {
+        let matches = explain_ownership("* @default\n/src/ @org/infra\n", "src/main.rs");
+        let uri = Url::parse("file:///repo/CODEOWNERS").unwrap();
+        let md = explain_markdown(&matches, Some(&uri));
+        assert!(md.contains("- ~~`*` @default~~ ([line 1](file:///repo/CODEOWNERS#L1))"));
+        assert!(md.contains(
+            "- **`/src/` @org/infra** ([line 2](file:///repo/CODEOWNERS#L2)) - last matching rule wins"
+        ));
+    }
+}
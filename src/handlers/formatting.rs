@@ -0,0 +1,257 @@
+//! Formatting handlers - document, range and on-type formatting
+//!
+//! All three run the shared formatter over the file and turn the line diff
+//! into minimal edits, so unchanged lines (and the cursor) are left alone.
+//! Columns are in UTF-16 code units, the LSP default position encoding.
+
+use tower_lsp::lsp_types::{Position, Range, TextEdit};
+
+use super::util::utf16_len;
+use crate::formatter::{diff_lines, format_with_options, hunks, FormatOptions, Hunk, LineEndings};
+
+/// Edits that format the whole document
+pub fn document_edits(content: &str, options: &FormatOptions) -> Vec<TextEdit> {
+    edits_where(content, options, |_, _| true)
+}
+
+/// Edits that format the lines `start_line..=end_line`. Changes are computed
+/// over the whole file so alignment stays consistent with the rest of the
+/// section, but only hunks touching the range are returned.
+pub fn range_edits(
+    content: &str,
+    options: &FormatOptions,
+    start_line: u32,
+    end_line: u32,
+) -> Vec<TextEdit> {
+    let (start, end) = (start_line as usize, end_line as usize);
+    edits_where(content, options, |old_start, old_end| {
+        if old_start == old_end {
+            // Pure insertion between lines
+            old_start >= start && old_start <= end + 1
+        } else {
+            old_start <= end && old_end > start
+        }
+    })
+}
+
+/// Edits after a newline was typed at `line`: formats the section the
+/// cursor is in, touching only lines above the cursor so the fresh (still
+/// empty) line survives.
+pub fn on_type_edits(content: &str, options: &FormatOptions, line: u32) -> Vec<TextEdit> {
+    let lines: Vec<&str> = content.lines().collect();
+    let line = line as usize;
+    if line == 0 || line > lines.len() {
+        return Vec::new();
+    }
+
+    // Section around the cursor, ignoring the cursor line itself
+    let is_blank = |i: usize| lines[i].trim().is_empty();
+    let mut start = line;
+    while start > 0 && !is_blank(start - 1) {
+        start -= 1;
+    }
+    let mut end = line + 1;
+    while end < lines.len() && !is_blank(end) {
+        end += 1;
+    }
+
+    if start == line {
+        return Vec::new();
+    }
+    let section: Vec<usize> = (start..line).chain(line + 1..end).collect();
+
+    let text: String = section.iter().map(|&i| format!("{}\n", lines[i])).collect();
+    let section_options = FormatOptions {
+        line_endings: LineEndings::Lf,
+        ..options.clone()
+    };
+    let formatted = format_with_options(&text, &section_options);
+
+    // A section has no blank lines, so the formatter maps lines one-to-one
+    section
+        .iter()
+        .zip(formatted.lines())
+        .filter(|(&i, new)| i < line && lines[i] != *new)
+        .map(|(&i, new)| TextEdit {
+            range: Range {
+                start: Position {
+                    line: i as u32,
+                    character: 0,
+                },
+                end: Position {
+                    line: i as u32,
+                    character: utf16_len(lines[i]),
+                },
+            },
+            new_text: new.to_string(),
+        })
+        .collect()
+}
+
+/// Minimal line edits that turn `content` into `new_content`
+pub fn replacement_edits(content: &str, new_content: &str) -> Vec<TextEdit> {
+    line_edits(content, new_content, |_, _| true)
+}
+
+fn edits_where(
+    content: &str,
+    options: &FormatOptions,
+    keep: impl Fn(usize, usize) -> bool,
+) -> Vec<TextEdit> {
+    let formatted = format_with_options(content, options);
+    line_edits(content, &formatted, keep)
+}
+
+fn line_edits(
+    content: &str,
+    formatted: &str,
+    keep: impl Fn(usize, usize) -> bool,
+) -> Vec<TextEdit> {
+    if formatted == content {
+        return Vec::new();
+    }
+
+    // Lines keep their terminators so line-ending changes become edits too
+    let old_lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = formatted.split_inclusive('\n').collect();
+
+    hunks(&diff_lines(&old_lines, &new_lines))
+        .into_iter()
+        .flat_map(split_line_for_line)
+        .filter(|h| keep(h.old_start, h.old_end))
+        .map(|h| TextEdit {
+            range: Range {
+                start: line_start(&old_lines, h.old_start),
+                end: line_start(&old_lines, h.old_end),
+            },
+            new_text: new_lines[h.new_start..h.new_end].concat(),
+        })
+        .collect()
+}
+
+/// Split a hunk that rewrites lines one-for-one into single-line hunks, so
+/// a range only picks up the lines it covers
+fn split_line_for_line(hunk: Hunk) -> Vec<Hunk> {
+    let len = hunk.old_end - hunk.old_start;
+    if len != hunk.new_end - hunk.new_start {
+        return vec![hunk];
+    }
+    (0..len)
+        .map(|k| Hunk {
+            old_start: hunk.old_start + k,
+            old_end: hunk.old_start + k + 1,
+            new_start: hunk.new_start + k,
+            new_end: hunk.new_start + k + 1,
+        })
+        .collect()
+}
+
+/// Position of the start of line `idx`, or the end of the document when
+/// `idx` is past the last line
+fn line_start(lines: &[&str], idx: usize) -> Position {
+    match lines.last() {
+        Some(last) if idx >= lines.len() && !last.ends_with('\n') => Position {
+            line: (lines.len() - 1) as u32,
+            character: utf16_len(last),
+        },
+        _ => Position {
+            line: idx as u32,
+            character: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::util::utf16_to_byte;
+
+    /// Apply non-overlapping edits to `content`, reading columns as UTF-16
+    /// code units like an LSP client (test helper)
+    fn apply(content: &str, edits: &[TextEdit]) -> String {
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let offset = |pos: Position| -> usize {
+            let line_start: usize = lines[..pos.line as usize].iter().map(|l| l.len()).sum();
+            let column = lines
+                .get(pos.line as usize)
+                .map_or(0, |line| utf16_to_byte(line, pos.character));
+            line_start + column
+        };
+        let mut result = content.to_string();
+        for edit in edits.iter().rev() {
+            result.replace_range(
+                offset(edit.range.start)..offset(edit.range.end),
+                &edit.new_text,
+            );
+        }
+        result
+    }
+
+    #[test]
+    fn test_document_edits_reach_formatted_output() {
+        let content = "*.rs   @a\n\n\n/src/ @b\n/docs/    @c";
+        let options = FormatOptions::default();
+        let edits = document_edits(content, &options);
+        assert_eq!(
+            apply(content, &edits),
+            format_with_options(content, &options)
+        );
+        // The untouched /src/ line is not part of any edit
+        assert!(edits
+            .iter()
+            .all(|e| e.range.start.line != 3 || e.range.end.line == 3));
+    }
+
+    #[test]
+    fn test_range_edits_only_touch_range() {
+        let content = "*.rs   @a\n/src/   @b\n/docs/   @c\n";
+        let edits = range_edits(content, &FormatOptions::default(), 1, 1);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start.line, 1);
+        assert_eq!(edits[0].new_text, "/src/ @b\n");
+    }
+
+    #[test]
+    fn test_on_type_aligns_section_above_cursor() {
+        let content = "/a/ @x\n/longer/ @y\n\n/below/ @z\n";
+        let options = FormatOptions {
+            align_owners: true,
+            ..Default::default()
+        };
+        // Cursor on the empty line 2 after pressing Enter
+        let edits = on_type_edits(content, &options, 2);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start.line, 0);
+        assert_eq!(edits[0].new_text, "/a/      @x");
+    }
+
+    #[test]
+    fn test_on_type_keeps_lines_below_cursor() {
+        let content = "/a/   @x\n\n/b/   @y\n";
+        // Cursor on line 1 splitting the section: only line 0 changes
+        let edits = on_type_edits(content, &FormatOptions::default(), 1);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start.line, 0);
+        assert_eq!(edits[0].new_text, "/a/ @x");
+    }
+
+    #[test]
+    fn test_edit_columns_count_utf16_units() {
+        // 🚀 is one char but two UTF-16 code units
+        let content = "/a/   @x # 🚀 launch\n/b/   @y # 🚀";
+        let options = FormatOptions::default();
+        let edits = document_edits(content, &options);
+        let last = edits.last().unwrap();
+        assert_eq!(last.range.end.line, 1);
+        assert_eq!(last.range.end.character, 13);
+        assert_eq!(
+            apply(content, &edits),
+            format_with_options(content, &options)
+        );
+
+        let content = "/a/   @x # 🚀\n\n";
+        let edits = on_type_edits(content, &options, 1);
+        assert_eq!(edits[0].range.end.character, 13);
+        assert_eq!(apply(content, &edits), "/a/ @x # 🚀\n\n");
+    }
+}
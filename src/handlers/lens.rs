@@ -2,10 +2,12 @@
 
 use tower_lsp::lsp_types::*;
 
+use super::matches::{matches_uri, SHOW_MATCHES_COMMAND};
 use crate::file_cache::FileCache;
 use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
 
-/// Generate code lenses showing file counts for each rule
+/// Generate code lenses showing file counts for each rule. Clicking a lens
+/// opens the rule's matches document.
 pub fn code_lenses(content: &str, file_cache: &FileCache) -> Vec<CodeLens> {
     let lines = parse_codeowners_file_with_positions(content);
     let mut lenses = Vec::new();
@@ -38,8 +40,10 @@ pub fn code_lenses(content: &str, file_cache: &FileCache) -> Vec<CodeLens> {
                 },
                 command: Some(Command {
                     title,
-                    command: String::new(),
-                    arguments: None,
+                    command: SHOW_MATCHES_COMMAND.to_string(),
+                    arguments: Some(vec![serde_json::Value::String(
+                        matches_uri(line.line_number, pattern).to_string(),
+                    )]),
                 }),
                 data: None,
             });
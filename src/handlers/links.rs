@@ -0,0 +1,187 @@
+//! Document link handler - clickable patterns and owners in CODEOWNERS
+//!
+//! Anchored paths open the file or directory in the workspace, other
+//! patterns open the matches document, and owners open their GitHub
+//! profile or team page.
+
+use std::path::Path;
+
+use tower_lsp::lsp_types::{DocumentLink, Position, Range, Url};
+
+use super::matches::matches_uri;
+use crate::file_cache::FileCache;
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
+
+/// Profile or team page of a GitHub owner (`@user` or `@org/team`).
+/// Emails and malformed owners have no page.
+pub fn owner_url(owner: &str, base_url: &str) -> Option<String> {
+    let name = owner.strip_prefix('@')?;
+    let base_url = base_url.trim_end_matches('/');
+    match name.split_once('/') {
+        Some((org, team)) if !org.is_empty() && !team.is_empty() => {
+            Some(format!("{}/orgs/{}/teams/{}", base_url, org, team))
+        }
+        Some(_) => None,
+        None if !name.is_empty() => Some(format!("{}/{}", base_url, name)),
+        None => None,
+    }
+}
+
+/// Anchored literal path of a pattern (`/src/`, `/Makefile`), if it has one
+fn workspace_path(pattern: &str) -> Option<&str> {
+    let path = pattern.strip_prefix('/')?.trim_end_matches('/');
+    let is_glob = path.contains(['*', '?', '[']);
+    (!path.is_empty() && !is_glob).then_some(path)
+}
+
+/// Links for every pattern and owner. Workspace paths are only linked when
+/// `file_cache` knows them; without a cache they are linked unconditionally.
+pub fn document_links(
+    content: &str,
+    root: &Path,
+    file_cache: Option<&FileCache>,
+    github_url: &str,
+) -> Vec<DocumentLink> {
+    let text_lines: Vec<&str> = content.lines().collect();
+    let mut links = Vec::new();
+
+    for parsed in parse_codeowners_file_with_positions(content) {
+        let CodeownersLine::Rule { pattern, .. } = &parsed.content else {
+            continue;
+        };
+        let line = parsed.line_number;
+        let range = |start: u32, end: u32| Range {
+            start: Position {
+                line,
+                character: start,
+            },
+            end: Position {
+                line,
+                character: end,
+            },
+        };
+
+        // Pattern
+        let pattern_range = range(parsed.pattern_start, parsed.pattern_end);
+        match workspace_path(pattern) {
+            Some(path) => {
+                let known = file_cache.is_none_or(|cache| cache.has_matches(pattern));
+                if let Some(target) = Url::from_file_path(root.join(path)).ok().filter(|_| known) {
+                    links.push(DocumentLink {
+                        range: pattern_range,
+                        target: Some(target),
+                        tooltip: Some(format!("Open {}", path)),
+                        data: None,
+                    });
+                }
+            }
+            None => links.push(DocumentLink {
+                range: pattern_range,
+                target: Some(matches_uri(line, pattern)),
+                tooltip: Some("Show matching files".to_string()),
+                data: None,
+            }),
+        }
+
+        // Owners, scanned from the text so duplicates get their own ranges
+        let Some(text) = text_lines.get(line as usize) else {
+            continue;
+        };
+        let owners_end = parsed.comment_start.unwrap_or(text.len() as u32) as usize;
+        let owners_start = (parsed.owners_start as usize).min(owners_end);
+        let Some(owners_text) = text.get(owners_start..owners_end) else {
+            continue;
+        };
+        let mut offset = owners_start;
+        for word in owners_text.split_inclusive(char::is_whitespace) {
+            let owner = word.trim_end();
+            if let Some(target) = owner_url(owner, github_url).and_then(|u| Url::parse(&u).ok()) {
+                links.push(DocumentLink {
+                    range: range(offset as u32, (offset + owner.len()) as u32),
+                    target: Some(target),
+                    tooltip: Some(if owner.contains('/') {
+                        "Open team page".to_string()
+                    } else {
+                        "Open GitHub profile".to_string()
+                    }),
+                    data: None,
+                });
+            }
+            offset += word.len();
+        }
+    }
+
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::DEFAULT_GITHUB_URL;
+
+    fn targets(links: &[DocumentLink]) -> Vec<(u32, u32, String)> {
+        links
+            .iter()
+            .map(|l| {
+                (
+                    l.range.start.line,
+                    l.range.start.character,
+                    l.target.as_ref().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_owner_url() {
+        assert_eq!(
+            owner_url("@alice", DEFAULT_GITHUB_URL).as_deref(),
+            Some("https://github.com/alice")
+        );
+        assert_eq!(
+            owner_url("@org/team", "https://ghe.example.com/").as_deref(),
+            Some("https://ghe.example.com/orgs/org/teams/team")
+        );
+        assert_eq!(owner_url("dev@example.com", DEFAULT_GITHUB_URL), None);
+        assert_eq!(owner_url("@", DEFAULT_GITHUB_URL), None);
+        assert_eq!(owner_url("@org/", DEFAULT_GITHUB_URL), None);
+    }
+
+    #[test]
+    fn test_document_links() {
+        let cache = FileCache::from_files(vec!["src/main.rs".to_string()]);
+        let content = "/src/ @alice @org/team # infra\n*.md  dev@example.com\n/gone/ @bob\n";
+        let links = document_links(
+            content,
+            Path::new("/repo"),
+            Some(&cache),
+            DEFAULT_GITHUB_URL,
+        );
+
+        assert_eq!(
+            targets(&links),
+            vec![
+                (0, 0, "file:///repo/src".to_string()),
+                (0, 6, "https://github.com/alice".to_string()),
+                (0, 13, "https://github.com/orgs/org/teams/team".to_string()),
+                (1, 0, matches_uri(1, "*.md").to_string()),
+                (2, 7, "https://github.com/bob".to_string()),
+            ]
+        );
+        assert_eq!(links[2].range.end.character, 22);
+    }
+
+    #[test]
+    fn test_document_links_without_cache() {
+        let links = document_links(
+            "/gone/ @bob\n",
+            Path::new("/repo"),
+            None,
+            DEFAULT_GITHUB_URL,
+        );
+        assert_eq!(
+            links[0].target.as_ref().unwrap().as_str(),
+            "file:///repo/gone"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Matches document - every file a rule matches, split into files it wins
+//! and files won by later rules
+//!
+//! Served as a virtual `codeowners-matches:` document through the custom
+//! `codeowners/textDocumentContent` request (same shape as LSP 3.18's
+//! `workspace/textDocumentContent`), and linked from code lenses and hover.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::Url;
+
+use crate::file_cache::FileCache;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine, ParsedLine};
+
+/// URI scheme of matches documents
+pub const MATCHES_SCHEME: &str = "codeowners-matches";
+
+/// Custom request serving virtual document contents
+pub const TEXT_DOCUMENT_CONTENT_REQUEST: &str = "codeowners/textDocumentContent";
+
+/// Command that opens the matches document for a rule
+pub const SHOW_MATCHES_COMMAND: &str = "codeowners.showMatches";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TextDocumentContentParams {
+    pub uri: Url,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TextDocumentContentResult {
+    pub text: String,
+}
+
+/// URI of the matches document for the rule on `line` (0-indexed)
+pub fn matches_uri(line: u32, pattern: &str) -> Url {
+    let mut uri = Url::parse(&format!("{}:/CODEOWNERS/L{}.md", MATCHES_SCHEME, line + 1))
+        .expect("static URI is valid");
+    uri.query_pairs_mut().append_pair("pattern", pattern);
+    uri
+}
+
+/// Line (0-indexed) and pattern encoded in a matches URI
+pub fn parse_matches_uri(uri: &Url) -> Option<(u32, String)> {
+    if uri.scheme() != MATCHES_SCHEME {
+        return None;
+    }
+    let line: u32 = uri
+        .path()
+        .strip_prefix("/CODEOWNERS/L")?
+        .strip_suffix(".md")?
+        .parse()
+        .ok()?;
+    let pattern = uri
+        .query_pairs()
+        .find(|(key, _)| key == "pattern")
+        .map(|(_, value)| value.into_owned())?;
+    Some((line.checked_sub(1)?, pattern))
+}
+
+/// Render the matches document as markdown. The rule is looked up by line
+/// first and by pattern if the file was edited since the link was made.
+pub fn matches_document(
+    content: &str,
+    line: u32,
+    pattern: &str,
+    file_cache: &FileCache,
+    root: &Path,
+) -> Option<String> {
+    let lines = parse_codeowners_file_with_positions(content);
+    let is_rule = |l: &&ParsedLine, wanted_line: Option<u32>| {
+        matches!(&l.content, CodeownersLine::Rule { pattern: p, .. }
+            if p == pattern && wanted_line.is_none_or(|n| n == l.line_number))
+    };
+    let rule = lines
+        .iter()
+        .find(|l| is_rule(l, Some(line)))
+        .or_else(|| lines.iter().find(|l| is_rule(l, None)))?;
+    let CodeownersLine::Rule { pattern, owners } = &rule.content else {
+        return None;
+    };
+
+    let mut won = Vec::new();
+    let mut shadowed = Vec::new();
+    for file in file_cache.get_matches(pattern) {
+        match check_file_ownership_parsed(&lines, file) {
+            Some(winner) if winner.line_number != rule.line_number => shadowed.push((file, winner)),
+            _ => won.push(file),
+        }
+    }
+
+    let link = |file: &str| match Url::from_file_path(root.join(file)) {
+        Ok(url) => format!("[{}]({})", file, url),
+        Err(_) => format!("`{}`", file),
+    };
+
+    let mut out = format!(
+        "# `{}` (line {})\n\nOwners: {}\n\nMatches {} {}.\n",
+        pattern,
+        rule.line_number + 1,
+        if owners.is_empty() {
+            "*none*".to_string()
+        } else {
+            owners.join(" ")
+        },
+        won.len() + shadowed.len(),
+        if won.len() + shadowed.len() == 1 {
+            "file"
+        } else {
+            "files"
+        }
+    );
+
+    out.push_str(&format!("\n## Owned by this rule ({})\n\n", won.len()));
+    if won.is_empty() {
+        out.push_str("*None - every match is taken by a later rule*\n");
+    }
+    for file in &won {
+        out.push_str(&format!("- {}\n", link(file)));
+    }
+
+    out.push_str(&format!(
+        "\n## Shadowed by later rules ({})\n\n",
+        shadowed.len()
+    ));
+    if shadowed.is_empty() {
+        out.push_str("*None*\n");
+    }
+    for (file, winner) in &shadowed {
+        out.push_str(&format!(
+            "- {} → line {} `{}` {}\n",
+            link(file),
+            winner.line_number + 1,
+            winner.pattern,
+            winner.owners.join(" ")
+        ));
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_uri_round_trip() {
+        let uri = matches_uri(4, "/src/**/*.rs?");
+        assert_eq!(uri.scheme(), MATCHES_SCHEME);
+        assert_eq!(
+            parse_matches_uri(&uri),
+            Some((4, "/src/**/*.rs?".to_string()))
+        );
+        assert_eq!(
+            parse_matches_uri(&Url::parse("file:///CODEOWNERS").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_matches_document_splits_won_and_shadowed() {
+        let cache = FileCache::from_files(vec![
+            "src/main.rs".to_string(),
+            "src/auth/login.rs".to_string(),
+            "docs/guide.md".to_string(),
+        ]);
+        let content = "/src/ @backend\n/src/auth/ @security\n";
+        let doc = matches_document(content, 0, "/src/", &cache, Path::new("/repo")).unwrap();
+
+        assert!(doc.contains("## Owned by this rule (1)"));
+        assert!(doc.contains("- [src/main.rs](file:///repo/src/main.rs)"));
+        assert!(doc.contains("## Shadowed by later rules (1)"));
+        assert!(doc.contains(
+            "- [src/auth/login.rs](file:///repo/src/auth/login.rs) → line 2 `/src/auth/` @security"
+        ));
+        assert!(!doc.contains("guide.md"));
+    }
+
+    #[test]
+    fn test_matches_document_follows_moved_rule() {
+        let cache = FileCache::from_files(vec!["docs/guide.md".to_string()]);
+        // Link was created when /docs/ was on line 0
+        let content = "# header\n/docs/ @docs\n";
+        let doc = matches_document(content, 0, "/docs/", &cache, Path::new("/repo")).unwrap();
+        assert!(doc.contains("(line 2)"));
+        assert!(matches_document(content, 0, "/gone/", &cache, Path::new("/repo")).is_none());
+    }
+}
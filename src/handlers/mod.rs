@@ -3,9 +3,17 @@
 //! This module contains the logic for LSP requests, keeping main.rs focused
 //! on the Backend struct and thin handler delegation.
 
+pub mod explain;
+pub mod formatting;
 pub mod lens;
 pub mod linked;
+pub mod links;
+pub mod matches;
 pub mod navigation;
+pub mod owner_picker;
+pub mod ownership_tree;
+pub mod previews;
+pub mod progress;
 pub mod selection;
 pub mod semantic;
 pub mod signature;
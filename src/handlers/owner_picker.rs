@@ -0,0 +1,253 @@
+//! Owner picker - ranked owner choices for the custom take-ownership commands
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::Position;
+
+use super::util::find_nth_owner_position;
+use crate::formatter::uses_crlf;
+use crate::parser::{
+    common_prefix_depth, find_insertion_point_with_owner, get_directory_prefix,
+    parse_codeowners_file, parse_codeowners_file_with_positions, CodeownersLine,
+};
+use crate::pattern::pattern_matches;
+
+/// Maximum number of choices offered in a `window/showMessageRequest` prompt
+pub const MAX_CHOICES: usize = 8;
+
+/// Rank candidate owners for `pattern`, best first.
+///
+/// Order: owners of the nearest sibling directories (deepest shared path
+/// first, later rules breaking ties), then the configured individual and
+/// team, then other CODEOWNERS owners by how often they appear, then owners
+/// only known from the GitHub cache.
+pub fn owner_choices(
+    content: &str,
+    pattern: &str,
+    cached_owners: &[String],
+    individual: Option<&str>,
+    team: Option<&str>,
+) -> Vec<String> {
+    let pattern_dir = get_directory_prefix(pattern);
+
+    // owner -> (best proximity, last line seen, occurrences)
+    let mut stats: HashMap<&str, (usize, u32, usize)> = HashMap::new();
+    let lines = parse_codeowners_file_with_positions(content);
+    for line in &lines {
+        if let CodeownersLine::Rule {
+            pattern: rule_pattern,
+            owners,
+        } = &line.content
+        {
+            let depth = common_prefix_depth(&pattern_dir, &get_directory_prefix(rule_pattern));
+            for owner in owners {
+                let entry = stats.entry(owner.as_str()).or_insert((0, 0, 0));
+                if depth >= entry.0 {
+                    entry.0 = depth;
+                    entry.1 = line.line_number;
+                }
+                entry.2 += 1;
+            }
+        }
+    }
+
+    let mut choices: Vec<String> = Vec::new();
+    let mut push = |owner: &str| {
+        if !choices.iter().any(|c| c == owner) {
+            choices.push(owner.to_string());
+        }
+    };
+
+    let mut nearby: Vec<(&str, usize, u32)> = stats
+        .iter()
+        .filter(|(_, (depth, _, _))| *depth > 0)
+        .map(|(owner, (depth, line, _))| (*owner, *depth, *line))
+        .collect();
+    nearby.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.2.cmp(&a.2)));
+    for (owner, _, _) in nearby {
+        push(owner);
+    }
+
+    for owner in [individual, team].into_iter().flatten() {
+        push(owner);
+    }
+
+    let mut by_frequency: Vec<(&str, usize)> = stats
+        .iter()
+        .map(|(owner, (_, _, count))| (*owner, *count))
+        .collect();
+    by_frequency.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    for (owner, _) in by_frequency {
+        push(owner);
+    }
+
+    let mut cached: Vec<&String> = cached_owners.iter().collect();
+    cached.sort();
+    for owner in cached {
+        push(owner);
+    }
+
+    choices
+}
+
+/// Where a new rule for `pattern` would go (the line `ownership::add_rule`
+/// inserts it at), and `content` with an empty line opened there so the
+/// rule doesn't run into the line after it
+pub fn rule_insertion_position(
+    content: &str,
+    pattern: &str,
+    owner: Option<&str>,
+) -> (String, Position) {
+    let line = find_insertion_point_with_owner(&parse_codeowners_file(content), pattern, owner);
+    let position = Position {
+        line: line as u32,
+        character: 0,
+    };
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    // The last line of a newline-terminated file is already empty
+    if line == lines.len() && (content.is_empty() || content.ends_with('\n')) {
+        return (content.to_string(), position);
+    }
+    lines.insert(line, "");
+    let newline = if uses_crlf(content) { "\r\n" } else { "\n" };
+    let mut opened = lines.join(newline);
+    if content.ends_with('\n') {
+        opened.push_str(newline);
+    }
+    (opened, position)
+}
+
+/// Where another owner of the rule owning `pattern` would go: just after
+/// that rule's last owner (the rule `ownership::add_owner_to_matching_rule`
+/// edits)
+pub fn owner_append_position(content: &str, pattern: &str) -> Option<Position> {
+    let relative_path = pattern.trim_start_matches('/');
+    let lines = parse_codeowners_file_with_positions(content);
+    let (line, owners) = lines.iter().rev().find_map(|line| match &line.content {
+        CodeownersLine::Rule {
+            pattern: rule_pattern,
+            owners,
+        } if pattern_matches(rule_pattern, relative_path) => Some((line.line_number, owners)),
+        _ => None,
+    })?;
+
+    let text = content.lines().nth(line as usize)?;
+    let end = match owners.last() {
+        Some(last) => {
+            let n = owners.iter().filter(|o| *o == last).count() - 1;
+            find_nth_owner_position(text, last, n)? + last.len()
+        }
+        None => text.trim_end().len(),
+    };
+    Some(Position {
+        line,
+        character: text[..end].encode_utf16().count() as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+* @org/default
+/docs/ @org/docs
+/src/api/ @org/api @alice
+/src/api/v2/ @org/api-v2
+/src/web/ @org/web
+";
+
+    #[test]
+    fn test_nearest_sibling_owners_first() {
+        let choices = owner_choices(CONTENT, "/src/api/v2/handlers.rs", &[], None, None);
+        assert_eq!(choices[0], "@org/api-v2");
+        // Shares /src/api with the file
+        assert!(choices[1] == "@org/api" || choices[1] == "@alice");
+        // Only shares /src with the file
+        assert_eq!(choices[3], "@org/web");
+    }
+
+    #[test]
+    fn test_configured_owners_before_unrelated_ones() {
+        let choices = owner_choices(
+            CONTENT,
+            "/src/web/app.ts",
+            &[],
+            Some("@me"),
+            Some("@org/mine"),
+        );
+        let me = choices.iter().position(|c| c == "@me").unwrap();
+        let docs = choices.iter().position(|c| c == "@org/docs").unwrap();
+        assert_eq!(choices[0], "@org/web");
+        assert!(me < docs);
+        assert_eq!(choices[me + 1], "@org/mine");
+    }
+
+    #[test]
+    fn test_cached_owners_last_and_deduped() {
+        let cached = vec!["@zed".to_string(), "@org/docs".to_string()];
+        let choices = owner_choices(CONTENT, "/README.md", &cached, None, None);
+        assert_eq!(choices.last().unwrap(), "@zed");
+        assert_eq!(choices.iter().filter(|c| *c == "@org/docs").count(), 1);
+    }
+
+    #[test]
+    fn test_empty_content_uses_settings_and_cache() {
+        let cached = vec!["@bob".to_string()];
+        let choices = owner_choices("", "/src/main.rs", &cached, Some("@me"), None);
+        assert_eq!(choices, vec!["@me", "@bob"]);
+    }
+
+    #[test]
+    fn test_owner_append_position_after_last_owner_of_winning_rule() {
+        // The later rule wins for files under /src/api/
+        let content = "/src/ @org/api\n/src/api/ @org/api @alice # team\n";
+        assert_eq!(
+            owner_append_position(content, "/src/api/mod.rs"),
+            Some(Position {
+                line: 1,
+                character: 25
+            })
+        );
+        assert_eq!(
+            owner_append_position(content, "/src/lib.rs"),
+            Some(Position {
+                line: 0,
+                character: 14
+            })
+        );
+        assert_eq!(owner_append_position(content, "/docs/a.md"), None);
+    }
+
+    #[test]
+    fn test_rule_insertion_position_before_catch_all() {
+        let content = "/docs/ @org/docs\n* @org/default\n";
+        assert_eq!(
+            rule_insertion_position(content, "/docs/guide.md", None),
+            (
+                "/docs/ @org/docs\n\n* @org/default\n".to_string(),
+                Position {
+                    line: 1,
+                    character: 0
+                }
+            )
+        );
+
+        // Same spot as `add_rule`, which also groups rules by owner
+        let content = "/src/ @org/backend\n/docs/ @org/docs\n";
+        let (_, position) = rule_insertion_position(content, "/lib/", Some("@org/backend"));
+        let added = crate::ownership::add_rule(content, "/lib/", "@org/backend");
+        assert_eq!(
+            added.lines().nth(position.line as usize),
+            Some("/lib/ @org/backend")
+        );
+
+        // Appending to a newline-terminated file needs no new line
+        let (opened, position) = rule_insertion_position("/docs/ @org/docs\n", "/lib/", None);
+        assert_eq!(opened, "/docs/ @org/docs\n");
+        assert_eq!(position.line, 1);
+        let (opened, _) = rule_insertion_position("/docs/ @org/docs", "/lib/", None);
+        assert_eq!(opened, "/docs/ @org/docs\n");
+    }
+}
@@ -0,0 +1,415 @@
+//! Ownership map - the custom `codeowners/ownershipTree` and
+//! `codeowners/ownersForFiles` requests used by editor tree views
+//!
+//! Both are answered from the in-memory [`FileCache`]: rules are parsed once
+//! per request and every cached file is resolved in parallel with
+//! [`check_file_ownership_parsed`], so the editor never walks the disk and
+//! sees the same winner as every other command.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::file_cache::FileCache;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::{parse_codeowners_file_with_positions, ParsedLine};
+
+/// Custom request returning the directory tree with effective owners
+pub const OWNERSHIP_TREE_REQUEST: &str = "codeowners/ownershipTree";
+
+/// Custom request resolving owners for many paths at once
+pub const OWNERS_FOR_FILES_REQUEST: &str = "codeowners/ownersForFiles";
+
+/// Params for `codeowners/ownershipTree`
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct OwnershipTreeParams {
+    /// Repo-relative directory to root the tree at (default: repo root)
+    pub root: Option<String>,
+}
+
+/// Params for `codeowners/ownersForFiles`: repo-relative files or directories
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OwnersForFilesParams {
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OwnersForFilesResult {
+    /// One entry per requested path, in request order
+    pub results: Vec<OwnersForPath>,
+}
+
+/// Ownership of a whole subtree (or a single file)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OwnershipStatus {
+    /// Every file has the same, non-empty owner set
+    Owned,
+    /// No file has owners
+    Unowned,
+    /// Files have different owner sets, or some are unowned
+    Mixed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    File,
+    Directory,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeNode {
+    pub name: String,
+    /// Repo-relative path ("" for the repo root)
+    pub path: String,
+    pub kind: NodeKind,
+    pub status: OwnershipStatus,
+    /// Owners shared by every file below; empty unless `status` is owned
+    pub owners: Vec<String>,
+    pub file_count: usize,
+    pub unowned_count: usize,
+    /// True when the children were dropped because the whole subtree shares
+    /// one owner set
+    pub collapsed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnersForPath {
+    pub path: String,
+    /// None when the path is neither a cached file nor a directory of them
+    pub kind: Option<NodeKind>,
+    pub status: OwnershipStatus,
+    pub owners: Vec<String>,
+    pub file_count: usize,
+    pub unowned_count: usize,
+    /// Pattern and line (1-based) of the winning rule, for files only
+    pub pattern: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// Owner set used to compare files; order and duplicates don't matter
+fn owner_set(lines: &[ParsedLine], path: &str) -> Vec<String> {
+    let mut owners = check_file_ownership_parsed(lines, path)
+        .map(|r| r.owners)
+        .unwrap_or_default();
+    owners.sort();
+    owners.dedup();
+    owners
+}
+
+/// Running summary of the files below a node
+#[derive(Default)]
+struct Summary {
+    file_count: usize,
+    unowned_count: usize,
+    /// The single owner set shared so far; None once files disagree
+    shared: Option<Vec<String>>,
+}
+
+impl Summary {
+    fn add(&mut self, owners: &[String], count: usize, uniform: bool) {
+        if self.file_count == 0 {
+            self.shared = uniform.then(|| owners.to_vec());
+        } else if !uniform || self.shared.as_deref() != Some(owners) {
+            self.shared = None;
+        }
+        self.file_count += count;
+    }
+
+    fn status(&self) -> OwnershipStatus {
+        match &self.shared {
+            Some(owners) if owners.is_empty() => OwnershipStatus::Unowned,
+            Some(_) => OwnershipStatus::Owned,
+            None => OwnershipStatus::Mixed,
+        }
+    }
+
+    fn owners(&self) -> Vec<String> {
+        self.shared.clone().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct DirBuilder {
+    dirs: BTreeMap<String, DirBuilder>,
+    files: BTreeMap<String, Vec<String>>,
+}
+
+impl DirBuilder {
+    fn insert(&mut self, rel: &str, owners: Vec<String>) {
+        match rel.split_once('/') {
+            Some((dir, rest)) => self
+                .dirs
+                .entry(dir.to_string())
+                .or_default()
+                .insert(rest, owners),
+            None => {
+                self.files.insert(rel.to_string(), owners);
+            }
+        }
+    }
+
+    fn build(self, name: String, path: String) -> TreeNode {
+        let join = |child: &str| {
+            if path.is_empty() {
+                child.to_string()
+            } else {
+                format!("{}/{}", path, child)
+            }
+        };
+
+        let mut children = Vec::new();
+        for (dir_name, dir) in self.dirs {
+            let child_path = join(&dir_name);
+            children.push(dir.build(dir_name, child_path));
+        }
+        for (file_name, owners) in self.files {
+            let status = if owners.is_empty() {
+                OwnershipStatus::Unowned
+            } else {
+                OwnershipStatus::Owned
+            };
+            children.push(TreeNode {
+                path: join(&file_name),
+                name: file_name,
+                kind: NodeKind::File,
+                status,
+                unowned_count: usize::from(owners.is_empty()),
+                owners,
+                file_count: 1,
+                collapsed: false,
+                children: Vec::new(),
+            });
+        }
+
+        let mut summary = Summary::default();
+        for child in &children {
+            summary.add(
+                &child.owners,
+                child.file_count,
+                child.status != OwnershipStatus::Mixed,
+            );
+            summary.unowned_count += child.unowned_count;
+        }
+
+        let status = summary.status();
+        let collapsed = status != OwnershipStatus::Mixed && !children.is_empty();
+        TreeNode {
+            name,
+            path,
+            kind: NodeKind::Directory,
+            status,
+            owners: summary.owners(),
+            file_count: summary.file_count,
+            unowned_count: summary.unowned_count,
+            collapsed,
+            children: if collapsed { Vec::new() } else { children },
+        }
+    }
+}
+
+fn normalize_dir(path: &str) -> String {
+    path.trim_matches('/').to_string()
+}
+
+fn is_below(file: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || file
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Build the ownership tree for the cached files under `root`. Directories
+/// whose files all share one owner set (or are all unowned) are collapsed.
+pub fn ownership_tree(content: &str, cache: &FileCache, root: Option<&str>) -> TreeNode {
+    let lines = parse_codeowners_file_with_positions(content);
+    let root = root.map(normalize_dir).unwrap_or_default();
+
+    let resolved: Vec<(&String, Vec<String>)> = cache
+        .all_files()
+        .par_iter()
+        .filter(|file| is_below(file, &root))
+        .map(|file| (file, owner_set(&lines, file)))
+        .collect();
+
+    let mut builder = DirBuilder::default();
+    for (file, owners) in resolved {
+        let rel = if root.is_empty() {
+            file.as_str()
+        } else {
+            &file[root.len() + 1..]
+        };
+        builder.insert(rel, owners);
+    }
+
+    let name = root.rsplit('/').next().unwrap_or_default().to_string();
+    builder.build(name, root)
+}
+
+/// Resolve owners for each path. Files are looked up directly; directories
+/// are summarized over the cached files below them.
+pub fn owners_for_files(content: &str, cache: &FileCache, paths: &[String]) -> Vec<OwnersForPath> {
+    let lines = parse_codeowners_file_with_positions(content);
+    let wanted: Vec<String> = paths.iter().map(|p| normalize_dir(p)).collect();
+    let files: HashSet<&str> = cache.all_files().iter().map(|f| f.as_str()).collect();
+
+    // One pass over the cache summarizes every requested directory
+    let mut summaries: HashMap<&str, Summary> = HashMap::new();
+    let dirs: Vec<&str> = wanted
+        .iter()
+        .filter(|p| !files.contains(p.as_str()))
+        .map(|p| p.as_str())
+        .collect();
+    if !dirs.is_empty() {
+        let below: Vec<(Vec<String>, Vec<&str>)> = cache
+            .all_files()
+            .par_iter()
+            .filter_map(|file| {
+                let matching: Vec<&str> =
+                    dirs.iter().copied().filter(|d| is_below(file, d)).collect();
+                (!matching.is_empty()).then(|| (owner_set(&lines, file), matching))
+            })
+            .collect();
+        for (owners, matching) in below {
+            for dir in matching {
+                let summary = summaries.entry(dir).or_default();
+                summary.add(&owners, 1, true);
+                summary.unowned_count += usize::from(owners.is_empty());
+            }
+        }
+    }
+
+    wanted
+        .iter()
+        .map(|path| {
+            if let Some(summary) = summaries.get(path.as_str()) {
+                return OwnersForPath {
+                    path: path.clone(),
+                    kind: Some(NodeKind::Directory),
+                    status: summary.status(),
+                    owners: summary.owners(),
+                    file_count: summary.file_count,
+                    unowned_count: summary.unowned_count,
+                    pattern: None,
+                    line: None,
+                };
+            }
+
+            // Files that aren't cached yet (e.g. new, unsaved) are still
+            // resolved by pattern
+            let is_file = files.contains(path.as_str());
+            let rule = check_file_ownership_parsed(&lines, path);
+            let owners = rule.as_ref().map(|r| r.owners.clone()).unwrap_or_default();
+            OwnersForPath {
+                path: path.clone(),
+                kind: is_file.then_some(NodeKind::File),
+                status: if owners.is_empty() {
+                    OwnershipStatus::Unowned
+                } else {
+                    OwnershipStatus::Owned
+                },
+                file_count: usize::from(is_file),
+                unowned_count: usize::from(is_file && owners.is_empty()),
+                owners,
+                line: rule.as_ref().map(|r| r.line_number + 1),
+                pattern: rule.map(|r| r.pattern),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> FileCache {
+        FileCache::from_files(vec![
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "src/auth/login.rs".to_string(),
+            "docs/guide.md".to_string(),
+            "docs/api/index.md".to_string(),
+            "README.md".to_string(),
+        ])
+    }
+
+    const CONTENT: &str = "/src/ @backend\n/src/auth/ @security\n/docs/ @docs @writers\n";
+
+    fn child<'a>(node: &'a TreeNode, name: &str) -> &'a TreeNode {
+        node.children.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_tree_collapses_uniform_subtrees() {
+        let tree = ownership_tree(CONTENT, &cache(), None);
+        assert_eq!(tree.status, OwnershipStatus::Mixed);
+        assert_eq!(tree.file_count, 6);
+        assert_eq!(tree.unowned_count, 1);
+
+        let docs = child(&tree, "docs");
+        assert_eq!(docs.status, OwnershipStatus::Owned);
+        assert_eq!(docs.owners, vec!["@docs", "@writers"]);
+        assert!(docs.collapsed);
+        assert!(docs.children.is_empty());
+        assert_eq!(docs.file_count, 2);
+
+        let src = child(&tree, "src");
+        assert_eq!(src.status, OwnershipStatus::Mixed);
+        assert!(src.owners.is_empty());
+        assert!(!src.collapsed);
+        assert_eq!(child(src, "auth").owners, vec!["@security"]);
+        assert_eq!(child(src, "main.rs").path, "src/main.rs");
+
+        let readme = child(&tree, "README.md");
+        assert_eq!(readme.kind, NodeKind::File);
+        assert_eq!(readme.status, OwnershipStatus::Unowned);
+    }
+
+    #[test]
+    fn test_tree_with_root() {
+        let tree = ownership_tree(CONTENT, &cache(), Some("/src/auth/"));
+        assert_eq!(tree.name, "auth");
+        assert_eq!(tree.path, "src/auth");
+        assert_eq!(tree.status, OwnershipStatus::Owned);
+        assert_eq!(tree.file_count, 1);
+    }
+
+    #[test]
+    fn test_rule_without_owners_is_unowned() {
+        let tree = ownership_tree("* @all\n/docs/\n", &cache(), None);
+        let docs = child(&tree, "docs");
+        assert_eq!(docs.status, OwnershipStatus::Unowned);
+        assert_eq!(docs.unowned_count, 2);
+        assert!(docs.collapsed);
+    }
+
+    #[test]
+    fn test_owners_for_files() {
+        let paths = vec![
+            "src/auth/login.rs".to_string(),
+            "src/".to_string(),
+            "new/file.rs".to_string(),
+        ];
+        let results = owners_for_files(CONTENT, &cache(), &paths);
+
+        assert_eq!(results[0].kind, Some(NodeKind::File));
+        assert_eq!(results[0].owners, vec!["@security"]);
+        assert_eq!(results[0].pattern.as_deref(), Some("/src/auth/"));
+        assert_eq!(results[0].line, Some(2));
+
+        assert_eq!(results[1].path, "src");
+        assert_eq!(results[1].kind, Some(NodeKind::Directory));
+        assert_eq!(results[1].status, OwnershipStatus::Mixed);
+        assert_eq!(results[1].file_count, 3);
+
+        assert_eq!(results[2].kind, None);
+        assert_eq!(results[2].status, OwnershipStatus::Unowned);
+        assert_eq!(results[2].file_count, 0);
+    }
+}
@@ -0,0 +1,110 @@
+//! Quickfix previews - the ownership impact of CODEOWNERS quickfixes and
+//! the "Move rule" fixes that are safe to offer
+//!
+//! Computing an impact means matching changed rules against the whole
+//! repository, which is too slow for `textDocument/codeAction` (sent on
+//! every cursor move). Previews are computed in the background once per
+//! version of the content, and code actions read them when the content
+//! still matches.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::NumberOrString;
+
+use crate::diagnostics::{codes, compute_diagnostics_sync, DiagnosticConfig};
+use crate::file_cache::FileCache;
+use crate::ownership::{apply_safe_fixes, ownership_impact, remove_line, OwnershipImpact};
+use crate::rule_move::{move_below_shadowing, MoveResult};
+
+/// Ownership impact of the quickfixes for one version of CODEOWNERS
+#[derive(Debug)]
+pub struct QuickfixPreviews {
+    /// The content the previews were computed for
+    pub content: String,
+    /// Impact of "Remove shadowed rule", by line (0-indexed)
+    pub removals: HashMap<u32, OwnershipImpact>,
+    /// "Move rule" results for shadowed rules that can be moved safely, by line
+    pub moves: HashMap<u32, MoveResult>,
+    /// Impact of "Fix all safe issues", when there is anything to fix
+    pub fix_all: Option<OwnershipImpact>,
+}
+
+impl QuickfixPreviews {
+    /// Previews for `content`, if they were computed for it
+    pub fn for_content<'a>(previews: Option<&'a Self>, content: &str) -> Option<&'a Self> {
+        previews.filter(|p| p.content == content)
+    }
+}
+
+/// Compute the impact of every quickfix offered for `content`
+pub fn compute_previews(content: &str, cache: &FileCache) -> QuickfixPreviews {
+    let (diagnostics, _) = compute_diagnostics_sync(content, None, &DiagnosticConfig::default());
+    let shadowed_code = NumberOrString::String(codes::SHADOWED_RULE.to_string());
+    let mut shadowed_lines: Vec<u32> = diagnostics
+        .iter()
+        .filter(|d| d.code.as_ref() == Some(&shadowed_code))
+        .map(|d| d.range.start.line)
+        .collect();
+    shadowed_lines.dedup();
+
+    let moves = shadowed_lines
+        .iter()
+        .filter_map(|&line| {
+            move_below_shadowing(content, line, cache)
+                .ok()
+                .map(|result| (line, result))
+        })
+        .collect();
+
+    let removals = shadowed_lines
+        .into_iter()
+        .map(|line| {
+            let removed = remove_line(content, line as usize);
+            (line, ownership_impact(content, &removed, cache))
+        })
+        .collect();
+
+    let fixed = apply_safe_fixes(content, None);
+    let fix_all =
+        (!fixed.fixes.is_empty()).then(|| ownership_impact(content, &fixed.content, cache));
+
+    QuickfixPreviews {
+        content: content.to_string(),
+        removals,
+        moves,
+        fix_all,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_previews_for_shadowed_rule_and_fix_all() {
+        let cache = FileCache::from_files(vec![
+            "src/auth/login.rs".to_string(),
+            "src/main.rs".to_string(),
+        ]);
+        // The first rule is an exact duplicate, so "Fix all" removes it too
+        let content = "/src/ @old\n/src/ @backend\n";
+        let previews = compute_previews(content, &cache);
+
+        let removal = &previews.removals[&0];
+        assert!(removal.changes.is_empty());
+        assert!(previews.fix_all.is_some());
+
+        assert!(QuickfixPreviews::for_content(Some(&previews), content).is_some());
+        assert!(QuickfixPreviews::for_content(Some(&previews), "/src/ @backend\n").is_none());
+
+        // Exact duplicates are removed, not moved
+        assert!(previews.moves.is_empty());
+        let nested = compute_previews("/src/auth/ @security\n/src/ @backend\n", &cache);
+        assert_eq!(nested.moves[&0].changed_files, 1);
+
+        let clean = compute_previews("/src/ @backend\n", &cache);
+        assert!(clean.removals.is_empty());
+        assert!(clean.moves.is_empty());
+        assert!(clean.fix_all.is_none());
+    }
+}
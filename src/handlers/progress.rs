@@ -0,0 +1,127 @@
+//! Work-done progress for long-running background tasks
+//!
+//! The server creates a token with `window/workDoneProgress/create` and then
+//! sends `$/progress` begin, report and end notifications on it. Everything
+//! is a no-op when the client doesn't support work-done progress or rejects
+//! the token, so callers never need to check.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tower_lsp::lsp_types::notification::Progress as ProgressNotification;
+use tower_lsp::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp::lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress, WorkDoneProgressBegin,
+    WorkDoneProgressCreateParams, WorkDoneProgressEnd, WorkDoneProgressReport,
+};
+use tower_lsp::Client;
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// An open progress indicator. Dropping it without calling [`Progress::end`]
+/// (e.g. when its task is aborted) still ends it in the editor.
+pub struct Progress {
+    client: Client,
+    token: Option<NumberOrString>,
+}
+
+impl Progress {
+    /// Create a token and send the begin notification
+    pub async fn begin(client: &Client, supported: bool, title: &str) -> Self {
+        let mut progress = Progress {
+            client: client.clone(),
+            token: None,
+        };
+        if !supported {
+            return progress;
+        }
+
+        let token = NumberOrString::String(format!(
+            "codeowners/{}",
+            NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+        ));
+        let created = client
+            .send_request::<WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                token: token.clone(),
+            })
+            .await;
+        if created.is_err() {
+            return progress;
+        }
+
+        progress.token = Some(token);
+        progress
+            .send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: title.to_string(),
+                cancellable: Some(false),
+                message: None,
+                percentage: None,
+            }))
+            .await;
+        progress
+    }
+
+    pub async fn report(&self, message: impl Into<String>, percentage: Option<u32>) {
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(false),
+            message: Some(message.into()),
+            percentage,
+        }))
+        .await;
+    }
+
+    pub async fn end(mut self, message: Option<String>) {
+        if let Some(token) = self.token.take() {
+            send_end(&self.client, token, message).await;
+        }
+    }
+
+    async fn send(&self, value: WorkDoneProgress) {
+        if let Some(token) = &self.token {
+            self.client
+                .send_notification::<ProgressNotification>(ProgressParams {
+                    token: token.clone(),
+                    value: ProgressParamsValue::WorkDone(value),
+                })
+                .await;
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            let client = self.client.clone();
+            tokio::spawn(async move { send_end(&client, token, None).await });
+        }
+    }
+}
+
+async fn send_end(client: &Client, token: NumberOrString, message: Option<String>) {
+    client
+        .send_notification::<ProgressNotification>(ProgressParams {
+            token,
+            value: ProgressParamsValue::WorkDone(WorkDoneProgress::End(WorkDoneProgressEnd {
+                message,
+            })),
+        })
+        .await;
+}
+
+/// Percentage of `done` out of `total` for progress reports
+pub fn percentage(done: usize, total: usize) -> u32 {
+    (done.min(total) * 100)
+        .checked_div(total)
+        .map_or(100, |p| p as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage() {
+        assert_eq!(percentage(0, 0), 100);
+        assert_eq!(percentage(1, 3), 33);
+        assert_eq!(percentage(5, 4), 100);
+    }
+}
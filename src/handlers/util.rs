@@ -1,5 +1,23 @@
 //! Shared utilities for LSP handlers
 
+/// Length of `text` in UTF-16 code units, the LSP default position encoding
+pub fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// Byte offset in `line` of the UTF-16 column `column`, clamped to the end
+/// of the line
+pub fn utf16_to_byte(line: &str, column: u32) -> usize {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= column as usize {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
 /// Find the byte position of the nth occurrence of an owner string in a line.
 ///
 /// `n` is the occurrence count (0-indexed) of this specific owner as a
@@ -39,6 +57,18 @@ pub fn find_nth_owner_position(line: &str, owner: &str, n: usize) -> Option<usiz
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_utf16_columns() {
+        // é is two bytes and one UTF-16 unit; 🚀 is four bytes and two units
+        let line = "# é 🚀 /docs/";
+        assert_eq!(utf16_len(line), 13);
+        assert_eq!(utf16_to_byte(line, 2), 2);
+        assert_eq!(utf16_to_byte(line, 4), 5);
+        assert_eq!(utf16_to_byte(line, 7), 10);
+        assert_eq!(&line[utf16_to_byte(line, 7)..], "/docs/");
+        assert_eq!(utf16_to_byte(line, 100), line.len());
+    }
+
     #[test]
     fn test_find_nth_owner_basic() {
         let line = "*.rs @alice @bob @charlie";
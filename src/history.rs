@@ -0,0 +1,129 @@
+//! Ownership timeline of a path from git history
+//!
+//! Walks the commits that touched CODEOWNERS, evaluates the path against the
+//! file as it was at each one, and keeps the commits where its effective
+//! owners changed.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::git::{codeowners_at, log};
+use crate::ownership::{check_file_ownership_parsed, CODEOWNERS_LOCATIONS};
+use crate::parser::parse_codeowners_file_with_positions;
+
+/// A commit that changed who owns the path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OwnershipEvent {
+    pub commit: String,
+    /// Author date, ISO 8601
+    pub date: String,
+    pub author: String,
+    pub subject: String,
+    /// Winning pattern, None when the path became unowned
+    pub rule: Option<String>,
+    /// Line number (1-indexed) of the winning rule
+    pub line: Option<u32>,
+    pub owners: Vec<String>,
+}
+
+/// Every change to the effective owners of `path`, oldest first.
+/// `preferred` is a custom CODEOWNERS location tried before the standard ones.
+pub fn ownership_history(
+    root: &Path,
+    path: &str,
+    preferred: Option<&str>,
+) -> Result<Vec<OwnershipEvent>, String> {
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    let locations: Vec<&str> = preferred.into_iter().chain(CODEOWNERS_LOCATIONS).collect();
+
+    let mut events: Vec<OwnershipEvent> = Vec::new();
+    for commit in log(root, &locations)? {
+        let content = codeowners_at(root, &commit.hash, preferred)
+            .map(|(_, content)| content)
+            .unwrap_or_default();
+        let parsed = parse_codeowners_file_with_positions(&content);
+        let result = check_file_ownership_parsed(&parsed, path);
+        let owners = result
+            .as_ref()
+            .map(|r| r.owners.clone())
+            .unwrap_or_default();
+
+        let changed = match events.last() {
+            Some(last) => last.owners != owners,
+            None => true,
+        };
+        if changed {
+            events.push(OwnershipEvent {
+                commit: commit.hash,
+                date: commit.date,
+                author: commit.author,
+                subject: commit.subject,
+                line: result.as_ref().map(|r| r.line_number + 1),
+                rule: result.map(|r| r.pattern),
+                owners,
+            });
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn commit_codeowners(dir: &Path, location: &str, content: &str, message: &str) {
+        let file = dir.join(location);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    #[test]
+    fn test_ownership_history() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+
+        commit_codeowners(root, "CODEOWNERS", "* @core\n", "add codeowners");
+        commit_codeowners(root, "CODEOWNERS", "* @core\n/docs/ @docs\n", "docs team");
+        commit_codeowners(
+            root,
+            "CODEOWNERS",
+            "# Owners\n* @core\n/docs/ @docs\n",
+            "comment",
+        );
+        fs::remove_file(root.join("CODEOWNERS")).unwrap();
+        commit_codeowners(root, ".github/CODEOWNERS", "/src/ @rust\n", "move");
+
+        let events = ownership_history(root, "./src/main.rs", None).unwrap();
+        let owners: Vec<_> = events.iter().map(|e| e.owners.clone()).collect();
+        assert_eq!(owners, vec![vec!["@core"], vec!["@rust"]]);
+        assert_eq!(events[0].subject, "add codeowners");
+        assert_eq!(events[1].subject, "move");
+        assert_eq!(events[1].rule.as_deref(), Some("/src/"));
+        assert_eq!(events[1].line, Some(1));
+        assert_eq!(events[1].author, "Test");
+
+        let events = ownership_history(root, "docs/a.md", None).unwrap();
+        let subjects: Vec<_> = events.iter().map(|e| e.subject.as_str()).collect();
+        assert_eq!(subjects, vec!["add codeowners", "docs team", "move"]);
+        assert!(events[2].owners.is_empty());
+        assert_eq!(events[2].rule, None);
+    }
+}
@@ -3,14 +3,26 @@
 //! Exposes the core modules used by both binaries, enabling
 //! external consumers (benchmarks, integration tests) to import them.
 
+pub mod approvals;
+pub mod baseline;
 pub mod blame;
 pub mod diagnostics;
 pub mod file_cache;
+pub mod formatter;
+pub mod git;
 pub mod github;
 pub mod handlers;
+pub mod history;
 pub mod lookup;
 pub mod ownership;
 pub mod parser;
+pub mod path_suggest;
 pub mod pattern;
+pub mod query;
+pub mod report;
+pub mod reviewers;
+pub mod rule_move;
+pub mod rule_sort;
 pub mod settings;
+pub mod stats;
 pub mod validation;
@@ -2,20 +2,28 @@
 use codeowners_lsp as lib;
 pub use lib::diagnostics;
 pub use lib::file_cache;
+pub use lib::formatter;
+pub use lib::git;
 pub use lib::github;
 pub use lib::handlers;
 pub use lib::ownership;
 pub use lib::parser;
+pub use lib::path_suggest;
 pub use lib::pattern;
+pub use lib::rule_move;
+pub use lib::rule_sort;
 pub use lib::settings;
 pub use lib::validation;
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
 use codeowners::Owners;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
@@ -23,25 +31,239 @@ use tower_lsp::{Client, LanguageServer, LspService, Server};
 use diagnostics::{compute_diagnostics_sync, DiagnosticConfig};
 use file_cache::FileCache;
 use github::{GitHubClient, PersistentCache};
-use ownership::{apply_safe_fixes, check_file_ownership};
-use parser::{
-    find_insertion_point_with_owner, find_owner_at_position, format_codeowners,
-    parse_codeowners_file, parse_codeowners_file_with_positions, serialize_codeowners,
-    CodeownersLine,
+use handlers::explain::{explain_markdown, ExplainParams, ExplainResult, EXPLAIN_REQUEST};
+use handlers::links::owner_url;
+use handlers::matches::{
+    matches_document, matches_uri, parse_matches_uri, TextDocumentContentParams,
+    TextDocumentContentResult, SHOW_MATCHES_COMMAND, TEXT_DOCUMENT_CONTENT_REQUEST,
 };
-use pattern::pattern_matches;
+use handlers::ownership_tree::{
+    owners_for_files, ownership_tree, OwnersForFilesParams, OwnersForFilesResult,
+    OwnershipTreeParams, TreeNode, OWNERSHIP_TREE_REQUEST, OWNERS_FOR_FILES_REQUEST,
+};
+use handlers::previews::{compute_previews, QuickfixPreviews};
+use handlers::progress::{percentage, Progress};
+use handlers::util::utf16_to_byte;
+use ownership::{
+    add_owner_to_matching_rule, add_rule, apply_safe_fixes, check_file_ownership,
+    explain_ownership, ownership_impact, remove_line,
+};
+use parser::{find_owner_at_position, parse_codeowners_file_with_positions, CodeownersLine};
+use path_suggest::RenameHistory;
+use rule_move::{fix_ordering, shadowing_lines};
+use rule_sort::sort_rules;
 use settings::{load_settings_from_path, Settings, CONFIG_FILE, CONFIG_FILE_LOCAL};
 
+/// Picker entry that falls back to typing the owner with completion
+const OTHER_OWNER_CHOICE: &str = "Other...";
+
+/// Outcome of asking the user to choose an owner
+enum OwnerPick {
+    Owner(String),
+    /// User wants to type an owner not in the list
+    Other,
+    Cancelled,
+    /// Client can neither prompt nor open documents
+    Unsupported,
+}
+
 struct Backend {
     client: Client,
     workspace_root: RwLock<Option<PathBuf>>,
     codeowners: RwLock<Option<Owners>>,
-    codeowners_path: RwLock<Option<PathBuf>>,
-    settings: RwLock<Settings>,
-    file_cache: RwLock<Option<FileCache>>,
+    codeowners_path: Arc<RwLock<Option<PathBuf>>>,
+    settings: Arc<RwLock<Settings>>,
+    file_cache: Arc<RwLock<Option<Arc<FileCache>>>>,
+    /// Git rename history, loaded in the background and kept across scans
+    /// until HEAD moves
+    renames: Arc<Mutex<RenameHistory>>,
     github_client: Arc<GitHubClient>,
     /// Track open documents to refresh diagnostics when CODEOWNERS changes
-    open_documents: RwLock<HashMap<Url, String>>,
+    open_documents: Arc<RwLock<HashMap<Url, String>>>,
+    /// Whether the client can apply edits via `workspace/applyEdit`
+    supports_apply_edit: RwLock<bool>,
+    /// Whether the client can open documents via `window/showDocument`
+    supports_show_document: RwLock<bool>,
+    /// Whether the client can fill in a code action's edit via `codeAction/resolve`
+    supports_code_action_resolve: RwLock<bool>,
+    /// Whether the client accepts server-initiated `$/progress`
+    supports_work_done_progress: RwLock<bool>,
+    /// True while the file cache is being scanned
+    cache_loading: Arc<watch::Sender<bool>>,
+    /// Bumped whenever CODEOWNERS diagnostics are recomputed; results from
+    /// older computations are dropped instead of published
+    diagnostics_version: Arc<AtomicU64>,
+    /// Background owner validation, replaced on every edit
+    validation_task: Mutex<Option<JoinHandle<()>>>,
+    /// Impact of the CODEOWNERS quickfixes, for the latest content previewed
+    quickfix_previews: Arc<RwLock<Option<QuickfixPreviews>>>,
+    /// Background preview computation, replaced on every edit
+    preview_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+/// What a spawned background task needs from the `Backend`
+#[derive(Clone)]
+struct TaskContext {
+    github_client: Arc<GitHubClient>,
+    client: Client,
+    supports_work_done_progress: bool,
+    diagnostics_version: Arc<AtomicU64>,
+    settings: Arc<RwLock<Settings>>,
+    codeowners_path: Arc<RwLock<Option<PathBuf>>>,
+    file_cache: Arc<RwLock<Option<Arc<FileCache>>>>,
+    renames: Arc<Mutex<RenameHistory>>,
+    open_documents: Arc<RwLock<HashMap<Url, String>>>,
+    cache_loading: Arc<watch::Sender<bool>>,
+    quickfix_previews: Arc<RwLock<Option<QuickfixPreviews>>>,
+    preview_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl TaskContext {
+    /// Scan `root` into the file cache - runs in blocking thread pool.
+    /// `report` shows progress in the editor; quick refreshes skip it.
+    async fn refresh_file_cache(&self, root: PathBuf, report: bool) {
+        self.cache_loading.send_replace(true);
+        let progress = Progress::begin(
+            &self.client,
+            report && self.supports_work_done_progress,
+            "Scanning workspace files",
+        )
+        .await;
+
+        // Heavy work in blocking thread
+        let history = self.renames.clone();
+        let scan = tokio::task::spawn_blocking(move || {
+            let head = git::resolve_revision(&root, "HEAD").ok();
+            let renames = history.lock().unwrap().for_head(head);
+            let cache = FileCache::new(&root).with_shared_renames(renames.clone());
+            (cache, renames, root)
+        })
+        .await
+        .ok();
+
+        // Only "did you mean" fixes need renames, so they load after the
+        // scan without holding it up
+        let cache = scan.map(|(cache, renames, root)| {
+            if renames.get().is_none() {
+                tokio::task::spawn_blocking(move || {
+                    renames.get_or_init(|| path_suggest::load_renames(&root));
+                });
+            }
+            cache
+        });
+
+        // Write back (fast)
+        let count = cache.as_ref().map(|c| c.all_files().len());
+        if let Some(cache) = cache {
+            *self.file_cache.write().unwrap() = Some(Arc::new(cache));
+        }
+        self.cache_loading.send_replace(false);
+        progress.end(count.map(|n| format!("{} files", n))).await;
+    }
+
+    /// CODEOWNERS content from the open buffer, or disk when it isn't open
+    fn codeowners_content(&self) -> Option<String> {
+        let codeowners_path = self.codeowners_path.read().unwrap();
+        let path = codeowners_path.as_ref()?;
+
+        // Try buffer first
+        if let Ok(url) = Url::from_file_path(path) {
+            let docs = self.open_documents.read().unwrap();
+            if let Some(text) = docs.get(&url) {
+                return Some(text.clone());
+            }
+        }
+
+        // Fall back to disk
+        fs::read_to_string(path).ok()
+    }
+
+    /// Compute diagnostics for the CODEOWNERS file
+    async fn compute_diagnostics(&self, content: &str) -> Vec<Diagnostic> {
+        // Check if GitHub validation is enabled and get diagnostic config
+        let (validate_owners, token, diag_config) = {
+            let settings = self.settings.read().unwrap();
+            let enabled = settings.validate_owners;
+            let token = settings.resolve_token();
+            let config = settings.diagnostic_config();
+            (enabled && token.is_some(), token, config)
+        };
+
+        let progress = Progress::begin(
+            &self.client,
+            self.supports_work_done_progress,
+            "Checking CODEOWNERS",
+        )
+        .await;
+
+        // Phase 1: Synchronous diagnostics (holds file_cache lock)
+        let (mut diagnostics, owners_to_validate) = {
+            let file_cache = self.file_cache.read().unwrap();
+            compute_diagnostics_sync(content, file_cache.as_deref(), &diag_config)
+        };
+
+        // Phase 2: Async GitHub validation (no locks held)
+        if validate_owners {
+            if let Some(token) = token {
+                progress.report("Validating owners on GitHub", None).await;
+                diagnostics::add_github_diagnostics(
+                    &mut diagnostics,
+                    owners_to_validate,
+                    &self.github_client,
+                    &token,
+                    &diag_config,
+                )
+                .await;
+            }
+        }
+
+        progress.end(None).await;
+        diagnostics
+    }
+
+    /// Compute full CODEOWNERS diagnostics and publish them, unless a newer
+    /// edit started another computation in the meantime
+    async fn publish_full_diagnostics(&self, uri: Url, content: &str) {
+        let version = self.diagnostics_version.fetch_add(1, Ordering::SeqCst) + 1;
+        let diagnostics = self.compute_diagnostics(content).await;
+        if self.diagnostics_version.load(Ordering::SeqCst) == version {
+            self.client
+                .publish_diagnostics(uri, diagnostics, None)
+                .await;
+            self.spawn_quickfix_previews(content.to_string());
+        }
+    }
+
+    /// Compute quickfix previews for `content` in the background, replacing
+    /// a computation still running for older content
+    fn spawn_quickfix_previews(&self, content: String) {
+        let Some(cache) = self.file_cache.read().unwrap().clone() else {
+            return;
+        };
+        let previews = self.quickfix_previews.clone();
+        let task = tokio::spawn(async move {
+            let computed =
+                tokio::task::spawn_blocking(move || compute_previews(&content, &cache)).await;
+            if let Ok(computed) = computed {
+                *previews.write().unwrap() = Some(computed);
+            }
+        });
+        if let Some(previous) = self.preview_task.lock().unwrap().replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Publish diagnostics for the CODEOWNERS file, using the open buffer
+    /// when there is one
+    async fn publish_codeowners_diagnostics(&self) {
+        let codeowners_path = self.codeowners_path.read().unwrap().clone();
+        let Some(path) = codeowners_path else {
+            return;
+        };
+        if let (Some(content), Ok(uri)) = (self.codeowners_content(), Url::from_file_path(&path)) {
+            self.publish_full_diagnostics(uri, &content).await;
+        }
+    }
 }
 
 impl Backend {
@@ -50,14 +272,50 @@ impl Backend {
             client,
             workspace_root: RwLock::new(None),
             codeowners: RwLock::new(None),
-            codeowners_path: RwLock::new(None),
-            settings: RwLock::new(Settings::default()),
-            file_cache: RwLock::new(None),
+            codeowners_path: Arc::new(RwLock::new(None)),
+            settings: Arc::new(RwLock::new(Settings::default())),
+            file_cache: Arc::new(RwLock::new(None)),
+            renames: Arc::new(Mutex::new(RenameHistory::default())),
             github_client: Arc::new(GitHubClient::new()),
-            open_documents: RwLock::new(HashMap::new()),
+            open_documents: Arc::new(RwLock::new(HashMap::new())),
+            supports_apply_edit: RwLock::new(false),
+            supports_show_document: RwLock::new(false),
+            supports_code_action_resolve: RwLock::new(false),
+            supports_work_done_progress: RwLock::new(false),
+            cache_loading: Arc::new(watch::Sender::new(false)),
+            diagnostics_version: Arc::new(AtomicU64::new(0)),
+            validation_task: Mutex::new(None),
+            quickfix_previews: Arc::new(RwLock::new(None)),
+            preview_task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn task_context(&self) -> TaskContext {
+        TaskContext {
+            github_client: self.github_client.clone(),
+            client: self.client.clone(),
+            supports_work_done_progress: self.supports_progress(),
+            diagnostics_version: self.diagnostics_version.clone(),
+            settings: self.settings.clone(),
+            codeowners_path: self.codeowners_path.clone(),
+            file_cache: self.file_cache.clone(),
+            renames: self.renames.clone(),
+            open_documents: self.open_documents.clone(),
+            cache_loading: self.cache_loading.clone(),
+            quickfix_previews: self.quickfix_previews.clone(),
+            preview_task: self.preview_task.clone(),
         }
     }
 
+    fn supports_progress(&self) -> bool {
+        *self.supports_work_done_progress.read().unwrap()
+    }
+
+    /// Start a new round of CODEOWNERS diagnostics, invalidating older ones
+    fn next_diagnostics_version(&self) -> u64 {
+        self.diagnostics_version.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
     /// Load settings from TOML config files in the workspace
     fn load_config_files(&self) -> Settings {
         let root = self.workspace_root.read().unwrap();
@@ -88,8 +346,8 @@ impl Backend {
                 }
             }
 
-            // Otherwise use the crate's locate function
-            if let Some(path) = codeowners::locate(&root) {
+            // Otherwise look in the standard locations
+            if let Some(path) = ownership::find_codeowners(&root) {
                 let owners = codeowners::from_path(&path);
                 return Some((Some(owners), Some(path)));
             }
@@ -116,21 +374,24 @@ impl Backend {
         *self.codeowners.write().unwrap() = Some(owners);
     }
 
-    /// Refresh file cache - runs in blocking thread pool
-    async fn refresh_file_cache(&self) {
+    /// Refresh file cache - runs in blocking thread pool. `report` shows
+    /// progress in the editor; quick refreshes (completion) skip it.
+    async fn refresh_file_cache(&self, report: bool) {
         let Some(root) = self.workspace_root.read().unwrap().clone() else {
             return;
         };
+        self.task_context().refresh_file_cache(root, report).await;
+    }
 
-        // Heavy work in blocking thread
-        let cache = tokio::task::spawn_blocking(move || FileCache::new(&root))
-            .await
-            .ok();
-
-        // Write back (fast)
-        if let Some(cache) = cache {
-            *self.file_cache.write().unwrap() = Some(cache);
+    /// Wait for a running file scan when there is no cache yet. Pending
+    /// requests are aborted by `$/cancelRequest`, so waiting here is
+    /// cancellable.
+    async fn wait_for_file_cache(&self) {
+        if self.file_cache.read().unwrap().is_some() {
+            return;
         }
+        let mut loading = self.cache_loading.subscribe();
+        let _ = loading.wait_for(|loading| !loading).await;
     }
 
     /// Load persistent cache from disk and populate in-memory cache
@@ -163,10 +424,7 @@ impl Backend {
 
         for (uri, text) in documents {
             if self.is_codeowners_file(&uri) {
-                let diagnostics = self.compute_diagnostics(&text).await;
-                self.client
-                    .publish_diagnostics(uri, diagnostics, None)
-                    .await;
+                self.publish_full_diagnostics(uri, &text).await;
             } else {
                 let line_count = text.lines().count() as u32;
                 let diagnostics = self.check_file_not_owned(&uri, line_count);
@@ -199,12 +457,12 @@ impl Backend {
 
     /// Validate uncached owners in a spawned task (doesn't block LSP responses)
     async fn validate_owners_task(
-        github_client: Arc<GitHubClient>,
-        lsp_client: Client,
+        ctx: TaskContext,
         settings: Settings,
         uri: Url,
         owners: Vec<diagnostics::OwnerValidationInfo>,
         content: String,
+        version: u64,
     ) {
         // Check if validation is enabled
         if !settings.validate_owners {
@@ -215,20 +473,36 @@ impl Backend {
         };
 
         // Find owners not already in cache
-        let uncached: Vec<_> = owners
+        let mut uncached: Vec<_> = owners
             .iter()
-            .filter(|(_, _, owner, _)| !github_client.is_cached(owner))
+            .filter(|(_, _, owner, _)| !ctx.github_client.is_cached(owner))
             .map(|(_, _, owner, _)| owner.clone())
             .collect();
+        uncached.sort();
+        uncached.dedup();
 
         if uncached.is_empty() {
             return;
         }
 
         // Validate uncached owners and fetch metadata
+        let progress = Progress::begin(
+            &ctx.client,
+            ctx.supports_work_done_progress,
+            "Validating owners",
+        )
+        .await;
+        let total = uncached.len();
         let mut any_validated = false;
-        for owner in uncached {
-            if github_client
+        for (done, owner) in uncached.into_iter().enumerate() {
+            progress
+                .report(
+                    format!("{} ({}/{})", owner, done + 1, total),
+                    Some(percentage(done, total)),
+                )
+                .await;
+            if ctx
+                .github_client
                 .validate_owner_with_info(&owner, &token)
                 .await
                 .is_some()
@@ -236,14 +510,35 @@ impl Backend {
                 any_validated = true;
             }
         }
+        progress.end(None).await;
 
-        // If we validated anything, refresh diagnostics
+        // If we validated anything, refresh diagnostics - unless a newer
+        // edit has published its own in the meantime
         // (cache save happens on file save or shutdown - skipping here to keep task simple)
-        if any_validated {
+        if any_validated && ctx.diagnostics_version.load(Ordering::SeqCst) == version {
             let file_cache = None; // Skip pattern matching for speed
             let diag_config = settings.diagnostic_config();
             let (diagnostics, _) = compute_diagnostics_sync(&content, file_cache, &diag_config);
-            lsp_client.publish_diagnostics(uri, diagnostics, None).await;
+            ctx.client.publish_diagnostics(uri, diagnostics, None).await;
+        }
+    }
+
+    /// Validate owners in the background, aborting any validation started
+    /// for an older version of the file
+    fn spawn_owner_validation(
+        &self,
+        uri: Url,
+        owners: Vec<diagnostics::OwnerValidationInfo>,
+        content: String,
+    ) {
+        let ctx = self.task_context();
+        let settings = self.settings.read().unwrap().clone();
+        let version = self.diagnostics_version.load(Ordering::SeqCst);
+        let task = tokio::spawn(Self::validate_owners_task(
+            ctx, settings, uri, owners, content, version,
+        ));
+        if let Some(previous) = self.validation_task.lock().unwrap().replace(task) {
+            previous.abort();
         }
     }
 
@@ -266,19 +561,7 @@ impl Backend {
 
     /// Get CODEOWNERS content from open buffer or disk
     fn get_codeowners_content(&self) -> Option<String> {
-        let codeowners_path = self.codeowners_path.read().unwrap();
-        let path = codeowners_path.as_ref()?;
-
-        // Try buffer first
-        if let Ok(url) = Url::from_file_path(path) {
-            let docs = self.open_documents.read().unwrap();
-            if let Some(text) = docs.get(&url) {
-                return Some(text.clone());
-            }
-        }
-
-        // Fall back to disk
-        fs::read_to_string(path).ok()
+        self.task_context().codeowners_content()
     }
 
     /// Collect all unique owners from CODEOWNERS file
@@ -336,38 +619,11 @@ impl Backend {
         self.get_ownership_status(uri).flatten()
     }
 
-    /// Compute diagnostics for the CODEOWNERS file
-    async fn compute_diagnostics(&self, content: &str) -> Vec<Diagnostic> {
-        // Check if GitHub validation is enabled and get diagnostic config
-        let (validate_owners, token, diag_config) = {
-            let settings = self.settings.read().unwrap();
-            let enabled = settings.validate_owners;
-            let token = self.get_github_token();
-            let config = settings.diagnostic_config();
-            (enabled && token.is_some(), token, config)
-        };
-
-        // Phase 1: Synchronous diagnostics (holds file_cache lock)
-        let (mut diagnostics, owners_to_validate) = {
-            let file_cache = self.file_cache.read().unwrap();
-            compute_diagnostics_sync(content, file_cache.as_ref(), &diag_config)
-        };
-
-        // Phase 2: Async GitHub validation (no locks held)
-        if validate_owners {
-            if let Some(token) = token {
-                diagnostics::add_github_diagnostics(
-                    &mut diagnostics,
-                    owners_to_validate,
-                    &self.github_client,
-                    &token,
-                    &diag_config,
-                )
-                .await;
-            }
-        }
-
-        diagnostics
+    /// Compute full CODEOWNERS diagnostics and publish them (see `TaskContext`)
+    async fn publish_full_diagnostics(&self, uri: Url, content: &str) {
+        self.task_context()
+            .publish_full_diagnostics(uri, content)
+            .await;
     }
 
     /// Check if a URI is the CODEOWNERS file
@@ -487,12 +743,75 @@ impl Backend {
         let lines: Vec<&str> = content.lines().collect();
         let mut actions = Vec::new();
 
+        // Impact summaries and safe moves computed in the background for this
+        // content; until they are ready, titles go without them and rules
+        // can only be removed
+        let requested_lines: HashSet<u32> = params
+            .context
+            .diagnostics
+            .iter()
+            .map(|d| d.range.start.line)
+            .collect();
+        let (removal_summaries, fix_all_summary, moves) = {
+            let previews = self.quickfix_previews.read().unwrap();
+            match QuickfixPreviews::for_content(previews.as_ref(), &content) {
+                Some(previews) => (
+                    previews
+                        .removals
+                        .iter()
+                        .map(|(line, impact)| (*line, impact.summary()))
+                        .collect(),
+                    previews.fix_all.as_ref().map(|impact| impact.summary()),
+                    previews
+                        .moves
+                        .iter()
+                        .filter(|(line, _)| requested_lines.contains(line))
+                        .map(|(line, result)| (*line, result.clone()))
+                        .collect(),
+                ),
+                None => (HashMap::new(), None, HashMap::new()),
+            }
+        };
+
         // Check each diagnostic in the request
         for diagnostic in &params.context.diagnostics {
             let line_num = diagnostic.range.start.line as usize;
 
-            // Handle "shadowed rule" diagnostics - offer to remove the dead rule
+            // Handle "shadowed rule" diagnostics - offer to move the rule below
+            // the rules shadowing it, or to remove the dead rule
             if diagnostic.message.contains("shadowed by") && line_num < lines.len() {
+                let moved = moves.get(&(line_num as u32));
+                let can_move = moved.is_some();
+                if let Some(result) = moved {
+                    let target = shadowing_lines(&content, line_num as u32)
+                        .into_iter()
+                        .max()
+                        .unwrap_or_default();
+                    let mut changes = HashMap::new();
+                    changes.insert(
+                        uri.clone(),
+                        handlers::formatting::replacement_edits(&content, &result.content),
+                    );
+                    actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                        title: format!(
+                            "Move rule below line {} (changes owner of {} file{})",
+                            target + 1,
+                            result.changed_files,
+                            if result.changed_files == 1 { "" } else { "s" }
+                        ),
+                        kind: Some(CodeActionKind::QUICKFIX),
+                        diagnostics: Some(vec![diagnostic.clone()]),
+                        edit: Some(WorkspaceEdit {
+                            changes: Some(changes),
+                            ..Default::default()
+                        }),
+                        command: None,
+                        is_preferred: Some(true),
+                        disabled: None,
+                        data: None,
+                    }));
+                }
+
                 let delete_range = Range {
                     start: Position {
                         line: line_num as u32,
@@ -514,7 +833,10 @@ impl Backend {
                 );
 
                 actions.push(CodeActionOrCommand::CodeAction(CodeAction {
-                    title: "Remove shadowed rule".to_string(),
+                    title: match removal_summaries.get(&(line_num as u32)) {
+                        Some(summary) => format!("Remove shadowed rule ({})", summary),
+                        None => "Remove shadowed rule".to_string(),
+                    },
                     kind: Some(CodeActionKind::QUICKFIX),
                     diagnostics: Some(vec![diagnostic.clone()]),
                     edit: Some(WorkspaceEdit {
@@ -522,12 +844,56 @@ impl Backend {
                         ..Default::default()
                     }),
                     command: None,
-                    is_preferred: Some(true),
+                    is_preferred: Some(!can_move),
                     disabled: None,
                     data: None,
                 }));
             }
 
+            // Handle "pattern matches no files" - offer "did you mean" replacements
+            let is_no_match = matches!(
+                &diagnostic.code,
+                Some(NumberOrString::String(code)) if code == diagnostics::codes::PATTERN_NO_MATCH
+            );
+            if is_no_match && line_num < lines.len() {
+                let line = lines[line_num];
+                let start = utf16_to_byte(line, diagnostic.range.start.character);
+                let end = utf16_to_byte(line, diagnostic.range.end.character);
+                if let Some(pattern) = line.get(start..end) {
+                    let file_cache = self.file_cache.read().unwrap();
+                    if let Some(ref cache) = *file_cache {
+                        let suggestions = path_suggest::suggest_replacements(pattern, cache);
+                        let confident = path_suggest::confident_suggestion(&suggestions)
+                            .map(|s| s.pattern.clone());
+
+                        for suggestion in &suggestions {
+                            let mut changes = HashMap::new();
+                            changes.insert(
+                                uri.clone(),
+                                vec![TextEdit {
+                                    range: diagnostic.range,
+                                    new_text: suggestion.pattern.clone(),
+                                }],
+                            );
+
+                            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                                title: format!("Replace with {}", suggestion.pattern),
+                                kind: Some(CodeActionKind::QUICKFIX),
+                                diagnostics: Some(vec![diagnostic.clone()]),
+                                edit: Some(WorkspaceEdit {
+                                    changes: Some(changes),
+                                    ..Default::default()
+                                }),
+                                command: None,
+                                is_preferred: confident.as_ref().map(|c| *c == suggestion.pattern),
+                                disabled: None,
+                                data: None,
+                            }));
+                        }
+                    }
+                }
+            }
+
             // Handle "duplicate owner" diagnostics - offer to dedupe
             if diagnostic.message.contains("Duplicate owner") && line_num < lines.len() {
                 let line = lines[line_num];
@@ -761,7 +1127,14 @@ impl Backend {
             );
 
             actions.push(CodeActionOrCommand::CodeAction(CodeAction {
-                title: format!("Fix all safe issues ({} fixes)", fix_result.fixes.len()),
+                title: match fix_all_summary {
+                    Some(summary) => format!(
+                        "Fix all safe issues ({} fixes; {})",
+                        fix_result.fixes.len(),
+                        summary
+                    ),
+                    None => format!("Fix all safe issues ({} fixes)", fix_result.fixes.len()),
+                },
                 kind: Some(CodeActionKind::SOURCE_FIX_ALL),
                 diagnostics: None,
                 edit: Some(WorkspaceEdit {
@@ -775,6 +1148,112 @@ impl Backend {
             }));
         }
 
+        // Whole-file source actions are only computed when the client asks
+        // for them (or for everything)
+        let wants = |kind: &CodeActionKind| {
+            params.context.only.as_ref().is_none_or(|only| {
+                only.iter()
+                    .any(|requested| kind.as_str().starts_with(requested.as_str()))
+            })
+        };
+
+        // Add "Fix ordering" source action: move every shadowed rule below
+        // the rules shadowing it
+        let ordering_kind = CodeActionKind::new("source.fixOrdering");
+        let ordering_result = if wants(&ordering_kind) {
+            let file_cache = self.file_cache.read().unwrap();
+            file_cache
+                .as_ref()
+                .and_then(|cache| fix_ordering(&content, cache).ok())
+        } else {
+            None
+        };
+        if let Some(result) = ordering_result.filter(|r| !r.moves.is_empty()) {
+            let mut changes = HashMap::new();
+            changes.insert(
+                uri.clone(),
+                handlers::formatting::replacement_edits(&content, &result.content),
+            );
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!(
+                    "Fix ordering{} ({} moves, changes owner of {} file{}{})",
+                    if result.incomplete { " partially" } else { "" },
+                    result.moves.len(),
+                    result.changed_files,
+                    if result.changed_files == 1 { "" } else { "s" },
+                    if result.incomplete {
+                        "; some rules stay shadowed"
+                    } else {
+                        ""
+                    }
+                ),
+                kind: Some(ordering_kind),
+                diagnostics: None,
+                edit: Some(WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                }),
+                command: None,
+                is_preferred: None,
+                disabled: None,
+                data: None,
+            }));
+        }
+
+        // Add "Sort rules" source action, plus a disabled action explaining
+        // each block that has to stay (partly) unsorted
+        let sort_kind = CodeActionKind::new("source.sortRules");
+        let sort_result = if wants(&sort_kind) {
+            let file_cache = self.file_cache.read().unwrap();
+            file_cache.as_ref().map(|cache| sort_rules(&content, cache))
+        } else {
+            None
+        };
+        if let Some(sort_result) = sort_result {
+            if sort_result.moved > 0 {
+                let mut changes = HashMap::new();
+                changes.insert(
+                    uri.clone(),
+                    handlers::formatting::replacement_edits(&content, &sort_result.content),
+                );
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Sort rules within sections ({} moved)", sort_result.moved),
+                    kind: Some(sort_kind.clone()),
+                    diagnostics: None,
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    command: None,
+                    is_preferred: None,
+                    disabled: None,
+                    data: None,
+                }));
+            }
+
+            for block in &sort_result.refused {
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!(
+                        "Sort rules on lines {}-{}",
+                        block.start_line + 1,
+                        block.end_line + 1
+                    ),
+                    kind: Some(sort_kind.clone()),
+                    diagnostics: None,
+                    edit: None,
+                    command: None,
+                    is_preferred: None,
+                    disabled: Some(CodeActionDisabled {
+                        reason: format!(
+                            "Reordering would change the winning rule: {}",
+                            block.reasons.join("; ")
+                        ),
+                    }),
+                    data: None,
+                }));
+            }
+        }
+
         if actions.is_empty() {
             Ok(None)
         } else {
@@ -782,111 +1261,306 @@ impl Backend {
         }
     }
 
-    /// Publish diagnostics for the CODEOWNERS file
-    async fn publish_codeowners_diagnostics(&self) {
-        let codeowners_path = self.codeowners_path.read().unwrap().clone();
-        if let Some(path) = codeowners_path {
-            if let Ok(content) = fs::read_to_string(&path) {
-                let diagnostics = self.compute_diagnostics(&content).await;
-                if let Ok(uri) = Url::from_file_path(&path) {
-                    self.client
-                        .publish_diagnostics(uri, diagnostics, None)
-                        .await;
-                }
+    /// Handle `codeowners/explain`: every rule matching a file, in order
+    async fn explain(&self, params: ExplainParams) -> Result<ExplainResult> {
+        let invalid =
+            |message: &str| tower_lsp::jsonrpc::Error::invalid_params(message.to_string());
+
+        let path = match (params.path, params.uri) {
+            (Some(path), _) => path.trim_start_matches('/').to_string(),
+            (None, Some(uri)) => {
+                let root = self.workspace_root.read().unwrap().clone();
+                uri.to_file_path()
+                    .ok()
+                    .zip(root)
+                    .and_then(|(p, root)| {
+                        p.strip_prefix(&root)
+                            .ok()
+                            .map(|r| r.to_string_lossy().to_string())
+                    })
+                    .ok_or_else(|| invalid("URI is not inside the workspace"))?
             }
+            (None, None) => return Err(invalid("Expected a uri or path")),
+        };
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| invalid("No CODEOWNERS file"))?;
+
+        let matches = explain_ownership(&content, &path);
+        let owners = matches
+            .iter()
+            .find(|m| m.winner)
+            .map(|m| m.owners.clone())
+            .unwrap_or_default();
+        Ok(ExplainResult {
+            path,
+            owners,
+            matches,
+        })
+    }
+
+    /// Handle `codeowners/ownershipTree`: the directory tree with owners
+    async fn ownership_tree(&self, params: OwnershipTreeParams) -> Result<TreeNode> {
+        let invalid =
+            |message: &str| tower_lsp::jsonrpc::Error::invalid_params(message.to_string());
+
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| invalid("No CODEOWNERS file"))?;
+        self.wait_for_file_cache().await;
+        let file_cache = self.file_cache.read().unwrap();
+        let cache = file_cache.as_ref().ok_or_else(|| invalid("No file list"))?;
+        Ok(ownership_tree(&content, cache, params.root.as_deref()))
+    }
+
+    /// Handle `codeowners/ownersForFiles`: owners for many paths at once
+    async fn owners_for_files(&self, params: OwnersForFilesParams) -> Result<OwnersForFilesResult> {
+        let invalid =
+            |message: &str| tower_lsp::jsonrpc::Error::invalid_params(message.to_string());
+
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| invalid("No CODEOWNERS file"))?;
+        self.wait_for_file_cache().await;
+        let file_cache = self.file_cache.read().unwrap();
+        let cache = file_cache.as_ref().ok_or_else(|| invalid("No file list"))?;
+        Ok(OwnersForFilesResult {
+            results: owners_for_files(&content, cache, &params.paths),
+        })
+    }
+
+    /// Open a rule's matches document in the editor
+    async fn show_matches(&self, uri: Url) -> std::result::Result<(), String> {
+        if !*self.supports_show_document.read().unwrap() {
+            return Err("This editor cannot open the matches document".to_string());
         }
+        self.client
+            .show_document(ShowDocumentParams {
+                uri,
+                external: Some(false),
+                take_focus: Some(true),
+                selection: None,
+            })
+            .await
+            .map_err(|e| format!("Failed to open matches document: {}", e))?;
+        Ok(())
     }
 
-    /// Add a new ownership entry to the CODEOWNERS file
-    fn add_ownership(&self, pattern: &str, owner: &str) -> std::result::Result<(), String> {
-        let codeowners_path = self.codeowners_path.read().unwrap();
-        let path = codeowners_path
-            .as_ref()
-            .ok_or_else(|| "No CODEOWNERS file found".to_string())?
-            .clone();
-        drop(codeowners_path);
+    /// Handle `codeowners/textDocumentContent` for `codeowners-matches:` URIs
+    async fn text_document_content(
+        &self,
+        params: TextDocumentContentParams,
+    ) -> Result<TextDocumentContentResult> {
+        let invalid =
+            |message: &str| tower_lsp::jsonrpc::Error::invalid_params(message.to_string());
+
+        let (line, pattern) =
+            parse_matches_uri(&params.uri).ok_or_else(|| invalid("Not a matches document URI"))?;
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| invalid("No CODEOWNERS file"))?;
+        let root = self
+            .workspace_root
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| invalid("No workspace root"))?;
 
-        let content =
-            fs::read_to_string(&path).map_err(|e| format!("Failed to read CODEOWNERS: {}", e))?;
+        self.wait_for_file_cache().await;
+        let file_cache = self.file_cache.read().unwrap();
+        let cache = file_cache.as_ref().ok_or_else(|| invalid("No file list"))?;
+        let text = matches_document(&content, line, &pattern, cache, &root)
+            .ok_or_else(|| invalid(&format!("Rule '{}' no longer exists", pattern)))?;
+        Ok(TextDocumentContentResult { text })
+    }
 
-        let mut lines = parse_codeowners_file(&content);
-        let insertion_point = find_insertion_point_with_owner(&lines, pattern, Some(owner));
+    /// Publish diagnostics for the CODEOWNERS file
+    async fn publish_codeowners_diagnostics(&self) {
+        self.task_context().publish_codeowners_diagnostics().await;
+    }
 
-        lines.insert(
-            insertion_point,
-            CodeownersLine::Rule {
-                pattern: pattern.to_string(),
-                owners: vec![owner.to_string()],
-            },
-        );
+    /// Compute the edited CODEOWNERS content for an ownership command.
+    /// Edits the open buffer when there is one, so unsaved changes are kept.
+    /// Returns (path, content the edit is based on, new content).
+    fn ownership_edit(
+        &self,
+        command: &str,
+        pattern: &str,
+        owner: &str,
+    ) -> std::result::Result<(PathBuf, String, String), String> {
+        let path = self
+            .codeowners_path
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| "No CODEOWNERS file found".to_string())?;
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| "Failed to read CODEOWNERS".to_string())?;
 
-        let new_content = serialize_codeowners(&lines);
-        let new_content = if new_content.ends_with('\n') {
-            new_content
+        let new_content = if command.starts_with("codeowners.addToExisting") {
+            if self.codeowners.read().unwrap().is_none() {
+                return Err("No CODEOWNERS loaded".to_string());
+            }
+            add_owner_to_matching_rule(&content, pattern, owner)?
+        } else if command.starts_with("codeowners.takeOwnership") {
+            add_rule(&content, pattern, owner)
         } else {
-            format!("{}\n", new_content)
+            return Err(format!("Unknown command: {}", command));
         };
 
-        fs::write(&path, new_content).map_err(|e| format!("Failed to write CODEOWNERS: {}", e))?;
-
-        Ok(())
+        Ok((path, content, new_content))
     }
 
-    /// Add an owner to an existing entry that matches the file
-    fn add_to_existing(&self, pattern: &str, owner: &str) -> std::result::Result<(), String> {
-        let codeowners_path = self.codeowners_path.read().unwrap();
-        let path = codeowners_path
-            .as_ref()
-            .ok_or_else(|| "No CODEOWNERS file found".to_string())?
-            .clone();
-        drop(codeowners_path);
+    /// Apply edited CODEOWNERS content through the editor (`workspace/applyEdit`)
+    /// so it lands on the undo stack. Clients without that capability get a
+    /// direct disk write instead, which refuses to clobber changes made since
+    /// the edit was computed.
+    async fn apply_codeowners_edit(
+        &self,
+        path: &Path,
+        base: &str,
+        new_content: &str,
+    ) -> std::result::Result<(), String> {
+        let supports_apply_edit = *self.supports_apply_edit.read().unwrap();
+        if !supports_apply_edit {
+            write_if_unchanged(path, base, new_content)?;
+            self.load_codeowners().await;
+            self.publish_codeowners_diagnostics().await;
+            return Ok(());
+        }
 
-        let content =
-            fs::read_to_string(&path).map_err(|e| format!("Failed to read CODEOWNERS: {}", e))?;
+        let edit = full_document_edit(path, base, new_content)
+            .ok_or_else(|| format!("Invalid CODEOWNERS path: {}", path.display()))?;
+        let response = self
+            .client
+            .apply_edit(edit)
+            .await
+            .map_err(|e| format!("Failed to apply edit: {}", e))?;
+        if !response.applied {
+            return Err(response
+                .failure_reason
+                .unwrap_or_else(|| "Editor rejected the CODEOWNERS edit".to_string()));
+        }
 
-        let mut lines = parse_codeowners_file(&content);
+        self.load_codeowners_from_content(new_content);
+        Ok(())
+    }
 
-        // Strip leading slash from pattern to get relative path
-        let relative_path = pattern.trim_start_matches('/');
+    /// Owner candidates for `pattern`, best first (see `handlers::owner_picker`)
+    fn owner_choices(&self, pattern: &str) -> Vec<String> {
+        let content = self.get_codeowners_content().unwrap_or_default();
+        let (individual, team) = {
+            let settings = self.settings.read().unwrap();
+            (settings.individual.clone(), settings.team.clone())
+        };
+        handlers::owner_picker::owner_choices(
+            &content,
+            pattern,
+            &self.github_client.get_cached_owners(),
+            individual.as_deref(),
+            team.as_deref(),
+        )
+    }
 
-        let codeowners = self.codeowners.read().unwrap();
-        if codeowners.is_none() {
-            return Err("No CODEOWNERS loaded".to_string());
-        }
+    /// Ask the user to pick an owner for `pattern` via `window/showMessageRequest`
+    async fn pick_owner(&self, pattern: &str) -> OwnerPick {
+        let supports_show_document = *self.supports_show_document.read().unwrap();
 
-        // Find the matching rule by checking which pattern in our parsed lines
-        // would match this file. We iterate in reverse since last match wins.
-        let mut matching_idx = None;
-        for (idx, line) in lines.iter().enumerate().rev() {
-            if let CodeownersLine::Rule {
-                pattern: rule_pattern,
-                ..
-            } = line
-            {
-                if pattern_matches(rule_pattern, relative_path) {
-                    matching_idx = Some(idx);
-                    break;
-                }
-            }
+        let mut actions: Vec<MessageActionItem> = self
+            .owner_choices(pattern)
+            .into_iter()
+            .take(handlers::owner_picker::MAX_CHOICES)
+            .map(|title| MessageActionItem {
+                title,
+                properties: HashMap::new(),
+            })
+            .collect();
+        if supports_show_document {
+            actions.push(MessageActionItem {
+                title: OTHER_OWNER_CHOICE.to_string(),
+                properties: HashMap::new(),
+            });
+        }
+        if actions.is_empty() {
+            return OwnerPick::Unsupported;
         }
 
-        let idx = matching_idx.ok_or("No matching rule found")?;
+        let response = self
+            .client
+            .show_message_request(
+                MessageType::INFO,
+                format!("Choose an owner for {}", pattern),
+                Some(actions),
+            )
+            .await;
 
-        if let CodeownersLine::Rule { owners, .. } = &mut lines[idx] {
-            if !owners.contains(&owner.to_string()) {
-                owners.push(owner.to_string());
-            }
+        match response {
+            Ok(Some(item)) if item.title == OTHER_OWNER_CHOICE => OwnerPick::Other,
+            Ok(Some(item)) => OwnerPick::Owner(item.title),
+            Ok(None) => OwnerPick::Cancelled,
+            // Client can't show prompts: go straight to completion if possible
+            Err(_) if supports_show_document => OwnerPick::Other,
+            Err(_) => OwnerPick::Unsupported,
         }
+    }
 
-        let new_content = serialize_codeowners(&lines);
-        let new_content = if new_content.ends_with('\n') {
-            new_content
+    /// Completion-based fallback for the custom commands: open CODEOWNERS with
+    /// the cursor where the owner (or the new rule) goes, so owner completion
+    /// (ranked like the picker) takes over. A new rule gets an empty line
+    /// opened for it first; nothing else is edited until the user types.
+    async fn open_for_owner_entry(
+        &self,
+        command: &str,
+        pattern: &str,
+    ) -> std::result::Result<(), String> {
+        let path = self
+            .codeowners_path
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| "No CODEOWNERS file found".to_string())?;
+        let content = self
+            .get_codeowners_content()
+            .ok_or_else(|| "Failed to read CODEOWNERS".to_string())?;
+
+        let adding_owner = command.starts_with("codeowners.addToExisting");
+        let position = if adding_owner {
+            handlers::owner_picker::owner_append_position(&content, pattern)
+                .ok_or_else(|| "No matching rule found".to_string())?
         } else {
-            format!("{}\n", new_content)
+            // No owner picked yet, so there is no owner group to join
+            let (opened, position) =
+                handlers::owner_picker::rule_insertion_position(&content, pattern, None);
+            if opened != content {
+                self.apply_codeowners_edit(&path, &content, &opened).await?;
+            }
+            position
         };
 
-        fs::write(&path, new_content).map_err(|e| format!("Failed to write CODEOWNERS: {}", e))?;
+        let uri = Url::from_file_path(&path)
+            .map_err(|_| format!("Invalid CODEOWNERS path: {}", path.display()))?;
+        self.client
+            .show_document(ShowDocumentParams {
+                uri,
+                external: Some(false),
+                take_focus: Some(true),
+                selection: Some(Range {
+                    start: position,
+                    end: position,
+                }),
+            })
+            .await
+            .map_err(|e| format!("Failed to open CODEOWNERS: {}", e))?;
 
+        if !adding_owner {
+            self.client
+                .show_message(
+                    MessageType::INFO,
+                    format!("Add a rule for {} followed by its owners", pattern),
+                )
+                .await;
+        }
         Ok(())
     }
 
@@ -909,6 +1583,32 @@ impl LanguageServer for Backend {
             }
         }
 
+        *self.supports_apply_edit.write().unwrap() = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.apply_edit)
+            .unwrap_or(false);
+        *self.supports_show_document.write().unwrap() = params
+            .capabilities
+            .window
+            .as_ref()
+            .and_then(|w| w.show_document.as_ref())
+            .is_some_and(|d| d.support);
+        *self.supports_code_action_resolve.write().unwrap() = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|t| t.code_action.as_ref())
+            .and_then(|c| c.resolve_support.as_ref())
+            .is_some_and(|r| r.properties.iter().any(|p| p == "edit"));
+        *self.supports_work_done_progress.write().unwrap() = params
+            .capabilities
+            .window
+            .as_ref()
+            .and_then(|w| w.work_done_progress)
+            .unwrap_or(false);
+
         // Load config: TOML files first, then JSON init options override
         let mut settings = self.load_config_files();
         if let Some(opts) = &params.initialization_options {
@@ -927,7 +1627,11 @@ impl LanguageServer for Backend {
         *self.settings.write().unwrap() = settings;
 
         self.load_codeowners().await;
-        self.refresh_file_cache().await;
+        // Files are scanned in `initialized`, where progress can be reported;
+        // requests that need them wait until then
+        if self.workspace_root.read().unwrap().is_some() {
+            self.cache_loading.send_replace(true);
+        }
         self.load_persistent_cache();
 
         // Check if we should run background validation
@@ -1025,7 +1729,12 @@ impl LanguageServer for Backend {
                         ..Default::default()
                     },
                 )),
-                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        resolve_provider: Some(true),
+                        ..Default::default()
+                    },
+                )),
                 execute_command_provider: Some(ExecuteCommandOptions {
                     commands: vec![
                         "codeowners.takeOwnership.individual".to_string(),
@@ -1034,6 +1743,7 @@ impl LanguageServer for Backend {
                         "codeowners.addToExisting.individual".to_string(),
                         "codeowners.addToExisting.team".to_string(),
                         "codeowners.addToExisting.custom".to_string(),
+                        SHOW_MATCHES_COMMAND.to_string(),
                     ],
                     work_done_progress_options: Default::default(),
                 }),
@@ -1043,6 +1753,11 @@ impl LanguageServer for Backend {
                     ..Default::default()
                 }),
                 document_formatting_provider: Some(OneOf::Left(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: "\n".to_string(),
+                    more_trigger_character: None,
+                }),
                 document_symbol_provider: Some(OneOf::Left(true)),
                 folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
                 references_provider: Some(OneOf::Left(true)),
@@ -1054,6 +1769,10 @@ impl LanguageServer for Backend {
                 code_lens_provider: Some(CodeLensOptions {
                     resolve_provider: Some(false),
                 }),
+                document_link_provider: Some(DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
                         SemanticTokensOptions {
@@ -1133,7 +1852,16 @@ impl LanguageServer for Backend {
                 .await;
         }
 
-        self.publish_codeowners_diagnostics().await;
+        // Scan in the background so the watchers and other requests aren't
+        // held up; CODEOWNERS diagnostics need the files, so they follow
+        let root = self.workspace_root.read().unwrap().clone();
+        let ctx = self.task_context();
+        tokio::spawn(async move {
+            if let Some(root) = root {
+                ctx.refresh_file_cache(root, true).await;
+            }
+            ctx.publish_codeowners_diagnostics().await;
+        });
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -1153,10 +1881,7 @@ impl LanguageServer for Backend {
             .insert(uri.clone(), text.clone());
 
         if self.is_codeowners_file(uri) {
-            let diagnostics = self.compute_diagnostics(&text).await;
-            self.client
-                .publish_diagnostics(uri.clone(), diagnostics, None)
-                .await;
+            self.publish_full_diagnostics(uri.clone(), &text).await;
         } else {
             // Check if file has no CODEOWNERS entry
             let line_count = text.lines().count() as u32;
@@ -1193,6 +1918,8 @@ impl LanguageServer for Backend {
             if self.is_codeowners_file(uri) {
                 // Parse CODEOWNERS from buffer content (handles unsaved changes)
                 self.load_codeowners_from_content(&change.text);
+                // Results of computations for older content are now outdated
+                self.next_diagnostics_version();
 
                 // Lightweight diagnostics for CODEOWNERS:
                 // - NO file cache (skip expensive pattern-no-match checks)
@@ -1233,24 +1960,9 @@ impl LanguageServer for Backend {
 
                 // Spawn background validation for uncached owners (fire-and-forget)
                 // Don't await - we don't want to block typing while hitting GitHub API
-                {
-                    let client = self.github_client.clone();
-                    let lsp_client = self.client.clone();
-                    let settings = self.settings.read().unwrap().clone();
-                    let content = change.text.clone();
-                    let uri = uri.clone();
-                    tokio::spawn(async move {
-                        Self::validate_owners_task(
-                            client,
-                            lsp_client,
-                            settings,
-                            uri,
-                            owners_to_validate,
-                            content,
-                        )
-                        .await;
-                    });
-                }
+                self.spawn_owner_validation(uri.clone(), owners_to_validate, change.text.clone());
+                self.task_context()
+                    .spawn_quickfix_previews(change.text.clone());
             } else {
                 // Non-CODEOWNERS file changed - update its diagnostics with new line count
                 let line_count = change.text.lines().count() as u32;
@@ -1275,7 +1987,7 @@ impl LanguageServer for Backend {
 
         if self.is_codeowners_file(uri) {
             self.load_codeowners().await;
-            self.refresh_file_cache().await;
+            self.refresh_file_cache(true).await;
 
             // Refresh diagnostics on ALL open files (file-not-owned may have changed)
             self.refresh_all_open_documents().await;
@@ -1291,22 +2003,7 @@ impl LanguageServer for Backend {
                 };
                 let (_, owners_to_validate) =
                     compute_diagnostics_sync(&content, None, &diag_config);
-
-                let client = self.github_client.clone();
-                let lsp_client = self.client.clone();
-                let settings = self.settings.read().unwrap().clone();
-                let uri = uri.clone();
-                tokio::spawn(async move {
-                    Self::validate_owners_task(
-                        client,
-                        lsp_client,
-                        settings,
-                        uri,
-                        owners_to_validate,
-                        content,
-                    )
-                    .await;
-                });
+                self.spawn_owner_validation(uri.clone(), owners_to_validate, content);
             }
         }
     }
@@ -1327,7 +2024,8 @@ impl LanguageServer for Backend {
                     // Find if we're hovering over an @owner
                     if let Some(owner) = find_owner_at_position(line, char_idx) {
                         let info = self.github_client.get_owner_info(&owner);
-                        let formatted = format_owner_hover(&owner, info.as_ref());
+                        let github_url = self.settings.read().unwrap().github_url().to_string();
+                        let formatted = format_owner_hover(&owner, info.as_ref(), &github_url);
                         return Ok(Some(Hover {
                             contents: HoverContents::Markup(MarkupContent {
                                 kind: MarkupKind::Markdown,
@@ -1347,6 +2045,7 @@ impl LanguageServer for Backend {
                             if char_idx >= parsed_line.pattern_start as usize
                                 && char_idx <= parsed_line.pattern_end as usize
                             {
+                                self.wait_for_file_cache().await;
                                 let file_cache = self.file_cache.read().unwrap();
                                 if let Some(ref cache) = *file_cache {
                                     let matches = cache.get_matches(pattern);
@@ -1367,14 +2066,25 @@ impl LanguageServer for Backend {
                                             String::new()
                                         };
                                         format!(
-                                            "**Pattern:** `{}`\n\n**Matches {} {}:**\n{}{}",
+                                            "**Pattern:** `{}`\n\n**Matches {} {}:**\n{}{}\n\n[Show all matches]({})",
                                             pattern,
                                             count,
                                             if count == 1 { "file" } else { "files" },
                                             files_list,
-                                            more
+                                            more,
+                                            matches_uri(position.line, pattern)
                                         )
                                     };
+                                    let impact = ownership_impact(
+                                        &content,
+                                        &remove_line(&content, line_idx),
+                                        cache,
+                                    );
+                                    let formatted = format!(
+                                        "{}\n\n---\n\n**Deleting this** {}",
+                                        formatted,
+                                        impact.summary()
+                                    );
                                     return Ok(Some(Hover {
                                         contents: HoverContents::Markup(MarkupContent {
                                             kind: MarkupKind::Markdown,
@@ -1439,9 +2149,10 @@ impl LanguageServer for Backend {
                 let owner_list: Vec<&str> = owners.split_whitespace().collect();
 
                 // Look up owner info from cache for rich hover
+                let github_url = self.settings.read().unwrap().github_url().to_string();
                 let format_with_cache = |owner: &str| -> String {
                     let info = self.github_client.get_owner_info(owner);
-                    format_owner_with_info(owner, info.as_ref())
+                    format_owner_with_info(owner, info.as_ref(), &github_url)
                 };
 
                 let owners_text = if owner_list.len() == 1 {
@@ -1464,6 +2175,26 @@ impl LanguageServer for Backend {
             }
         };
 
+        // Explain the decision when more than one rule matches
+        let why = relative_path.as_ref().and_then(|path| {
+            let content = self.get_codeowners_content()?;
+            let matches = explain_ownership(&content, path);
+            if matches.len() < 2 {
+                return None;
+            }
+            let codeowners_uri = self
+                .codeowners_path
+                .read()
+                .unwrap()
+                .as_ref()
+                .and_then(|p| Url::from_file_path(p).ok());
+            Some(explain_markdown(&matches, codeowners_uri.as_ref()))
+        });
+        let formatted = match why {
+            Some(why) => format!("{}\n\n---\n\n{}", formatted, why),
+            None => formatted,
+        };
+
         Ok(Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
@@ -1581,7 +2312,29 @@ impl LanguageServer for Backend {
 
         let mut actions = Vec::new();
 
+        let supports_resolve = *self.supports_code_action_resolve.read().unwrap();
         let make_action = |title: String, command: &str, pattern: &str, owner: Option<&str>| {
+            // With a known owner, clients that resolve code actions get the
+            // edit itself, computed in `codeAction/resolve` for the picked
+            // action only. Other clients run the command, which applies the
+            // same edit through `workspace/applyEdit`.
+            if let Some(o) = owner.filter(|_| supports_resolve) {
+                return CodeActionOrCommand::CodeAction(CodeAction {
+                    title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: None,
+                    edit: None,
+                    command: None,
+                    is_preferred: None,
+                    disabled: None,
+                    data: Some(serde_json::json!({
+                        "command": command,
+                        "pattern": pattern,
+                        "owner": o,
+                    })),
+                });
+            }
+
             let mut args = vec![
                 serde_json::Value::String(uri.to_string()),
                 serde_json::Value::String(pattern.to_string()),
@@ -1681,6 +2434,28 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn code_action_resolve(&self, mut action: CodeAction) -> Result<CodeAction> {
+        let Some(data) = action.data.as_ref() else {
+            return Ok(action);
+        };
+        let field = |name: &str| data.get(name).and_then(|v| v.as_str());
+        let (Some(command), Some(pattern), Some(owner)) =
+            (field("command"), field("pattern"), field("owner"))
+        else {
+            return Ok(action);
+        };
+
+        match self.ownership_edit(command, pattern, owner) {
+            Ok((path, base, new_content)) => {
+                action.edit = full_document_edit(&path, &base, &new_content);
+            }
+            Err(e) => {
+                self.client.show_message(MessageType::ERROR, &e).await;
+            }
+        }
+        Ok(action)
+    }
+
     async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
         let uri = &params.text_document.uri;
         let range = params.range;
@@ -1807,7 +2582,7 @@ impl LanguageServer for Backend {
         }
 
         self.load_codeowners().await;
-        self.refresh_file_cache().await;
+        self.refresh_file_cache(true).await;
         self.refresh_all_open_documents().await;
     }
 
@@ -1819,7 +2594,7 @@ impl LanguageServer for Backend {
         }
         *self.settings.write().unwrap() = settings;
         self.load_codeowners().await;
-        self.refresh_file_cache().await;
+        self.refresh_file_cache(true).await;
         self.refresh_all_open_documents().await;
     }
 
@@ -1830,6 +2605,20 @@ impl LanguageServer for Backend {
         let command = &params.command;
         let args = params.arguments;
 
+        if command == SHOW_MATCHES_COMMAND {
+            let uri = args
+                .first()
+                .and_then(|v| v.as_str())
+                .and_then(|s| Url::parse(s).ok())
+                .ok_or_else(|| {
+                    tower_lsp::jsonrpc::Error::invalid_params("Missing matches URI argument")
+                })?;
+            if let Err(e) = self.show_matches(uri).await {
+                self.client.show_message(MessageType::ERROR, &e).await;
+            }
+            return Ok(None);
+        }
+
         if args.first().and_then(|v| v.as_str()).is_none() {
             return Err(tower_lsp::jsonrpc::Error::invalid_params(
                 "Missing URI argument",
@@ -1844,17 +2633,31 @@ impl LanguageServer for Backend {
         let owner = args.get(2).and_then(|v| v.as_str());
 
         let is_custom = command.ends_with(".custom");
-        if is_custom && owner.is_none() {
-            self.client
-                .show_message(
-                    MessageType::INFO,
-                    "Custom owner feature requires editor support for input dialogs. Please manually edit the CODEOWNERS file.",
-                )
-                .await;
-            return Ok(None);
-        }
+        let picked = if is_custom && owner.is_none() {
+            match self.pick_owner(pattern).await {
+                OwnerPick::Owner(owner) => Some(owner),
+                OwnerPick::Other => {
+                    if let Err(e) = self.open_for_owner_entry(command, pattern).await {
+                        self.client.show_message(MessageType::ERROR, &e).await;
+                    }
+                    return Ok(None);
+                }
+                OwnerPick::Cancelled => return Ok(None),
+                OwnerPick::Unsupported => {
+                    self.client
+                        .show_message(
+                            MessageType::INFO,
+                            "Custom owner feature requires editor support for input dialogs. Please manually edit the CODEOWNERS file.",
+                        )
+                        .await;
+                    return Ok(None);
+                }
+            }
+        } else {
+            None
+        };
 
-        let owner = if let Some(o) = owner {
+        let owner = if let Some(o) = picked.as_deref().or(owner) {
             o.to_string()
         } else {
             let settings = self.settings.read().unwrap();
@@ -1873,19 +2676,18 @@ impl LanguageServer for Backend {
             }
         };
 
-        let result = if command.starts_with("codeowners.addToExisting") {
-            self.add_to_existing(pattern, &owner)
-        } else if command.starts_with("codeowners.takeOwnership") {
-            self.add_ownership(pattern, &owner)
-        } else {
-            Err(format!("Unknown command: {}", command))
+        let result = match self.ownership_edit(command, pattern, &owner) {
+            Ok((path, base, new_content)) => {
+                self.apply_codeowners_edit(&path, &base, &new_content).await
+            }
+            Err(e) => Err(e),
         };
 
         match result {
             Ok(()) => {
-                self.load_codeowners().await;
-                self.refresh_file_cache().await;
-                self.publish_codeowners_diagnostics().await;
+                // Ownership changed: refresh other open files and inlay hints
+                self.refresh_file_not_owned_diagnostics().await;
+                let _ = self.client.inlay_hint_refresh().await;
                 self.client
                     .show_message(
                         MessageType::INFO,
@@ -1909,7 +2711,7 @@ impl LanguageServer for Backend {
         }
 
         // Refresh file cache to pick up new/renamed/deleted files
-        self.refresh_file_cache().await;
+        self.refresh_file_cache(false).await;
 
         let position = params.text_document_position.position;
 
@@ -2129,6 +2931,15 @@ impl LanguageServer for Backend {
                     }
                 }
             }
+
+            // Rank owners of nearby directories first, like the owner picker
+            let line_pattern = line.split_whitespace().next().unwrap_or("");
+            let ranked = self.owner_choices(line_pattern);
+            for item in &mut items {
+                if let Some(rank) = ranked.iter().position(|o| *o == item.label) {
+                    item.sort_text = Some(format!("{:05}", rank));
+                }
+            }
         }
 
         if items.is_empty() {
@@ -2149,28 +2960,56 @@ impl LanguageServer for Backend {
             return Ok(None);
         };
 
-        let formatted = format_codeowners(&content);
+        let options = self.settings.read().unwrap().format_options();
+        let edits = handlers::formatting::document_edits(&content, &options);
+        Ok(if edits.is_empty() { None } else { Some(edits) })
+    }
+
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document.uri;
 
-        if formatted == content {
+        if !self.is_codeowners_file(uri) {
             return Ok(None);
         }
 
-        let line_count = content.lines().count();
-        let last_line_len = content.lines().last().map(|l| l.len()).unwrap_or(0);
+        let Some(content) = self.get_codeowners_content() else {
+            return Ok(None);
+        };
 
-        Ok(Some(vec![TextEdit {
-            range: Range {
-                start: Position {
-                    line: 0,
-                    character: 0,
-                },
-                end: Position {
-                    line: line_count as u32,
-                    character: last_line_len as u32,
-                },
-            },
-            new_text: formatted,
-        }]))
+        let options = self.settings.read().unwrap().format_options();
+        let edits = handlers::formatting::range_edits(
+            &content,
+            &options,
+            params.range.start.line,
+            params.range.end.line,
+        );
+        Ok(if edits.is_empty() { None } else { Some(edits) })
+    }
+
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document_position.text_document.uri;
+
+        if !self.is_codeowners_file(uri) {
+            return Ok(None);
+        }
+
+        let Some(content) = self.get_codeowners_content() else {
+            return Ok(None);
+        };
+
+        let options = self.settings.read().unwrap().format_options();
+        let edits = handlers::formatting::on_type_edits(
+            &content,
+            &options,
+            params.text_document_position.position.line,
+        );
+        Ok(if edits.is_empty() { None } else { Some(edits) })
     }
 
     async fn document_symbol(
@@ -2314,6 +3153,7 @@ impl LanguageServer for Backend {
         let Some(content) = self.get_codeowners_content() else {
             return Ok(None);
         };
+        self.wait_for_file_cache().await;
         let file_cache = self.file_cache.read().unwrap();
         let Some(ref cache) = *file_cache else {
             return Ok(None);
@@ -2326,6 +3166,27 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn document_link(&self, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>> {
+        if !self.is_codeowners_file(&params.text_document.uri) {
+            return Ok(None);
+        }
+        let Some(content) = self.get_codeowners_content() else {
+            return Ok(None);
+        };
+        let Some(root) = self.workspace_root.read().unwrap().clone() else {
+            return Ok(None);
+        };
+        let github_url = self.settings.read().unwrap().github_url().to_string();
+        let file_cache = self.file_cache.read().unwrap();
+        let links =
+            handlers::links::document_links(&content, &root, file_cache.as_deref(), &github_url);
+        if links.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(links))
+        }
+    }
+
     async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
         if !self.is_codeowners_file(&params.text_document_position_params.text_document.uri) {
             return Ok(None);
@@ -2378,6 +3239,58 @@ impl LanguageServer for Backend {
     }
 }
 
+/// Build a WorkspaceEdit replacing the whole of `old_content` in the file at `path`
+fn full_document_edit(path: &Path, old_content: &str, new_content: &str) -> Option<WorkspaceEdit> {
+    let uri = Url::from_file_path(path).ok()?;
+    let end = Position {
+        line: old_content.matches('\n').count() as u32,
+        character: old_content
+            .rsplit('\n')
+            .next()
+            .unwrap_or("")
+            .encode_utf16()
+            .count() as u32,
+    };
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri,
+        vec![TextEdit {
+            range: Range {
+                start: Position {
+                    line: 0,
+                    character: 0,
+                },
+                end,
+            },
+            new_text: new_content.to_string(),
+        }],
+    );
+
+    Some(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    })
+}
+
+/// Write `new_content` only if the file still holds `base` (the version the edit
+/// was computed from). Guards against clobbering concurrent edits and unsaved
+/// buffer changes that never reached disk.
+fn write_if_unchanged(
+    path: &Path,
+    base: &str,
+    new_content: &str,
+) -> std::result::Result<(), String> {
+    let on_disk =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read CODEOWNERS: {}", e))?;
+    if on_disk != base {
+        return Err(
+            "CODEOWNERS has unsaved or concurrent changes; save it and try again".to_string(),
+        );
+    }
+    fs::write(path, new_content).map_err(|e| format!("Failed to write CODEOWNERS: {}", e))
+}
+
 /// Find which line numbers changed between two versions of content
 fn find_changed_lines(old: &str, new: &str) -> Vec<usize> {
     let old_lines: Vec<&str> = old.lines().collect();
@@ -2451,7 +3364,7 @@ fn check_patterns_for_lines(
 }
 
 /// Format rich hover content for an owner in CODEOWNERS file
-fn format_owner_hover(owner: &str, info: Option<&github::OwnerInfo>) -> String {
+fn format_owner_hover(owner: &str, info: Option<&github::OwnerInfo>, github_url: &str) -> String {
     match info {
         Some(github::OwnerInfo::User(user)) => {
             let mut lines = Vec::new();
@@ -2513,54 +3426,29 @@ fn format_owner_hover(owner: &str, info: Option<&github::OwnerInfo>) -> String {
         }
         Some(github::OwnerInfo::Unknown(_)) | None => {
             // Build basic link even without cached info
-            let url = if let Some(username) = owner.strip_prefix('@') {
-                if username.contains('/') {
-                    let parts: Vec<&str> = username.splitn(2, '/').collect();
-                    if parts.len() == 2 {
-                        format!("https://github.com/orgs/{}/teams/{}", parts[0], parts[1])
-                    } else {
-                        format!("https://github.com/{}", username)
-                    }
-                } else {
-                    format!("https://github.com/{}", username)
-                }
-            } else {
-                String::new()
-            };
-
-            if url.is_empty() {
-                format!("## {}", owner)
-            } else {
-                format!("## [`{}`]({})\n\n*Not validated yet*", owner, url)
+            match owner_url(owner, github_url) {
+                Some(url) => format!("## [`{}`]({})\n\n*Not validated yet*", owner, url),
+                None => format!("## {}", owner),
             }
         }
     }
 }
 
 /// Format an owner as a clickable GitHub link (LSP-specific, uses markdown)
-fn format_owner_link(owner: &str) -> String {
-    if let Some(user) = owner.strip_prefix('@') {
-        if user.contains('/') {
-            // Team: @org/team -> https://github.com/orgs/org/teams/team
-            let parts: Vec<&str> = user.splitn(2, '/').collect();
-            if parts.len() == 2 {
-                let url = format!("https://github.com/orgs/{}/teams/{}", parts[0], parts[1]);
-                return format!("[`{}`]({})", owner, url);
-            }
-        } else {
-            // User: @user -> https://github.com/user
-            let url = format!("https://github.com/{}", user);
-            return format!("[`{}`]({})", owner, url);
-        }
-    } else if owner.contains('@') {
-        // Email - no link
-        return format!("`{}`", owner);
+fn format_owner_link(owner: &str, github_url: &str) -> String {
+    match owner_url(owner, github_url) {
+        Some(url) => format!("[`{}`]({})", owner, url),
+        // Emails and malformed owners - no link
+        None => format!("`{}`", owner),
     }
-    format!("`{}`", owner)
 }
 
 /// Format an owner with rich metadata if available
-fn format_owner_with_info(owner: &str, info: Option<&github::OwnerInfo>) -> String {
+fn format_owner_with_info(
+    owner: &str,
+    info: Option<&github::OwnerInfo>,
+    github_url: &str,
+) -> String {
     match info {
         Some(github::OwnerInfo::User(user)) => {
             let mut parts = vec![format!("[`{}`]({})", owner, user.html_url)];
@@ -2623,7 +3511,7 @@ fn format_owner_with_info(owner: &str, info: Option<&github::OwnerInfo>) -> Stri
 
             parts.join(" — ")
         }
-        _ => format_owner_link(owner),
+        _ => format_owner_link(owner, github_url),
     }
 }
 
@@ -2632,6 +3520,14 @@ async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(Backend::new);
+    let (service, socket) = LspService::build(Backend::new)
+        .custom_method(
+            TEXT_DOCUMENT_CONTENT_REQUEST,
+            Backend::text_document_content,
+        )
+        .custom_method(EXPLAIN_REQUEST, Backend::explain)
+        .custom_method(OWNERSHIP_TREE_REQUEST, Backend::ownership_tree)
+        .custom_method(OWNERS_FOR_FILES_REQUEST, Backend::owners_for_files)
+        .finish();
     Server::new(stdin, stdout, socket).serve(service).await;
 }
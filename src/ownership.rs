@@ -3,14 +3,27 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
+
 use crate::file_cache::FileCache;
-use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
+use crate::formatter::{diff_lines, uses_crlf, DiffOp};
+use crate::parser::{
+    find_insertion_point_with_owner, parse_codeowners_file, parse_codeowners_file_with_positions,
+    serialize_codeowners, CodeownersLine,
+};
+use crate::path_suggest::{confident_suggestion, suggest_replacements};
 use crate::pattern::pattern_matches;
 
+/// CODEOWNERS locations relative to the repository root, in lookup order.
+/// The working tree and git revisions are both searched in this order.
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = ["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"];
+
 /// Find a CODEOWNERS file starting from the given directory
-#[allow(dead_code)] // Used by CLI only
 pub fn find_codeowners(start: &Path) -> Option<PathBuf> {
-    codeowners::locate(start)
+    CODEOWNERS_LOCATIONS
+        .iter()
+        .map(|location| start.join(location))
+        .find(|path| path.exists())
 }
 
 /// Get the repository root from a CODEOWNERS file path
@@ -69,6 +82,305 @@ pub fn check_file_ownership_parsed(
     matching_rule
 }
 
+/// One rule that matches a path, as reported by [`explain_ownership`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleMatch {
+    /// Line number (1-indexed)
+    pub line: u32,
+    pub pattern: String,
+    pub owners: Vec<String>,
+    /// Whether this rule decides ownership (the last match)
+    pub winner: bool,
+    /// Why the rule wins or loses
+    pub reason: String,
+}
+
+/// Every rule matching `file_path`, in file order, with the winner marked
+pub fn explain_ownership(content: &str, file_path: &str) -> Vec<RuleMatch> {
+    let lines = parse_codeowners_file_with_positions(content);
+    explain_ownership_parsed(&lines, file_path)
+}
+
+/// Explain ownership against pre-parsed lines
+pub fn explain_ownership_parsed(
+    lines: &[crate::parser::ParsedLine],
+    file_path: &str,
+) -> Vec<RuleMatch> {
+    let file_path = file_path.trim_start_matches("./");
+
+    let matching: Vec<(u32, &String, &Vec<String>)> = lines
+        .iter()
+        .filter_map(|l| match &l.content {
+            CodeownersLine::Rule { pattern, owners } if pattern_matches(pattern, file_path) => {
+                Some((l.line_number + 1, pattern, owners))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let Some(&(winner_line, winner_pattern, winner_owners)) = matching.last() else {
+        return Vec::new();
+    };
+
+    matching
+        .iter()
+        .map(|&(line, pattern, owners)| {
+            let winner = line == winner_line;
+            let reason = if winner {
+                let how = if matching.len() == 1 {
+                    "only matching rule"
+                } else {
+                    "last matching rule wins"
+                };
+                if owners.is_empty() {
+                    format!("{}; it lists no owners, so the file is unowned", how)
+                } else {
+                    how.to_string()
+                }
+            } else {
+                let same = if owners == winner_owners {
+                    " (same owners)"
+                } else {
+                    ""
+                };
+                format!(
+                    "overridden by '{}' on line {}, which matches later{}",
+                    winner_pattern, winner_line, same
+                )
+            };
+            RuleMatch {
+                line,
+                pattern: pattern.clone(),
+                owners: owners.clone(),
+                winner,
+                reason,
+            }
+        })
+        .collect()
+}
+
+/// A file whose owners change with an edit (empty owners = unowned)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerChange {
+    pub file: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Files moving from one owner set to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnerTransition {
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// Files whose owners differ between two versions of a CODEOWNERS file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnershipImpact {
+    pub changes: Vec<OwnerChange>,
+}
+
+/// Compare winning rules before and after an edit for every cached file.
+/// Only files matched by added, removed or reordered rules can change, so
+/// the rest are never checked.
+pub fn ownership_impact(old: &str, new: &str, cache: &FileCache) -> OwnershipImpact {
+    let old_lines = parse_codeowners_file_with_positions(old);
+    let new_lines = parse_codeowners_file_with_positions(new);
+    let rules = |lines: &[crate::parser::ParsedLine]| -> Vec<(String, Vec<String>)> {
+        lines
+            .iter()
+            .filter_map(|l| match &l.content {
+                CodeownersLine::Rule { pattern, owners } => Some((pattern.clone(), owners.clone())),
+                _ => None,
+            })
+            .collect()
+    };
+    let old_rules = rules(&old_lines);
+    let new_rules = rules(&new_lines);
+
+    let mut changed_patterns: Vec<&str> = diff_lines(&old_rules, &new_rules)
+        .into_iter()
+        .filter_map(|op| match op {
+            DiffOp::Delete(i) => Some(old_rules[i].0.as_str()),
+            DiffOp::Insert(j) => Some(new_rules[j].0.as_str()),
+            DiffOp::Equal(..) => None,
+        })
+        .collect();
+    changed_patterns.sort();
+    changed_patterns.dedup();
+
+    let mut candidates: Vec<&String> = changed_patterns
+        .iter()
+        .flat_map(|p| cache.get_matches(p))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let owners_of = |lines: &[crate::parser::ParsedLine], file: &str| {
+        check_file_ownership_parsed(lines, file)
+            .map(|r| r.owners)
+            .unwrap_or_default()
+    };
+    let changes = candidates
+        .into_iter()
+        .filter_map(|file| {
+            let before = owners_of(&old_lines, file);
+            let after = owners_of(&new_lines, file);
+            (before != after).then(|| OwnerChange {
+                file: file.clone(),
+                before,
+                after,
+            })
+        })
+        .collect();
+
+    OwnershipImpact { changes }
+}
+
+impl OwnershipImpact {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// New owners ("unowned" when none) and how many files go to them,
+    /// largest group first
+    pub fn by_new_owners(&self) -> Vec<(String, usize)> {
+        let mut groups: HashMap<String, usize> = HashMap::new();
+        for change in &self.changes {
+            let owners = if change.after.is_empty() {
+                "unowned".to_string()
+            } else {
+                change.after.join(" ")
+            };
+            *groups.entry(owners).or_default() += 1;
+        }
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        groups
+    }
+
+    /// Changes grouped by old → new owner set, largest group first
+    pub fn transitions(&self) -> Vec<OwnerTransition> {
+        let mut groups: HashMap<(&[String], &[String]), Vec<String>> = HashMap::new();
+        for change in &self.changes {
+            groups
+                .entry((&change.before, &change.after))
+                .or_default()
+                .push(change.file.clone());
+        }
+        let mut transitions: Vec<OwnerTransition> = groups
+            .into_iter()
+            .map(|((before, after), files)| OwnerTransition {
+                before: before.to_vec(),
+                after: after.to_vec(),
+                files,
+            })
+            .collect();
+        transitions.sort_by(|a, b| {
+            b.files
+                .len()
+                .cmp(&a.files.len())
+                .then_with(|| a.before.cmp(&b.before))
+                .then_with(|| a.after.cmp(&b.after))
+        });
+        transitions
+    }
+
+    /// One-line summary, e.g. "reassigns 42 files: 30 → @org/a, 12 → unowned"
+    pub fn summary(&self) -> String {
+        const MAX_GROUPS: usize = 3;
+        if self.is_empty() {
+            return "changes no file owners".to_string();
+        }
+        let groups = self.by_new_owners();
+        let mut parts: Vec<String> = groups
+            .iter()
+            .take(MAX_GROUPS)
+            .map(|(owners, count)| format!("{} → {}", count, owners))
+            .collect();
+        if groups.len() > MAX_GROUPS {
+            let rest: usize = groups[MAX_GROUPS..].iter().map(|(_, count)| count).sum();
+            parts.push(format!("{} → other owners", rest));
+        }
+        format!(
+            "reassigns {} file{}: {}",
+            self.changes.len(),
+            if self.changes.len() == 1 { "" } else { "s" },
+            parts.join(", ")
+        )
+    }
+}
+
+/// Content with line `line` (0-indexed) removed
+pub fn remove_line(content: &str, line: usize) -> String {
+    let newline = if uses_crlf(content) { "\r\n" } else { "\n" };
+    let mut output = content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| *i != line)
+        .map(|(_, l)| l)
+        .collect::<Vec<_>>()
+        .join(newline);
+    if content.ends_with('\n') && !output.is_empty() {
+        output.push_str(newline);
+    }
+    output
+}
+
+/// Insert a new rule for `pattern` next to related rules and return the updated content
+pub fn add_rule(content: &str, pattern: &str, owner: &str) -> String {
+    let mut lines = parse_codeowners_file(content);
+    let insertion_point = find_insertion_point_with_owner(&lines, pattern, Some(owner));
+
+    lines.insert(
+        insertion_point,
+        CodeownersLine::Rule {
+            pattern: pattern.to_string(),
+            owners: vec![owner.to_string()],
+        },
+    );
+
+    with_trailing_newline(serialize_codeowners(&lines))
+}
+
+/// Add `owner` to the rule that currently owns `pattern` (last match wins)
+/// and return the updated content
+pub fn add_owner_to_matching_rule(
+    content: &str,
+    pattern: &str,
+    owner: &str,
+) -> Result<String, String> {
+    let mut lines = parse_codeowners_file(content);
+
+    // Strip leading slash from pattern to get relative path
+    let relative_path = pattern.trim_start_matches('/');
+
+    let idx = lines
+        .iter()
+        .rposition(|line| {
+            matches!(line, CodeownersLine::Rule { pattern: rule_pattern, .. }
+                if pattern_matches(rule_pattern, relative_path))
+        })
+        .ok_or("No matching rule found")?;
+
+    if let CodeownersLine::Rule { owners, .. } = &mut lines[idx] {
+        if !owners.iter().any(|o| o == owner) {
+            owners.push(owner.to_string());
+        }
+    }
+
+    Ok(with_trailing_newline(serialize_codeowners(&lines)))
+}
+
+fn with_trailing_newline(content: String) -> String {
+    if content.ends_with('\n') {
+        content
+    } else {
+        format!("{}\n", content)
+    }
+}
+
 /// Fixes applied to a CODEOWNERS file
 pub struct FixResult {
     pub content: String,
@@ -77,7 +389,9 @@ pub struct FixResult {
 
 /// Apply safe fixes to CODEOWNERS content.
 /// Safe fixes: duplicate owners, exact duplicate patterns (shadowed rules),
-/// and patterns matching no files (when file_cache is provided).
+/// and patterns matching no files (when file_cache is provided). A no-match
+/// pattern with exactly one confident "did you mean" candidate is rewritten
+/// to that candidate instead of being removed.
 pub fn apply_safe_fixes(content: &str, file_cache: Option<&FileCache>) -> FixResult {
     let lines = parse_codeowners_file_with_positions(content);
     let original_lines: Vec<&str> = content.lines().collect();
@@ -123,9 +437,25 @@ pub fn apply_safe_fixes(content: &str, file_cache: Option<&FileCache>) -> FixRes
             }
             exact_patterns.insert(normalized_pattern.to_string(), line_num);
 
-            // Fix 3: Remove patterns that match no files
+            // Fix 3: Repair or remove patterns that match no files
             if let Some(cache) = file_cache {
                 if !cache.has_matches(pattern) {
+                    let suggestions = suggest_replacements(pattern, cache);
+                    if let Some(suggestion) = confident_suggestion(&suggestions) {
+                        let current = line_replacements
+                            .get(&line_num)
+                            .cloned()
+                            .unwrap_or_else(|| original_lines[line_num].to_string());
+                        line_replacements
+                            .insert(line_num, current.replacen(pattern, &suggestion.pattern, 1));
+                        fixes.push(format!(
+                            "line {}: replaced pattern '{}' with '{}' (matches no files)",
+                            line_num + 1,
+                            pattern,
+                            suggestion.pattern
+                        ));
+                        continue;
+                    }
                     lines_to_delete.insert(line_num);
                     fixes.push(format!(
                         "line {}: removed pattern '{}' (matches no files)",
@@ -196,6 +526,62 @@ mod tests {
         assert_eq!(result.fixes.len(), 1);
     }
 
+    #[test]
+    fn test_apply_safe_fixes_repairs_typo_pattern() {
+        let cache = FileCache::from_files(vec!["services/api/main.rs".to_string()]);
+        let content = "/servces/api/ @api-team\n/nowhere/ @ghost\n";
+        let result = apply_safe_fixes(content, Some(&cache));
+        assert_eq!(result.content, "/services/api/ @api-team\n");
+        assert_eq!(result.fixes.len(), 2);
+        assert!(result.fixes[0].contains("replaced pattern"));
+    }
+
+    #[test]
+    fn test_explain_ownership() {
+        let content = "* @org/default\n/src/ @org/infra\n*.md @docs\n/src/api/ @org/infra\n";
+        let matches = explain_ownership(content, "src/api/main.rs");
+        assert_eq!(
+            matches.iter().map(|m| m.line).collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+        assert!(matches[2].winner);
+        assert_eq!(matches[2].reason, "last matching rule wins");
+        assert!(!matches[0].winner);
+        assert_eq!(
+            matches[0].reason,
+            "overridden by '/src/api/' on line 4, which matches later"
+        );
+        assert!(matches[1].reason.ends_with("(same owners)"));
+
+        let unowned = explain_ownership("/docs/ @docs\n/docs/internal/\n", "docs/internal/a.md");
+        assert!(unowned[1].reason.contains("no owners"));
+        assert!(explain_ownership(content, "README").len() == 1);
+        assert!(explain_ownership("/src/ @a\n", "README").is_empty());
+    }
+
+    #[test]
+    fn test_add_rule_keeps_related_rules_together() {
+        let content = "/src/ @src-team\n/docs/ @docs-team\n";
+        let result = add_rule(content, "/src/main.rs", "@me");
+        assert_eq!(
+            result,
+            "/src/ @src-team\n/src/main.rs @me\n/docs/ @docs-team\n"
+        );
+    }
+
+    #[test]
+    fn test_add_owner_to_matching_rule() {
+        let content = "* @default\n/src/ @src-team\n";
+        let result = add_owner_to_matching_rule(content, "/src/main.rs", "@me").unwrap();
+        assert_eq!(result, "* @default\n/src/ @src-team @me\n");
+
+        // Already an owner - unchanged
+        let again = add_owner_to_matching_rule(&result, "/src/main.rs", "@me").unwrap();
+        assert_eq!(again, result);
+
+        assert!(add_owner_to_matching_rule("/docs/ @d\n", "/src/main.rs", "@me").is_err());
+    }
+
     #[test]
     fn test_get_repo_root() {
         let path = PathBuf::from("/project/.github/CODEOWNERS");
@@ -222,6 +608,24 @@ mod tests {
         assert_eq!(get_repo_root(&path, &fallback), PathBuf::from("/project"));
     }
 
+    #[test]
+    fn test_find_codeowners_lookup_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert_eq!(find_codeowners(root), None);
+
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/CODEOWNERS"), "").unwrap();
+        assert_eq!(find_codeowners(root), Some(root.join("docs/CODEOWNERS")));
+
+        std::fs::create_dir_all(root.join(".github")).unwrap();
+        std::fs::write(root.join(".github/CODEOWNERS"), "").unwrap();
+        assert_eq!(find_codeowners(root), Some(root.join(".github/CODEOWNERS")));
+
+        std::fs::write(root.join("CODEOWNERS"), "").unwrap();
+        assert_eq!(find_codeowners(root), Some(root.join("CODEOWNERS")));
+    }
+
     #[test]
     fn test_get_repo_root_fallback() {
         // Test fallback when parent is None (root path)
@@ -239,4 +643,54 @@ mod tests {
         assert_eq!(result.pattern, "*.rs");
         assert_eq!(result.owners, vec!["@rust"]);
     }
+
+    fn impact_cache() -> FileCache {
+        FileCache::from_files(vec![
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "src/auth/login.rs".to_string(),
+            "docs/guide.md".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_ownership_impact_of_deleting_rule() {
+        let old = "* @org/a\n/src/ @org/b\n/src/auth/ @org/c\n";
+        let new = remove_line(old, 1);
+        assert_eq!(new, "* @org/a\n/src/auth/ @org/c\n");
+
+        let impact = ownership_impact(old, &new, &impact_cache());
+        assert_eq!(impact.changes.len(), 2);
+        assert_eq!(impact.changes[0].file, "src/lib.rs");
+        assert_eq!(impact.changes[0].before, vec!["@org/b"]);
+        assert_eq!(impact.changes[0].after, vec!["@org/a"]);
+        assert_eq!(impact.summary(), "reassigns 2 files: 2 → @org/a");
+    }
+
+    #[test]
+    fn test_ownership_impact_summary_groups() {
+        let old = "/src/ @org/b\n/docs/ @org/d\n";
+        let new = "/src/main.rs @org/b\n/docs/ @org/a\n";
+        let impact = ownership_impact(old, new, &impact_cache());
+        assert_eq!(
+            impact.summary(),
+            "reassigns 3 files: 2 → unowned, 1 → @org/a"
+        );
+        let transitions = impact.transitions();
+        assert_eq!(transitions[0].before, vec!["@org/b"]);
+        assert!(transitions[0].after.is_empty());
+        assert_eq!(
+            transitions[0].files,
+            vec!["src/auth/login.rs", "src/lib.rs"]
+        );
+        assert_eq!(transitions[1].files, vec!["docs/guide.md"]);
+        // Owner-preserving edits change nothing
+        let same = ownership_impact(
+            old,
+            "/src/ @org/b\n/docs/guide.md @org/d\n",
+            &impact_cache(),
+        );
+        assert!(same.is_empty());
+        assert_eq!(same.summary(), "changes no file owners");
+    }
 }
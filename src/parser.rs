@@ -236,7 +236,7 @@ pub fn find_insertion_point_with_owner(
 /// "/src/foo/bar.rs" -> "/src/foo"
 /// "/src/**/*.rs" -> "/src"
 /// "*.js" -> ""
-fn get_directory_prefix(pattern: &str) -> String {
+pub fn get_directory_prefix(pattern: &str) -> String {
     // Remove leading / for comparison
     let p = pattern.strip_prefix('/').unwrap_or(pattern);
 
@@ -267,7 +267,7 @@ fn get_directory_prefix(pattern: &str) -> String {
 /// Count common directory depth between two paths
 /// "/src/foo" and "/src/foo/bar" -> 2 (src, foo)
 /// "/src/foo" and "/lib/bar" -> 0
-fn common_prefix_depth(a: &str, b: &str) -> usize {
+pub fn common_prefix_depth(a: &str, b: &str) -> usize {
     if a.is_empty() || b.is_empty() {
         return 0;
     }
@@ -333,69 +333,7 @@ pub fn find_owner_at_position(line: &str, char_idx: usize) -> Option<String> {
 
 /// Format a CODEOWNERS file: normalize rule spacing, preserve comments exactly
 pub fn format_codeowners(content: &str) -> String {
-    let mut result = Vec::new();
-    let mut prev_was_empty = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-
-        // Preserve blank lines but collapse multiple
-        if trimmed.is_empty() {
-            if !prev_was_empty && !result.is_empty() {
-                result.push(String::new());
-            }
-            prev_was_empty = true;
-            continue;
-        }
-        prev_was_empty = false;
-
-        // Comments: preserve exactly as-is (people use specific formatting)
-        if trimmed.starts_with('#') {
-            result.push(line.to_string());
-            continue;
-        }
-
-        // Rules: normalize spacing between pattern and owners, preserve inline comments
-        let parts: Vec<&str> = trimmed
-            .split_whitespace()
-            .take_while(|part| !part.starts_with('#'))
-            .collect();
-        if parts.is_empty() {
-            continue;
-        }
-
-        let pattern = parts[0];
-        let owners = &parts[1..];
-
-        // Find inline comment in the original line
-        let inline_comment = find_inline_comment_start(line).map(|char_off| {
-            line.chars()
-                .skip(char_off)
-                .collect::<String>()
-                .trim_end()
-                .to_string()
-        });
-
-        let mut formatted = if owners.is_empty() {
-            pattern.to_string()
-        } else {
-            format!("{} {}", pattern, owners.join(" "))
-        };
-
-        if let Some(comment) = inline_comment {
-            formatted.push(' ');
-            formatted.push_str(&comment);
-        }
-
-        result.push(formatted);
-    }
-
-    // Ensure trailing newline
-    let mut output = result.join("\n");
-    if !output.is_empty() && !output.ends_with('\n') {
-        output.push('\n');
-    }
-    output
+    crate::formatter::format_with_options(content, &crate::formatter::FormatOptions::default())
 }
 
 #[cfg(test)]
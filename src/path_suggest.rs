@@ -0,0 +1,497 @@
+//! "Did you mean" suggestions for patterns that match no files.
+//!
+//! Most `pattern-no-match` warnings come from typos (`/servces/api/`) or
+//! directories that were renamed after the rule was written. This module
+//! repairs such patterns by walking the workspace directory tree segment by
+//! segment (closest names by edit distance) and by replaying git rename
+//! history onto the pattern.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+use crate::file_cache::FileCache;
+use crate::pattern::CompiledPattern;
+
+/// Maximum number of suggestions returned for a single pattern
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Number of partial repairs kept per segment while searching the tree
+const BEAM_WIDTH: usize = 8;
+
+/// How many recent commits to search for renames; older history rarely
+/// explains a stale CODEOWNERS pattern and is slow to walk in large repos
+const RENAME_HISTORY_COMMITS: usize = 2000;
+
+/// A replacement pattern for a rule that matches no files
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathSuggestion {
+    /// The replacement pattern (same anchoring and trailing slash as the original)
+    pub pattern: String,
+    /// Total edit distance across repaired segments (0 for rename-derived suggestions)
+    pub distance: usize,
+    /// Number of git renames that map the old pattern onto this one
+    pub renames: usize,
+}
+
+impl PathSuggestion {
+    /// Whether this suggestion came from git rename history
+    pub fn from_rename(&self) -> bool {
+        self.renames > 0
+    }
+}
+
+/// Directory tree of the workspace: parent directory -> child names
+pub struct PathIndex {
+    children: HashMap<String, HashSet<String>>,
+    /// Every file and directory name at any depth (for unanchored single-segment patterns)
+    names: HashSet<String>,
+}
+
+impl PathIndex {
+    /// Build the index from a flat list of repository-relative file paths
+    pub fn new(files: &[String]) -> Self {
+        let mut children: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut names = HashSet::new();
+
+        for file in files {
+            let mut parent = String::new();
+            for segment in file.split('/') {
+                children
+                    .entry(parent.clone())
+                    .or_default()
+                    .insert(segment.to_string());
+                names.insert(segment.to_string());
+                if !parent.is_empty() {
+                    parent.push('/');
+                }
+                parent.push_str(segment);
+            }
+        }
+
+        Self { children, names }
+    }
+}
+
+/// Load file renames from recent git history as (old_path, new_path) pairs,
+/// newest first
+pub fn load_renames(repo_root: &Path) -> Vec<(String, String)> {
+    let max_count = format!("--max-count={}", RENAME_HISTORY_COMMITS);
+    let output = Command::new("git")
+        .args([
+            "log",
+            "-M",
+            "--diff-filter=R",
+            "--name-status",
+            "--format=",
+            &max_count,
+            "HEAD",
+        ])
+        .current_dir(repo_root)
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_rename_log(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Rename history shared between file scans. Renames only change with new
+/// commits, so the history is kept until HEAD moves.
+#[derive(Default)]
+pub struct RenameHistory {
+    head: Option<String>,
+    renames: Arc<OnceLock<Vec<(String, String)>>>,
+}
+
+impl RenameHistory {
+    /// The (possibly not yet loaded) renames for `head`, starting over when
+    /// HEAD differs from the previous call
+    pub fn for_head(&mut self, head: Option<String>) -> Arc<OnceLock<Vec<(String, String)>>> {
+        if head != self.head {
+            self.head = head;
+            self.renames = Arc::new(OnceLock::new());
+        }
+        self.renames.clone()
+    }
+}
+
+/// Parse `git log --name-status` output, keeping only rename entries
+fn parse_rename_log(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let status = parts.next()?;
+            if !status.starts_with('R') {
+                return None;
+            }
+            let old = parts.next()?;
+            let new = parts.next()?;
+            Some((old.to_string(), new.to_string()))
+        })
+        .collect()
+}
+
+/// Suggest replacement patterns for a pattern that matches no files.
+///
+/// Rename-derived suggestions come first (most renames first), followed by
+/// the closest existing paths by edit distance. Every suggestion is verified
+/// to match at least one file.
+pub fn suggest_replacements(pattern: &str, file_cache: &FileCache) -> Vec<PathSuggestion> {
+    let mut suggestions: Vec<PathSuggestion> = Vec::new();
+
+    for (candidate, count) in rename_candidates(pattern, file_cache.renames()) {
+        if candidate != pattern && file_cache.has_matches(&candidate) {
+            suggestions.push(PathSuggestion {
+                pattern: candidate,
+                distance: 0,
+                renames: count,
+            });
+        }
+    }
+
+    for (candidate, distance) in similar_candidates(pattern, file_cache.path_index()) {
+        if suggestions.iter().any(|s| s.pattern == candidate) {
+            continue;
+        }
+        if file_cache.has_matches(&candidate) {
+            suggestions.push(PathSuggestion {
+                pattern: candidate,
+                distance,
+                renames: 0,
+            });
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.renames
+            .cmp(&a.renames)
+            .then_with(|| a.distance.cmp(&b.distance))
+            .then_with(|| a.pattern.cmp(&b.pattern))
+    });
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Pick the suggestion that is safe to apply without asking.
+///
+/// Rename history is trusted over spelling: a single rename-derived candidate
+/// wins even if there are near-miss spellings. Otherwise the only candidate
+/// is used, and ambiguity means no automatic fix.
+pub fn confident_suggestion(suggestions: &[PathSuggestion]) -> Option<&PathSuggestion> {
+    let renamed: Vec<&PathSuggestion> = suggestions.iter().filter(|s| s.from_rename()).collect();
+    match renamed.len() {
+        1 => Some(renamed[0]),
+        0 if suggestions.len() == 1 => suggestions.first(),
+        _ => None,
+    }
+}
+
+/// Map a pattern through git renames, counting how many renames agree on each result
+fn rename_candidates(pattern: &str, renames: &[(String, String)]) -> Vec<(String, usize)> {
+    let anchor = if pattern.starts_with('/') { "/" } else { "" };
+    let body = pattern.trim_start_matches('/');
+    let compiled = CompiledPattern::new(pattern);
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (old, new) in renames {
+        if !compiled.matches(old) {
+            continue;
+        }
+
+        // Strip the common trailing segments to find what was actually renamed:
+        // servces/api/x.rs -> services/api/x.rs renames servces -> services
+        let mut old_segments: Vec<&str> = old.split('/').collect();
+        let mut new_segments: Vec<&str> = new.split('/').collect();
+        while old_segments.len() > 1
+            && new_segments.len() > 1
+            && old_segments.last() == new_segments.last()
+        {
+            old_segments.pop();
+            new_segments.pop();
+        }
+        let old_prefix = old_segments.join("/");
+        let new_prefix = new_segments.join("/");
+
+        let rest = if body == old_prefix {
+            ""
+        } else if let Some(rest) = body
+            .strip_prefix(old_prefix.as_str())
+            .filter(|r| r.starts_with('/'))
+        {
+            rest
+        } else {
+            continue;
+        };
+
+        *counts
+            .entry(format!("{}{}{}", anchor, new_prefix, rest))
+            .or_default() += 1;
+    }
+
+    counts.into_iter().collect()
+}
+
+/// Repair literal path segments against the directory tree, returning (pattern, distance)
+fn similar_candidates(pattern: &str, index: &PathIndex) -> Vec<(String, usize)> {
+    let anchor = if pattern.starts_with('/') { "/" } else { "" };
+    let trailing = if pattern.ends_with('/') { "/" } else { "" };
+    let body = pattern.trim_start_matches('/').trim_end_matches('/');
+    if body.is_empty() {
+        return Vec::new();
+    }
+    let segments: Vec<&str> = body.split('/').collect();
+
+    // Unanchored single-segment patterns (`docs/`, `Makefile`) match at any depth
+    if anchor.is_empty() && segments.len() == 1 {
+        let segment = segments[0];
+        if is_glob(segment) {
+            return Vec::new();
+        }
+        return closest_names(segment, index.names.iter())
+            .into_iter()
+            .map(|(name, distance)| (format!("{}{}", name, trailing), distance))
+            .collect();
+    }
+
+    // Beam search from the root, one literal segment at a time
+    let mut beam: Vec<(String, usize)> = vec![(String::new(), 0)];
+    let mut repaired = 0;
+    for segment in &segments {
+        if is_glob(segment) {
+            break;
+        }
+        let mut next: Vec<(String, usize)> = Vec::new();
+        for (prefix, cost) in &beam {
+            let Some(children) = index.children.get(prefix) else {
+                continue;
+            };
+            if children.contains(*segment) {
+                next.push((join_path(prefix, segment), *cost));
+                continue;
+            }
+            for (child, distance) in closest_names(segment, children.iter()) {
+                next.push((join_path(prefix, &child), cost + distance));
+            }
+        }
+        next.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        next.truncate(BEAM_WIDTH);
+        if next.is_empty() {
+            return Vec::new();
+        }
+        beam = next;
+        repaired += 1;
+    }
+
+    let rest = segments[repaired..].join("/");
+    beam.into_iter()
+        .filter(|(_, cost)| *cost > 0)
+        .map(|(prefix, cost)| {
+            let path = if rest.is_empty() {
+                prefix
+            } else {
+                format!("{}/{}", prefix, rest)
+            };
+            (format!("{}{}{}", anchor, path, trailing), cost)
+        })
+        .collect()
+}
+
+/// Names within the allowed edit distance of `segment`, closest first
+fn closest_names<'a>(
+    segment: &str,
+    names: impl Iterator<Item = &'a String>,
+) -> Vec<(String, usize)> {
+    let max = max_distance(segment);
+    let mut close: Vec<(String, usize)> = names
+        .filter(|name| name.len().abs_diff(segment.len()) <= max)
+        .filter_map(|name| {
+            let distance = edit_distance(segment, name);
+            (distance > 0 && distance <= max).then(|| (name.clone(), distance))
+        })
+        .collect();
+    close.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    close.truncate(BEAM_WIDTH);
+    close
+}
+
+/// Typo budget for a segment: short names tolerate a single edit
+fn max_distance(segment: &str) -> usize {
+    match segment.chars().count() {
+        0..=3 => 1,
+        4..=7 => 2,
+        _ => 3,
+    }
+}
+
+fn is_glob(segment: &str) -> bool {
+    segment.contains(['*', '?', '['])
+}
+
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+/// Edit distance between two strings where an adjacent swap counts as one edit
+/// (optimal string alignment), so `scr` -> `src` is a single typo
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(files: &[&str]) -> FileCache {
+        FileCache::from_files(files.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("servces", "services"), 1);
+        assert_eq!(edit_distance("api", "api"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("scr", "src"), 1);
+    }
+
+    #[test]
+    fn test_typo_in_directory_segment() {
+        let cache = cache(&["services/api/main.rs", "services/web/app.ts"]);
+        let suggestions = suggest_replacements("/servces/api/", &cache);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].pattern, "/services/api/");
+        assert_eq!(suggestions[0].distance, 1);
+        assert!(!suggestions[0].from_rename());
+    }
+
+    #[test]
+    fn test_typo_keeps_glob_suffix() {
+        let cache = cache(&["src/lib/util.rs"]);
+        let suggestions = suggest_replacements("/scr/**/*.rs", &cache);
+
+        assert_eq!(suggestions[0].pattern, "/src/**/*.rs");
+    }
+
+    #[test]
+    fn test_unanchored_single_segment_matches_any_depth() {
+        let cache = cache(&["packages/core/docs/intro.md"]);
+        let suggestions = suggest_replacements("dosc/", &cache);
+
+        assert_eq!(suggestions[0].pattern, "docs/");
+    }
+
+    #[test]
+    fn test_no_suggestion_when_too_different() {
+        let cache = cache(&["services/api/main.rs"]);
+        assert!(suggest_replacements("/frontend/", &cache).is_empty());
+    }
+
+    #[test]
+    fn test_rename_history_wins() {
+        let cache = cache(&["backend/api/main.rs", "servers/api/main.rs"]).with_renames(vec![(
+            "servces/api/main.rs".to_string(),
+            "backend/api/main.rs".to_string(),
+        )]);
+        let suggestions = suggest_replacements("/servces/api/", &cache);
+
+        assert_eq!(suggestions[0].pattern, "/backend/api/");
+        assert!(suggestions[0].from_rename());
+        assert_eq!(
+            confident_suggestion(&suggestions).map(|s| s.pattern.as_str()),
+            Some("/backend/api/")
+        );
+    }
+
+    #[test]
+    fn test_rename_of_exact_file() {
+        let cache = cache(&["docs/README.md"]).with_renames(vec![(
+            "docs/readme.md".to_string(),
+            "docs/README.md".to_string(),
+        )]);
+        let suggestions = suggest_replacements("/docs/readme.md", &cache);
+
+        assert_eq!(suggestions[0].pattern, "/docs/README.md");
+    }
+
+    #[test]
+    fn test_confident_suggestion_requires_single_candidate() {
+        let one = vec![PathSuggestion {
+            pattern: "/services/".to_string(),
+            distance: 1,
+            renames: 0,
+        }];
+        assert!(confident_suggestion(&one).is_some());
+
+        let two = vec![
+            one[0].clone(),
+            PathSuggestion {
+                pattern: "/servers/".to_string(),
+                distance: 1,
+                renames: 0,
+            },
+        ];
+        assert!(confident_suggestion(&two).is_none());
+        assert!(confident_suggestion(&[]).is_none());
+    }
+
+    #[test]
+    fn test_parse_rename_log() {
+        let output = "R100\told/a.rs\tnew/a.rs\nM\tsrc/lib.rs\n\nR087\tx.md\ty.md\n";
+        assert_eq!(
+            parse_rename_log(output),
+            vec![
+                ("old/a.rs".to_string(), "new/a.rs".to_string()),
+                ("x.md".to_string(), "y.md".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_history_kept_until_head_moves() {
+        let mut history = RenameHistory::default();
+        let first = history.for_head(Some("a".to_string()));
+        first
+            .set(vec![("old.rs".to_string(), "new.rs".to_string())])
+            .unwrap();
+
+        let same = history.for_head(Some("a".to_string()));
+        assert!(Arc::ptr_eq(&first, &same));
+
+        let moved = history.for_head(Some("b".to_string()));
+        assert!(moved.get().is_none());
+    }
+}
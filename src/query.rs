@@ -0,0 +1,408 @@
+//! Ownership queries: a small predicate language for listing files
+//!
+//! A query combines predicates with `and`, `or`, `not` and parentheses;
+//! predicates next to each other are joined with `and`. Each file is judged
+//! by its winning rule, the same way `check` resolves it.
+//!
+//! ```text
+//! owner:@org/web and ext:ts and not path:legacy/**
+//! unowned or owners>2
+//! rule:42                  winning rule on line 42 (or rule:/src/ by pattern)
+//! individual-only          every owner is a user or email, not a team
+//! ```
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::file_cache::FileCache;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::ParsedLine;
+use crate::pattern::CompiledPattern;
+
+/// A file and its winning rule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileOwnership {
+    pub path: String,
+    /// Pattern of the winning rule
+    pub rule: Option<String>,
+    /// Line of the winning rule (1-indexed)
+    pub line: Option<u32>,
+    pub owners: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn holds(self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+enum Predicate {
+    /// One of the winning owners (case-insensitive)
+    Owner(String),
+    /// File extension, without the dot
+    Ext(String),
+    /// CODEOWNERS-style pattern on the path
+    Path(CompiledPattern),
+    Owned,
+    Unowned,
+    RuleLine(u32),
+    RulePattern(String),
+    Owners(Comparison, usize),
+    IndividualOnly,
+    TeamOnly,
+}
+
+fn is_team(owner: &str) -> bool {
+    owner.starts_with('@') && owner.contains('/')
+}
+
+impl Predicate {
+    fn matches(&self, file: &FileOwnership) -> bool {
+        match self {
+            Predicate::Owner(owner) => file.owners.iter().any(|o| o.eq_ignore_ascii_case(owner)),
+            Predicate::Ext(ext) => file
+                .path
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.rsplit_once('.'))
+                .is_some_and(|(stem, e)| !stem.is_empty() && e.eq_ignore_ascii_case(ext)),
+            Predicate::Path(pattern) => pattern.matches(&file.path),
+            Predicate::Owned => !file.owners.is_empty(),
+            Predicate::Unowned => file.owners.is_empty(),
+            Predicate::RuleLine(line) => file.line == Some(*line),
+            Predicate::RulePattern(pattern) => file.rule.as_deref() == Some(pattern.as_str()),
+            Predicate::Owners(cmp, count) => cmp.holds(file.owners.len(), *count),
+            Predicate::IndividualOnly => {
+                !file.owners.is_empty() && !file.owners.iter().any(|o| is_team(o))
+            }
+            Predicate::TeamOnly => {
+                !file.owners.is_empty() && file.owners.iter().all(|o| is_team(o))
+            }
+        }
+    }
+
+    fn parse(word: &str) -> Result<Self, String> {
+        let lower = word.to_ascii_lowercase();
+        match lower.as_str() {
+            "owned" => return Ok(Predicate::Owned),
+            "unowned" => return Ok(Predicate::Unowned),
+            "individual-only" => return Ok(Predicate::IndividualOnly),
+            "team-only" => return Ok(Predicate::TeamOnly),
+            _ => {}
+        }
+
+        if let Some(rest) = lower.strip_prefix("owners") {
+            let (cmp, count) = [
+                ("<=", Comparison::LessOrEqual),
+                (">=", Comparison::GreaterOrEqual),
+                ("<", Comparison::Less),
+                (">", Comparison::Greater),
+                ("=", Comparison::Equal),
+                (":", Comparison::Equal),
+            ]
+            .into_iter()
+            .find_map(|(op, cmp)| rest.strip_prefix(op).map(|count| (cmp, count)))
+            .ok_or_else(|| format!("Expected a comparison in '{}' (e.g. owners>2)", word))?;
+            let count = count
+                .parse()
+                .map_err(|_| format!("Expected a number in '{}'", word))?;
+            return Ok(Predicate::Owners(cmp, count));
+        }
+
+        let (key, value) = word
+            .split_once(':')
+            .ok_or_else(|| format!("Unknown predicate '{}'", word))?;
+        if value.is_empty() {
+            return Err(format!("Missing value in '{}'", word));
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "owner" => Ok(Predicate::Owner(value.to_string())),
+            "ext" => Ok(Predicate::Ext(value.trim_start_matches('.').to_string())),
+            "path" => Ok(Predicate::Path(CompiledPattern::new(value))),
+            "rule" => Ok(match value.parse() {
+                Ok(line) => Predicate::RuleLine(line),
+                Err(_) => Predicate::RulePattern(value.to_string()),
+            }),
+            _ => Err(format!(
+                "Unknown predicate '{}' (expected owner:, ext:, path:, rule:, owners<op>N, owned, unowned, individual-only or team-only)",
+                key
+            )),
+        }
+    }
+}
+
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Is(Predicate),
+}
+
+impl Expr {
+    fn matches(&self, file: &FileOwnership) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(file) && b.matches(file),
+            Expr::Or(a, b) => a.matches(file) || b.matches(file),
+            Expr::Not(e) => !e.matches(file),
+            Expr::Is(predicate) => predicate.matches(file),
+        }
+    }
+}
+
+/// A parsed query
+pub struct Query(Expr);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+}
+
+/// Split on whitespace and parentheses; double quotes keep spaces in a value
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => word.push(c),
+            '(' | ')' => {
+                if !word.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut word)));
+                }
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut word)));
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    if !word.is_empty() {
+        tokens.push(Token::Word(word));
+    }
+    Ok(tokens)
+}
+
+fn is_keyword(token: Option<&Token>, keyword: &str) -> bool {
+    matches!(token, Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+}
+
+/// Recursive descent over the tokens: or > and > not > atom
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut query = self.and()?;
+        while is_keyword(self.peek(), "or") {
+            self.pos += 1;
+            query = Expr::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut query = self.not()?;
+        loop {
+            if is_keyword(self.peek(), "and") {
+                self.pos += 1;
+            } else if matches!(self.peek(), None | Some(Token::Close))
+                || is_keyword(self.peek(), "or")
+            {
+                return Ok(query);
+            }
+            query = Expr::And(Box::new(query), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if is_keyword(self.peek(), "not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("Expected ')'".to_string());
+                }
+                self.pos += 1;
+                Ok(query)
+            }
+            Some(Token::Close) => Err("Unexpected ')'".to_string()),
+            Some(Token::Word(word)) => {
+                if ["and", "or"].iter().any(|k| word.eq_ignore_ascii_case(k)) {
+                    return Err(format!("Expected a predicate before '{}'", word));
+                }
+                Predicate::parse(&word).map(Expr::Is)
+            }
+            None => Err("Expected a predicate at the end of the query".to_string()),
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err("Empty query".to_string());
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err("Unexpected ')'".to_string());
+        }
+        Ok(Query(expr))
+    }
+
+    pub fn matches(&self, file: &FileOwnership) -> bool {
+        self.0.matches(file)
+    }
+}
+
+/// Every file in `cache` with its winning rule, in path order
+pub fn file_ownership(rules: &[ParsedLine], cache: &FileCache) -> Vec<FileOwnership> {
+    let mut files: Vec<FileOwnership> = cache
+        .all_files()
+        .par_iter()
+        .map(|path| {
+            let result = check_file_ownership_parsed(rules, path);
+            FileOwnership {
+                path: path.clone(),
+                line: result.as_ref().map(|r| r.line_number + 1),
+                owners: result
+                    .as_ref()
+                    .map(|r| r.owners.clone())
+                    .unwrap_or_default(),
+                rule: result.map(|r| r.pattern),
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_codeowners_file_with_positions;
+
+    fn files() -> Vec<FileOwnership> {
+        let rules = parse_codeowners_file_with_positions(
+            "* @org/core\n*.ts @org/web\n/legacy/ @alice\n/shared/ @org/web @bob @carol\n/vendor/\n",
+        );
+        let cache = FileCache::from_files(
+            [
+                "README.md",
+                "src/app.ts",
+                "legacy/old.ts",
+                "shared/util.ts",
+                "vendor/lib.js",
+                "Makefile",
+            ]
+            .iter()
+            .map(|f| f.to_string())
+            .collect(),
+        );
+        file_ownership(&rules, &cache)
+    }
+
+    fn select(query: &str) -> Vec<String> {
+        let query = Query::parse(query).unwrap();
+        files()
+            .into_iter()
+            .filter(|f| query.matches(f))
+            .map(|f| f.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_query_predicates() {
+        assert_eq!(
+            select("owner:@org/web and ext:ts and not path:legacy/**"),
+            vec!["shared/util.ts", "src/app.ts"]
+        );
+        assert_eq!(select("unowned"), vec!["vendor/lib.js"]);
+        assert_eq!(select("rule:4"), vec!["shared/util.ts"]);
+        assert_eq!(select("rule:/legacy/"), vec!["legacy/old.ts"]);
+        assert_eq!(select("owners>2"), vec!["shared/util.ts"]);
+        assert_eq!(select("individual-only"), vec!["legacy/old.ts"]);
+        assert_eq!(
+            select("team-only and not ext:ts"),
+            vec!["Makefile", "README.md"]
+        );
+        assert_eq!(
+            select("OWNER:@ORG/WEB ext:.TS owners=1"),
+            vec!["src/app.ts"]
+        );
+    }
+
+    #[test]
+    fn test_query_precedence() {
+        // and binds tighter than or, not tighter than and
+        assert_eq!(
+            select("unowned or owner:@alice and not ext:md"),
+            vec!["legacy/old.ts", "vendor/lib.js"]
+        );
+        assert_eq!(
+            select("(unowned or owner:@alice) and not ext:ts"),
+            vec!["vendor/lib.js"]
+        );
+        assert_eq!(select("not not unowned"), vec!["vendor/lib.js"]);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let err = |q: &str| Query::parse(q).err().unwrap();
+        assert_eq!(err(""), "Empty query");
+        assert_eq!(err("(unowned"), "Expected ')'");
+        assert_eq!(err("unowned)"), "Unexpected ')'");
+        assert_eq!(
+            err("unowned and"),
+            "Expected a predicate at the end of the query"
+        );
+        assert_eq!(err("or unowned"), "Expected a predicate before 'or'");
+        assert_eq!(
+            err("owners~2"),
+            "Expected a comparison in 'owners~2' (e.g. owners>2)"
+        );
+        assert_eq!(err("path:\"a b"), "Unterminated quote");
+        assert!(err("color:red").starts_with("Unknown predicate 'color'"));
+        assert!(Query::parse("path:\"my docs/**\"").is_ok());
+    }
+}
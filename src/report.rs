@@ -0,0 +1,551 @@
+//! Machine-readable report formats for CI tools
+//!
+//! Commands turn their results into [`Finding`]s and pick a [`ReportFormat`]:
+//! SARIF 2.1.0 for GitHub code scanning, JUnit XML for test-report UIs,
+//! Checkstyle XML, and GitLab Code Quality JSON. Rule IDs are the
+//! [`codes`] constants, so they stay stable across releases.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+
+use crate::baseline;
+use crate::diagnostics::codes;
+
+const TOOL_NAME: &str = "codeowners-lsp";
+const TOOL_URI: &str = "https://github.com/radiosilence/codeowners-lsp";
+
+/// Every rule a finding can carry, with a short description
+pub const RULES: [(&str, &str); 9] = [
+    (
+        codes::INVALID_PATTERN,
+        "Pattern is not valid CODEOWNERS syntax",
+    ),
+    (
+        codes::INVALID_OWNER,
+        "Owner is not a @user, @org/team or email",
+    ),
+    (codes::PATTERN_NO_MATCH, "Pattern matches no files"),
+    (
+        codes::DUPLICATE_OWNER,
+        "Owner is listed more than once on a rule",
+    ),
+    (
+        codes::SHADOWED_RULE,
+        "Rule is overridden by a later rule for every file it matches",
+    ),
+    (codes::NO_OWNERS, "Rule has no owners"),
+    (
+        codes::GITHUB_OWNER_NOT_FOUND,
+        "Owner does not exist on GitHub",
+    ),
+    (
+        codes::GITHUB_OWNER_UNVERIFIED,
+        "Owner could not be verified on GitHub",
+    ),
+    (codes::FILE_NOT_OWNED, "File has no CODEOWNERS owner"),
+];
+
+/// Report output format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Sarif,
+    Junit,
+    Checkstyle,
+    Gitlab,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sarif" => Ok(ReportFormat::Sarif),
+            "junit" => Ok(ReportFormat::Junit),
+            "checkstyle" => Ok(ReportFormat::Checkstyle),
+            "gitlab" | "codequality" => Ok(ReportFormat::Gitlab),
+            _ => Err(format!(
+                "Invalid format '{}' (expected sarif, junit, checkstyle or gitlab)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// One problem at a location
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// One of the [`codes`] constants
+    pub rule_id: String,
+    pub level: Level,
+    pub message: String,
+    /// Repo-relative path
+    pub path: String,
+    /// 1-indexed line, None for whole-file findings
+    pub line: Option<u32>,
+    /// 1-indexed column
+    pub column: Option<u32>,
+    /// 1-indexed column just past the end
+    pub end_column: Option<u32>,
+    /// Line-independent key of the flagged rule text (see [`baseline::key`]),
+    /// so moving a rule keeps its GitLab fingerprint
+    pub key: Option<String>,
+}
+
+impl Finding {
+    /// A finding for a whole file
+    pub fn file(rule_id: &str, level: Level, path: &str, message: String) -> Self {
+        Finding {
+            rule_id: rule_id.to_string(),
+            level,
+            message,
+            path: path.to_string(),
+            line: None,
+            column: None,
+            end_column: None,
+            key: None,
+        }
+    }
+
+    /// A finding for an LSP diagnostic in `path`, whose content is `content`
+    pub fn from_diagnostic(path: &str, content: &str, diagnostic: &Diagnostic) -> Self {
+        let rule_id = match &diagnostic.code {
+            Some(NumberOrString::String(code)) => code.clone(),
+            Some(NumberOrString::Number(code)) => code.to_string(),
+            None => String::new(),
+        };
+        let level = match diagnostic.severity {
+            Some(DiagnosticSeverity::ERROR) => Level::Error,
+            Some(DiagnosticSeverity::WARNING) => Level::Warning,
+            _ => Level::Note,
+        };
+        let range = diagnostic.range;
+        // Owner diagnostics run to the end of the line (u32::MAX); report
+        // those without an end column rather than a made-up one
+        let end_column = (range.end.line == range.start.line && range.end.character != u32::MAX)
+            .then(|| range.end.character.saturating_add(1));
+        Finding {
+            rule_id,
+            level,
+            message: diagnostic.message.clone(),
+            path: path.to_string(),
+            line: Some(range.start.line + 1),
+            column: Some(range.start.character + 1),
+            end_column,
+            key: Some(baseline::key(content, diagnostic).1),
+        }
+    }
+
+    /// Key the finding by the text its line and columns cover in `content`
+    pub fn keyed(mut self, content: &str) -> Self {
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            let start = Position::new(line - 1, column - 1);
+            let end = Position::new(line - 1, self.end_column.map_or(u32::MAX, |c| c - 1));
+            self.key = Some(baseline::rule_fingerprint(content, Range::new(start, end)).0);
+        }
+        self
+    }
+}
+
+/// `path` relative to `root` with forward slashes, as report consumers expect
+pub fn repo_relative(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Render `findings` from the check named `suite` (e.g. "lint")
+pub fn render(format: ReportFormat, suite: &str, findings: &[Finding]) -> String {
+    match format {
+        ReportFormat::Sarif => serde_json::to_string_pretty(&sarif(findings)).unwrap(),
+        ReportFormat::Junit => junit(suite, findings),
+        ReportFormat::Checkstyle => checkstyle(findings),
+        ReportFormat::Gitlab => serde_json::to_string_pretty(&gitlab(findings)).unwrap(),
+    }
+}
+
+fn sarif(findings: &[Finding]) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let mut location = json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": finding.path },
+                },
+            });
+            if let Some(line) = finding.line {
+                let mut region = json!({ "startLine": line });
+                if let Some(column) = finding.column {
+                    region["startColumn"] = json!(column);
+                }
+                if let Some(end_column) = finding.end_column {
+                    region["endColumn"] = json!(end_column);
+                }
+                location["physicalLocation"]["region"] = region;
+            }
+
+            let mut result = json!({
+                "ruleId": finding.rule_id,
+                "level": finding.level.as_str(),
+                "message": { "text": finding.message },
+                "locations": [location],
+            });
+            if let Some(index) = RULES.iter().position(|(id, _)| *id == finding.rule_id) {
+                result["ruleIndex"] = json!(index);
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_URI,
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn location(finding: &Finding) -> String {
+    match finding.line {
+        Some(line) => format!("{}:{}", finding.path, line),
+        None => finding.path.clone(),
+    }
+}
+
+/// One testcase per finding, or a single passing testcase when clean
+fn junit(suite: &str, findings: &[Finding]) -> String {
+    let failures = findings.iter().filter(|f| f.level != Level::Note).count();
+    let tests = findings.len().max(1);
+    let suite = xml_escape(suite);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{TOOL_NAME}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">\n"
+    ));
+    if findings.is_empty() {
+        xml.push_str(&format!(
+            "    <testcase name=\"{suite}\" classname=\"{TOOL_NAME}.{suite}\"/>\n"
+        ));
+    }
+    for finding in findings {
+        let name = xml_escape(&format!("{} {}", finding.rule_id, location(finding)));
+        xml.push_str(&format!(
+            "    <testcase name=\"{name}\" classname=\"{TOOL_NAME}.{suite}\" file=\"{}\">\n",
+            xml_escape(&finding.path)
+        ));
+        let message = xml_escape(&finding.message);
+        if finding.level == Level::Note {
+            xml.push_str(&format!("      <system-out>{message}</system-out>\n"));
+        } else {
+            xml.push_str(&format!(
+                "      <failure message=\"{message}\" type=\"{}\">{}: {message}</failure>\n",
+                xml_escape(&finding.rule_id),
+                xml_escape(&location(finding))
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>");
+    xml
+}
+
+/// Findings grouped by file, in order of first appearance
+fn checkstyle(findings: &[Finding]) -> String {
+    let mut files: Vec<(&str, Vec<&Finding>)> = Vec::new();
+    for finding in findings {
+        match files.iter_mut().find(|(path, _)| *path == finding.path) {
+            Some((_, group)) => group.push(finding),
+            None => files.push((&finding.path, vec![finding])),
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<checkstyle version=\"4.3\">\n");
+    for (path, group) in files {
+        xml.push_str(&format!("  <file name=\"{}\">\n", xml_escape(path)));
+        for finding in group {
+            let severity = match finding.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                Level::Note => "info",
+            };
+            xml.push_str(&format!(
+                "    <error line=\"{}\"{} severity=\"{}\" message=\"{}\" source=\"{}.{}\"/>\n",
+                finding.line.unwrap_or(0),
+                finding
+                    .column
+                    .map(|c| format!(" column=\"{}\"", c))
+                    .unwrap_or_default(),
+                severity,
+                xml_escape(&finding.message),
+                TOOL_NAME,
+                xml_escape(&finding.rule_id)
+            ));
+        }
+        xml.push_str("  </file>\n");
+    }
+    xml.push_str("</checkstyle>");
+    xml
+}
+
+/// Stable fingerprint (FNV-1a) so GitLab can track findings between runs.
+/// Findings on a rule are keyed like the baseline, so edits elsewhere in the
+/// file don't turn them into new issues.
+fn fingerprint(finding: &Finding) -> String {
+    stable_hash(&format!(
+        "{}\0{}\0{}",
+        finding.rule_id,
+        finding.path,
+        finding.key.as_deref().unwrap_or(&finding.message)
+    ))
+}
+
+/// 64-bit FNV-1a of `key` as hex; unlike `DefaultHasher` it never changes
+/// between Rust releases, so it's safe to store
+pub fn stable_hash(key: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn gitlab(findings: &[Finding]) -> Value {
+    Value::Array(
+        findings
+            .iter()
+            .map(|finding| {
+                let severity = match finding.level {
+                    Level::Error => "major",
+                    Level::Warning => "minor",
+                    Level::Note => "info",
+                };
+                json!({
+                    "description": finding.message,
+                    "check_name": finding.rule_id,
+                    "fingerprint": fingerprint(finding),
+                    "severity": severity,
+                    "location": {
+                        "path": finding.path,
+                        "lines": { "begin": finding.line.unwrap_or(1) },
+                    },
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "* @all\n/docs/ @docs\n/src/ @old\n/lib/ @x\n/ @new\n";
+
+    fn shadowed(line: u32) -> Diagnostic {
+        Diagnostic {
+            range: Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 6 },
+            },
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(codes::SHADOWED_RULE.to_string())),
+            message: format!("Rule is shadowed by line {} <src/>", line + 3),
+            ..Default::default()
+        }
+    }
+
+    fn findings() -> Vec<Finding> {
+        vec![
+            Finding::from_diagnostic(".github/CODEOWNERS", CONTENT, &shadowed(2)),
+            Finding::file(
+                codes::FILE_NOT_OWNED,
+                Level::Error,
+                "src/new.rs",
+                "File has no owners".to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("SARIF".parse(), Ok(ReportFormat::Sarif));
+        assert_eq!("codequality".parse(), Ok(ReportFormat::Gitlab));
+        assert!("yaml".parse::<ReportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_sarif() {
+        let sarif = sarif(&findings());
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 9);
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "shadowed-rule");
+        assert_eq!(result["ruleIndex"], 4);
+        assert_eq!(result["level"], "warning");
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 3);
+        assert_eq!(region["startColumn"], 1);
+        assert_eq!(region["endColumn"], 7);
+
+        // Whole-file findings have no region
+        let location = &run["results"][1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/new.rs");
+        assert!(location.get("region").is_none());
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit("lint", &findings());
+        assert!(xml.contains("<testsuite name=\"lint\" tests=\"2\" failures=\"2\""));
+        assert!(xml.contains("name=\"shadowed-rule .github/CODEOWNERS:3\""));
+        assert!(xml.contains("message=\"Rule is shadowed by line 5 &lt;src/&gt;\""));
+
+        let clean = junit("lint", &[]);
+        assert!(clean.contains("tests=\"1\" failures=\"0\""));
+        assert!(clean.contains("<testcase name=\"lint\""));
+    }
+
+    #[test]
+    fn test_checkstyle() {
+        let xml = checkstyle(&findings());
+        assert!(xml.contains("<file name=\".github/CODEOWNERS\">"));
+        assert!(xml.contains(
+            "<error line=\"3\" column=\"1\" severity=\"warning\" message=\"Rule is shadowed by line 5 &lt;src/&gt;\" source=\"codeowners-lsp.shadowed-rule\"/>"
+        ));
+        assert!(xml.contains("<error line=\"0\" severity=\"error\""));
+    }
+
+    #[test]
+    fn test_end_of_line_diagnostics_render_in_every_format() {
+        let diagnostic = Diagnostic {
+            range: Range {
+                start: Position {
+                    line: 1,
+                    character: 9,
+                },
+                end: Position {
+                    line: 1,
+                    character: u32::MAX,
+                },
+            },
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(codes::DUPLICATE_OWNER.to_string())),
+            message: "Duplicate owner @b".to_string(),
+            ..Default::default()
+        };
+        let content = "* @a\n/src/ @b @b\n";
+        let findings = vec![Finding::from_diagnostic("CODEOWNERS", content, &diagnostic)];
+        assert_eq!(findings[0].column, Some(10));
+        assert_eq!(findings[0].end_column, None);
+
+        for format in [
+            ReportFormat::Sarif,
+            ReportFormat::Junit,
+            ReportFormat::Checkstyle,
+            ReportFormat::Gitlab,
+        ] {
+            let output = render(format, "lint", &findings);
+            assert!(output.contains("Duplicate owner @b"));
+            assert!(!output.contains("endColumn"));
+        }
+    }
+
+    #[test]
+    fn test_gitlab() {
+        let report = gitlab(&findings());
+        let issues = report.as_array().unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["check_name"], "shadowed-rule");
+        assert_eq!(issues[0]["severity"], "minor");
+        assert_eq!(issues[0]["location"]["lines"]["begin"], 3);
+        assert_eq!(issues[1]["severity"], "major");
+        assert_eq!(issues[0]["fingerprint"].as_str().unwrap().len(), 16);
+        assert_eq!(
+            issues[0]["fingerprint"],
+            gitlab(&findings())[0]["fingerprint"]
+        );
+        assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+
+        // A rule moved down by an added line keeps its fingerprint
+        let moved = format!("# Owners\n{}", CONTENT);
+        let finding = Finding::from_diagnostic(".github/CODEOWNERS", &moved, &shadowed(3));
+        assert_eq!(
+            gitlab(&[finding])[0]["fingerprint"],
+            issues[0]["fingerprint"]
+        );
+
+        // Findings built from a line and columns are keyed the same way
+        let keyed = Finding {
+            line: Some(3),
+            column: Some(1),
+            end_column: Some(7),
+            ..Finding::file(
+                codes::SHADOWED_RULE,
+                Level::Warning,
+                ".github/CODEOWNERS",
+                "Rule is shadowed by line 5 <src/>".to_string(),
+            )
+        }
+        .keyed(CONTENT);
+        assert_eq!(keyed.key, findings()[0].key);
+    }
+}
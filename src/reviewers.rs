@@ -0,0 +1,350 @@
+//! Required reviewers for a set of changed files
+//!
+//! GitHub requires one approval from any of a file's code owners, so each
+//! changed file contributes the set of owners who can approve it. The
+//! smallest group of reviewers that satisfies every file is a minimum
+//! hitting set over those owner sets.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+use crate::github::GitHubClient;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::parse_codeowners_file_with_positions;
+
+/// Search steps before the exact search gives up and keeps the best
+/// solution found so far (which is never worse than the greedy one)
+const SEARCH_BUDGET: usize = 100_000;
+
+/// Concurrent GitHub requests when expanding teams
+const CONCURRENCY: usize = 5;
+
+/// Who can approve one changed file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileApprovers {
+    pub file: String,
+    /// Owners of the winning rule; any one of them can approve
+    pub owners: Vec<String>,
+    pub pattern: String,
+    /// Line number (1-indexed)
+    pub line: u32,
+}
+
+/// Changed files that share the same approvers
+#[derive(Debug, Clone, Serialize)]
+pub struct ApproverGroup {
+    pub owners: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// Approvals GitHub will require for a change
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewPlan {
+    pub files: Vec<FileApprovers>,
+    /// Changed files no rule assigns owners to
+    pub unowned: Vec<String>,
+    /// Files grouped by owner set, largest group first
+    pub groups: Vec<ApproverGroup>,
+    /// Fewest owners (users or teams) that cover every owned file
+    pub reviewers: Vec<String>,
+    /// Fewest individual people, once teams are expanded to their members
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub individuals: Option<Vec<String>>,
+}
+
+impl ReviewPlan {
+    /// Teams (`@org/team`) among the required owners
+    pub fn teams(&self) -> Vec<String> {
+        let teams: BTreeSet<&String> = self
+            .groups
+            .iter()
+            .flat_map(|group| &group.owners)
+            .filter(|owner| is_team(owner))
+            .collect();
+        teams.into_iter().cloned().collect()
+    }
+
+    /// Solve the cover again over individual people. Teams missing from
+    /// `members` stay as they are, so a team nobody could expand can still
+    /// be requested.
+    pub fn expand_teams(&mut self, members: &HashMap<String, Vec<String>>) {
+        let sets: Vec<Vec<String>> = self
+            .groups
+            .iter()
+            .map(|group| {
+                group
+                    .owners
+                    .iter()
+                    .flat_map(|owner| match members.get(owner) {
+                        Some(people) if is_team(owner) => people.clone(),
+                        _ => vec![owner.clone()],
+                    })
+                    .collect()
+            })
+            .collect();
+        self.individuals = Some(minimum_cover(&sets));
+    }
+}
+
+fn is_team(owner: &str) -> bool {
+    owner.starts_with('@') && owner.contains('/')
+}
+
+/// A team whose members couldn't be listed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnexpandedTeam {
+    pub team: String,
+    pub reason: String,
+}
+
+/// Fetch the members of `teams`. Returns the members found and the teams
+/// that couldn't be expanded.
+pub async fn fetch_team_members(
+    client: &GitHubClient,
+    teams: Vec<String>,
+    token: &str,
+) -> (HashMap<String, Vec<String>>, Vec<UnexpandedTeam>) {
+    let results: Vec<(String, Result<Vec<String>, String>)> = stream::iter(teams)
+        .map(|team: String| async move {
+            let members = client.team_members(&team, token).await;
+            (team, members)
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut members = HashMap::new();
+    let mut failed = Vec::new();
+    for (team, result) in results {
+        match result {
+            Ok(people) if !people.is_empty() => {
+                members.insert(team, people);
+            }
+            Ok(_) => failed.push(UnexpandedTeam {
+                team,
+                reason: "team has no members".to_string(),
+            }),
+            Err(reason) => failed.push(UnexpandedTeam { team, reason }),
+        }
+    }
+    failed.sort_by(|a, b| a.team.cmp(&b.team));
+    (members, failed)
+}
+
+/// Work out who can approve each of `files`, and the fewest owners that
+/// cover all of them
+pub fn review_plan(content: &str, files: &[String]) -> ReviewPlan {
+    let parsed = parse_codeowners_file_with_positions(content);
+
+    let mut approvers = Vec::new();
+    let mut unowned = Vec::new();
+    let mut by_owners: BTreeMap<Vec<String>, Vec<String>> = BTreeMap::new();
+
+    let mut files: Vec<&String> = files.iter().collect();
+    files.sort();
+    files.dedup();
+
+    for file in files {
+        match check_file_ownership_parsed(&parsed, file) {
+            Some(result) if !result.owners.is_empty() => {
+                let mut owners = result.owners;
+                owners.sort();
+                owners.dedup();
+                by_owners
+                    .entry(owners.clone())
+                    .or_default()
+                    .push(file.clone());
+                approvers.push(FileApprovers {
+                    file: file.clone(),
+                    owners,
+                    pattern: result.pattern,
+                    line: result.line_number + 1,
+                });
+            }
+            _ => unowned.push(file.clone()),
+        }
+    }
+
+    let mut groups: Vec<ApproverGroup> = by_owners
+        .into_iter()
+        .map(|(owners, files)| ApproverGroup { owners, files })
+        .collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.files.len()));
+
+    let sets: Vec<Vec<String>> = groups.iter().map(|group| group.owners.clone()).collect();
+    ReviewPlan {
+        files: approvers,
+        unowned,
+        groups,
+        reviewers: minimum_cover(&sets),
+        individuals: None,
+    }
+}
+
+/// Smallest set of owners containing at least one member of every set
+/// (sorted). Exact for the sizes CODEOWNERS produces in practice; very large
+/// inputs fall back to the best solution found within the search budget.
+pub fn minimum_cover(sets: &[Vec<String>]) -> Vec<String> {
+    let mut sets: Vec<BTreeSet<&str>> = sets
+        .iter()
+        .map(|set| set.iter().map(|owner| owner.as_str()).collect())
+        .filter(|set: &BTreeSet<&str>| !set.is_empty())
+        .collect();
+    sets.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    sets.dedup();
+
+    // Any owner of a subset also covers its supersets
+    let mut minimal: Vec<BTreeSet<&str>> = Vec::new();
+    for set in sets {
+        if !minimal.iter().any(|kept| kept.is_subset(&set)) {
+            minimal.push(set);
+        }
+    }
+
+    let mut best = greedy_cover(&minimal);
+    let mut chosen = Vec::new();
+    let mut budget = SEARCH_BUDGET;
+    search(&minimal, &mut chosen, &mut best, &mut budget);
+
+    let mut best: Vec<String> = best.into_iter().map(str::to_string).collect();
+    best.sort();
+    best
+}
+
+/// Repeatedly pick the owner that covers the most remaining sets
+fn greedy_cover<'a>(sets: &[BTreeSet<&'a str>]) -> Vec<&'a str> {
+    let mut open: Vec<&BTreeSet<&str>> = sets.iter().collect();
+    let mut chosen = Vec::new();
+    while !open.is_empty() {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for set in &open {
+            for owner in set.iter() {
+                *counts.entry(owner).or_default() += 1;
+            }
+        }
+        // Ties go to the alphabetically first owner
+        let pick = counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(owner, _)| *owner)
+            .unwrap();
+        chosen.push(pick);
+        open.retain(|set| !set.contains(pick));
+    }
+    chosen
+}
+
+/// Branch on the owners of the smallest uncovered set, pruning anything
+/// that can't beat the best solution so far
+fn search<'a>(
+    sets: &[BTreeSet<&'a str>],
+    chosen: &mut Vec<&'a str>,
+    best: &mut Vec<&'a str>,
+    budget: &mut usize,
+) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
+
+    let open = sets
+        .iter()
+        .filter(|set| !chosen.iter().any(|owner| set.contains(owner)))
+        .min_by_key(|set| set.len());
+    let Some(open) = open else {
+        if chosen.len() < best.len() {
+            *best = chosen.clone();
+        }
+        return;
+    };
+    if chosen.len() + 1 >= best.len() {
+        return;
+    }
+    for owner in open {
+        chosen.push(owner);
+        search(sets, chosen, best, budget);
+        chosen.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sets(input: &[&[&str]]) -> Vec<Vec<String>> {
+        input
+            .iter()
+            .map(|set| set.iter().map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_minimum_cover_prefers_shared_owner() {
+        let cover = minimum_cover(&sets(&[&["@a", "@b"], &["@b", "@c"], &["@c", "@d"]]));
+        assert_eq!(cover.len(), 2);
+
+        let cover = minimum_cover(&sets(&[&["@a", "@x"], &["@b", "@x"], &["@c", "@x"]]));
+        assert_eq!(cover, vec!["@x"]);
+
+        assert!(minimum_cover(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_minimum_cover_beats_greedy() {
+        // Greedy takes @a (covers 2) and then needs @b and @c; the
+        // optimum is @e and @f
+        let cover = minimum_cover(&sets(&[
+            &["@a", "@e"],
+            &["@a", "@f"],
+            &["@b", "@e"],
+            &["@c", "@f"],
+        ]));
+        assert_eq!(cover, vec!["@e", "@f"]);
+    }
+
+    #[test]
+    fn test_review_plan() {
+        let content = "* @org/core\n/docs/ @docs @org/core\n/api/ @api\n/vendor/\n";
+        let plan = review_plan(
+            content,
+            &files(&[
+                "docs/a.md",
+                "api/x.go",
+                "src/main.rs",
+                "vendor/lib.c",
+                "api/x.go",
+            ]),
+        );
+
+        assert_eq!(plan.files.len(), 3);
+        assert_eq!(plan.unowned, vec!["vendor/lib.c"]);
+        assert_eq!(plan.groups.len(), 3);
+        assert_eq!(plan.reviewers, vec!["@api", "@org/core"]);
+        assert_eq!(plan.teams(), vec!["@org/core"]);
+
+        let api = plan.files.iter().find(|f| f.file == "api/x.go").unwrap();
+        assert_eq!(api.pattern, "/api/");
+        assert_eq!(api.line, 3);
+    }
+
+    #[test]
+    fn test_expand_teams() {
+        let content = "/a/ @org/one\n/b/ @org/two\n/c/ @carol\n/d/ @org/missing\n";
+        let mut plan = review_plan(content, &files(&["a/1", "b/1", "c/1", "d/1"]));
+        let members = HashMap::from([
+            ("@org/one".to_string(), files(&["@alice", "@bob"])),
+            ("@org/two".to_string(), files(&["@bob", "@dave"])),
+        ]);
+        plan.expand_teams(&members);
+        assert_eq!(
+            plan.individuals,
+            Some(files(&["@bob", "@carol", "@org/missing"]))
+        );
+    }
+}
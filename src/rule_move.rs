@@ -0,0 +1,273 @@
+//! Fix shadowed rules by moving them instead of deleting them
+//!
+//! A specific rule placed above a more general one (`/src/auth/ @security`
+//! above `/src/ @backend`) never wins. Moving it directly below the last rule
+//! that shadows it restores what the author meant. Before a move is offered,
+//! the new ownership is checked against the [`FileCache`]: only files matched
+//! by the moved rules may change owner, and they must change to those rules.
+
+use std::collections::HashSet;
+
+use crate::file_cache::FileCache;
+use crate::formatter::uses_crlf;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine, ParsedLine};
+use crate::pattern::pattern_subsumes;
+
+/// Result of moving one or more rules
+#[derive(Debug, Clone)]
+pub struct MoveResult {
+    pub content: String,
+    /// Human-readable description of each move
+    pub moves: Vec<String>,
+    /// Number of cached files whose owners change
+    pub changed_files: usize,
+    /// Rules are still shadowed because [`fix_ordering`] ran out of passes
+    pub incomplete: bool,
+}
+
+/// Lines (0-indexed) of later rules that shadow the rule on `line`,
+/// using the same inclusion logic as the shadowed-rule diagnostic.
+/// Exact duplicates are left to "Remove shadowed rule".
+pub fn shadowing_lines(content: &str, line: u32) -> Vec<u32> {
+    let parsed = parse_codeowners_file_with_positions(content);
+    rule_at(&parsed, line)
+        .map(|pattern| shadowers(&parsed, line, pattern))
+        .unwrap_or_default()
+}
+
+/// Move the rule on `line` directly below the last rule that shadows it
+pub fn move_below_shadowing(
+    content: &str,
+    line: u32,
+    cache: &FileCache,
+) -> Result<MoveResult, String> {
+    let parsed = parse_codeowners_file_with_positions(content);
+    let pattern =
+        rule_at(&parsed, line).ok_or_else(|| format!("Line {} is not a rule", line + 1))?;
+    let target = shadowers(&parsed, line, pattern)
+        .into_iter()
+        .max()
+        .ok_or_else(|| format!("'{}' is not shadowed by a later rule", pattern))?;
+
+    let new_content = move_line(content, line as usize, target as usize);
+    let changed_files = verify_moves(content, &new_content, &[pattern], cache)?;
+
+    Ok(MoveResult {
+        content: new_content,
+        moves: vec![format!(
+            "line {}: moved '{}' below line {}",
+            line + 1,
+            pattern,
+            target + 1
+        )],
+        changed_files,
+        incomplete: false,
+    })
+}
+
+/// Move every shadowed rule below its shadowing rules. Rules are moved one
+/// at a time, bottom-most first, until nothing is shadowed, so nested
+/// specific rules end up in specific-last order.
+pub fn fix_ordering(content: &str, cache: &FileCache) -> Result<MoveResult, String> {
+    // Every move pushes a rule strictly down, but cap the passes anyway in
+    // case two patterns include each other
+    let rule_count = parse_codeowners_file_with_positions(content)
+        .iter()
+        .filter(|l| matches!(l.content, CodeownersLine::Rule { .. }))
+        .count();
+    fix_ordering_within(content, cache, rule_count * rule_count + 1)
+}
+
+/// [`fix_ordering`] with at most `max_moves` moves
+fn fix_ordering_within(
+    content: &str,
+    cache: &FileCache,
+    max_moves: usize,
+) -> Result<MoveResult, String> {
+    let mut current = content.to_string();
+    let mut moves = Vec::new();
+    let mut moved_patterns: Vec<String> = Vec::new();
+    let mut incomplete = false;
+
+    for pass in 0..=max_moves {
+        let parsed = parse_codeowners_file_with_positions(&current);
+        let next = parsed.iter().rev().find_map(|l| match &l.content {
+            CodeownersLine::Rule { pattern, .. } => shadowers(&parsed, l.line_number, pattern)
+                .into_iter()
+                .max()
+                .map(|target| (l.line_number, pattern.clone(), target)),
+            _ => None,
+        });
+        let Some((line, pattern, target)) = next else {
+            break;
+        };
+        if pass == max_moves {
+            incomplete = true;
+            break;
+        }
+
+        moves.push(format!(
+            "moved '{}' below '{}'",
+            pattern,
+            rule_at(&parsed, target).unwrap_or_default()
+        ));
+        current = move_line(&current, line as usize, target as usize);
+        if !moved_patterns.contains(&pattern) {
+            moved_patterns.push(pattern);
+        }
+    }
+
+    let patterns: Vec<&str> = moved_patterns.iter().map(|p| p.as_str()).collect();
+    let changed_files = verify_moves(content, &current, &patterns, cache)?;
+
+    Ok(MoveResult {
+        content: current,
+        moves,
+        changed_files,
+        incomplete,
+    })
+}
+
+fn rule_at(parsed: &[ParsedLine], line: u32) -> Option<&str> {
+    parsed.iter().find_map(|l| match &l.content {
+        CodeownersLine::Rule { pattern, .. } if l.line_number == line => Some(pattern.as_str()),
+        _ => None,
+    })
+}
+
+fn shadowers(parsed: &[ParsedLine], line: u32, pattern: &str) -> Vec<u32> {
+    let stripped = pattern.trim_start_matches('/');
+    parsed
+        .iter()
+        .filter(|l| l.line_number > line)
+        .filter_map(|l| match &l.content {
+            CodeownersLine::Rule { pattern: later, .. } => {
+                let later_stripped = later.trim_start_matches('/');
+                let is_catchall = later_stripped == "*" || later_stripped == "**";
+                let shadows = later_stripped != stripped
+                    && (is_catchall || pattern_subsumes(pattern, later))
+                    // Patterns that include each other are equivalent, not shadowing
+                    && !pattern_subsumes(later, pattern);
+                shadows.then_some(l.line_number)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Move line `from` to directly below line `below` (`below` > `from`)
+fn move_line(content: &str, from: usize, below: usize) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let line = lines.remove(from);
+    lines.insert(below, line);
+
+    let newline = if uses_crlf(content) { "\r\n" } else { "\n" };
+    let mut output = lines.join(newline);
+    if content.ends_with('\n') {
+        output.push_str(newline);
+    }
+    output
+}
+
+/// Check that only files matched by `patterns` change owner, and that each
+/// of them is now owned by one of those rules. Returns the number of files
+/// whose owners change.
+fn verify_moves(
+    old: &str,
+    new: &str,
+    patterns: &[&str],
+    cache: &FileCache,
+) -> Result<usize, String> {
+    let old_lines = parse_codeowners_file_with_positions(old);
+    let new_lines = parse_codeowners_file_with_positions(new);
+    let moved: HashSet<&str> = patterns.iter().copied().collect();
+
+    // Other files keep the relative order of every rule that matches them,
+    // so only files matched by a moved rule can change
+    let mut candidates: Vec<&String> = patterns.iter().flat_map(|p| cache.get_matches(p)).collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut changed = 0;
+    for file in candidates {
+        let before = check_file_ownership_parsed(&old_lines, file);
+        let after = check_file_ownership_parsed(&new_lines, file);
+        let before_owners = before.as_ref().map(|r| &r.owners);
+        let after_owners = after.as_ref().map(|r| &r.owners);
+        if before_owners == after_owners {
+            continue;
+        }
+        match after {
+            Some(rule) if moved.contains(rule.pattern.as_str()) => changed += 1,
+            _ => {
+                return Err(format!(
+                    "Moving would change the owner of '{}' to an unrelated rule",
+                    file
+                ))
+            }
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> FileCache {
+        FileCache::from_files(vec![
+            "src/auth/login.rs".to_string(),
+            "src/auth/oauth/google.rs".to_string(),
+            "src/main.rs".to_string(),
+            "docs/readme.md".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_move_below_shadowing_rule() {
+        let content = "/src/auth/ @security\n/docs/ @docs\n/src/ @backend\n";
+        let result = move_below_shadowing(content, 0, &cache()).unwrap();
+        assert_eq!(
+            result.content,
+            "/docs/ @docs\n/src/ @backend\n/src/auth/ @security\n"
+        );
+        assert_eq!(result.changed_files, 2);
+        assert_eq!(shadowing_lines(content, 0), vec![2]);
+    }
+
+    #[test]
+    fn test_move_requires_shadowing() {
+        let content = "/src/ @backend\n/src/auth/ @security\n";
+        assert!(move_below_shadowing(content, 1, &cache()).is_err());
+        assert!(move_below_shadowing(content, 5, &cache()).is_err());
+        // Exact duplicates are not moved
+        assert!(shadowing_lines("/src/ @a\n/src/ @b\n", 0).is_empty());
+    }
+
+    #[test]
+    fn test_fix_ordering_keeps_nested_rules_specific_last() {
+        let content = "/src/auth/oauth/ @oauth\n/src/auth/ @security\n/src/ @backend\n";
+        let result = fix_ordering(content, &cache()).unwrap();
+        assert_eq!(
+            result.content,
+            "/src/ @backend\n/src/auth/ @security\n/src/auth/oauth/ @oauth\n"
+        );
+        assert_eq!(result.moves.len(), 2);
+        assert_eq!(result.changed_files, 2);
+        assert!(!result.incomplete);
+
+        // Running out of moves is reported rather than passed off as fixed
+        let capped = fix_ordering_within(content, &cache(), 1).unwrap();
+        assert_eq!(capped.moves.len(), 1);
+        assert!(capped.incomplete);
+    }
+
+    #[test]
+    fn test_fix_ordering_noop() {
+        let content = "* @all\n/src/ @backend\n";
+        let result = fix_ordering(content, &cache()).unwrap();
+        assert_eq!(result.content, content);
+        assert!(result.moves.is_empty());
+    }
+}
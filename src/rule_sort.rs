@@ -0,0 +1,305 @@
+//! Ownership-preserving rule sorting
+//!
+//! Rules are sorted alphabetically within each block of consecutive rules
+//! (blocks are delimited by comments and blank lines). Under last-match-wins
+//! two rules may only trade places when no file can be matched by both, so
+//! sorting is a topological sort over "must stay before" constraints that
+//! picks the alphabetically first rule whenever it is free to move.
+//!
+//! Two rules are constrained when one pattern includes the other
+//! ([`pattern_subsumes`]) or when any file in the [`FileCache`] matches both,
+//! whoever owns them: even with equal owners, swapping them changes which
+//! rule wins for the shared files.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::file_cache::FileCache;
+use crate::formatter::uses_crlf;
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
+use crate::pattern::pattern_subsumes;
+
+/// A block that could not be fully sorted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefusedBlock {
+    /// First line of the block (0-indexed)
+    pub start_line: u32,
+    /// Last line of the block (0-indexed, inclusive)
+    pub end_line: u32,
+    /// Why rules in the block had to keep their relative order
+    pub reasons: Vec<String>,
+}
+
+/// Result of sorting rules
+#[derive(Debug, Clone)]
+pub struct SortResult {
+    pub content: String,
+    /// Number of rules that changed position
+    pub moved: usize,
+    pub refused: Vec<RefusedBlock>,
+}
+
+struct BlockRule<'a> {
+    line: usize,
+    pattern: &'a str,
+    key: String,
+    matches: HashSet<&'a String>,
+}
+
+/// Sort rules within each block as far as ownership allows
+pub fn sort_rules(content: &str, cache: &FileCache) -> SortResult {
+    let parsed = parse_codeowners_file_with_positions(content);
+    let mut lines: Vec<&str> = content.lines().collect();
+    let original = lines.clone();
+
+    let mut moved = 0;
+    let mut refused = Vec::new();
+
+    // Group consecutive rule lines into blocks
+    let mut blocks: Vec<Vec<BlockRule>> = Vec::new();
+    let mut current: Vec<BlockRule> = Vec::new();
+    for parsed_line in &parsed {
+        if let CodeownersLine::Rule { pattern, .. } = &parsed_line.content {
+            current.push(BlockRule {
+                line: parsed_line.line_number as usize,
+                pattern,
+                key: sort_key(pattern),
+                matches: HashSet::new(),
+            });
+        } else if !current.is_empty() {
+            blocks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    // Already sorted blocks are left alone without matching any files
+    let unsorted = blocks
+        .iter_mut()
+        .filter(|b| b.windows(2).any(|pair| pair[1].key < pair[0].key));
+    for block in unsorted {
+        for rule in block.iter_mut() {
+            rule.matches = cache.get_matches(rule.pattern).into_iter().collect();
+        }
+        let (order, reasons) = sort_block(block);
+
+        for (position, &idx) in order.iter().enumerate() {
+            if idx != position {
+                moved += 1;
+            }
+            lines[block[position].line] = original[block[idx].line];
+        }
+
+        if !reasons.is_empty() {
+            refused.push(RefusedBlock {
+                start_line: block[0].line as u32,
+                end_line: block[block.len() - 1].line as u32,
+                reasons,
+            });
+        }
+    }
+
+    let newline = if uses_crlf(content) { "\r\n" } else { "\n" };
+    let mut output = lines.join(newline);
+    if content.ends_with('\n') {
+        output.push_str(newline);
+    }
+
+    SortResult {
+        content: output,
+        moved,
+        refused,
+    }
+}
+
+/// Alphabetical order ignores anchoring and case
+fn sort_key(pattern: &str) -> String {
+    pattern.trim_start_matches('/').to_lowercase()
+}
+
+/// Sorted order of the block (indices into it) and the reasons for every
+/// pair that alphabetical order wanted to swap but could not
+fn sort_block(block: &[BlockRule]) -> (Vec<usize>, Vec<String>) {
+    let n = block.len();
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut in_degree = vec![0; n];
+    let mut reasons = Vec::new();
+
+    for i in 0..n {
+        for j in i + 1..n {
+            if let Some(reason) = conflict(&block[i], &block[j]) {
+                successors[i].push(j);
+                in_degree[j] += 1;
+                if block[j].key < block[i].key {
+                    reasons.push(reason);
+                }
+            }
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<(&str, usize)>> = (0..n)
+        .filter(|&i| in_degree[i] == 0)
+        .map(|i| Reverse((block[i].key.as_str(), i)))
+        .collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(Reverse((_, i))) = ready.pop() {
+        order.push(i);
+        for &j in &successors[i] {
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.push(Reverse((block[j].key.as_str(), j)));
+            }
+        }
+    }
+
+    (order, reasons)
+}
+
+/// Why `earlier` must stay before `later`, if it must
+fn conflict(earlier: &BlockRule, later: &BlockRule) -> Option<String> {
+    let describe = |rule: &BlockRule| format!("'{}' (line {})", rule.pattern, rule.line + 1);
+
+    if pattern_subsumes(later.pattern, earlier.pattern) {
+        return Some(format!(
+            "{} must stay before {}: it includes every file the later pattern matches",
+            describe(earlier),
+            describe(later)
+        ));
+    }
+    if pattern_subsumes(earlier.pattern, later.pattern) {
+        return Some(format!(
+            "{} must stay before {}: the later pattern includes every file it matches",
+            describe(earlier),
+            describe(later)
+        ));
+    }
+
+    let mut shared: Vec<&&String> = earlier.matches.intersection(&later.matches).collect();
+    if shared.is_empty() {
+        return None;
+    }
+    shared.sort();
+    let example = shared[0];
+    let others = match shared.len() - 1 {
+        0 => String::new(),
+        1 => " and 1 other file".to_string(),
+        n => format!(" and {} other files", n),
+    };
+    Some(format!(
+        "{} must stay before {}: both match '{}'{}",
+        describe(earlier),
+        describe(later),
+        example,
+        others
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> FileCache {
+        FileCache::from_files(vec![
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "docs/guide.md".to_string(),
+            "api/handler.go".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_sorts_disjoint_rules() {
+        let content = "# Services\n/src/ @rust\n/docs/ @docs # manual\n/api/ @go\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(
+            result.content,
+            "# Services\n/api/ @go\n/docs/ @docs # manual\n/src/ @rust\n"
+        );
+        assert_eq!(result.moved, 2);
+        assert!(result.refused.is_empty());
+    }
+
+    #[test]
+    fn test_refuses_overlapping_rules() {
+        // /src/ and *.rs both match src/main.rs: swapping changes its owner
+        let content = "/src/ @src\n*.rs @rust\n/docs/ @docs\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(result.content, "/docs/ @docs\n/src/ @src\n*.rs @rust\n");
+        assert_eq!(result.refused.len(), 1);
+        assert_eq!(result.refused[0].start_line, 0);
+        assert_eq!(result.refused[0].end_line, 2);
+        assert!(result.refused[0].reasons[0].contains("both match 'src/lib.rs'"));
+    }
+
+    #[test]
+    fn test_refuses_inclusion_without_files() {
+        // No cached files under /lib/, but the inclusion still counts
+        let content = "/lib/ @all\n/lib/a/ @a\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(result.content, content);
+        assert_eq!(result.moved, 0);
+        assert!(result.refused.is_empty()); // already alphabetical
+
+        let content = "/lib/z/ @z\n/lib/ @all\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(result.content, content);
+        assert!(result.refused[0].reasons[0].contains("includes every file"));
+    }
+
+    #[test]
+    fn test_same_owners_keep_overlapping_order() {
+        // Swapping would keep the owners but move src/*.rs to another line
+        let content = "/src/ @rust\n*.rs @rust\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(result.content, content);
+        assert_eq!(result.refused.len(), 1);
+    }
+
+    #[test]
+    fn test_every_file_keeps_its_winning_rule() {
+        use crate::ownership::check_file_ownership;
+
+        let content = "\
+*.go @go
+/src/ @rust
+*.md @docs
+*.rs @rust
+/api/ @go
+/docs/ @docs
+/src/lib.rs @rust
+";
+        let cache = cache();
+        let result = sort_rules(content, &cache);
+        assert!(result.moved > 0);
+
+        // Compare the winning rule text, since line numbers change
+        let winner = |content: &str, file: &str| {
+            check_file_ownership(content, file).map(|r| {
+                content
+                    .lines()
+                    .nth(r.line_number as usize)
+                    .unwrap()
+                    .to_string()
+            })
+        };
+        for file in cache.all_files() {
+            assert_eq!(
+                winner(content, file),
+                winner(&result.content, file),
+                "{} changed its winning rule",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_blocks_do_not_mix() {
+        let content = "/src/ @a\n/api/ @b\n\n/docs/ @c\n# x\n/api/ @d\n";
+        let result = sort_rules(content, &cache());
+        assert_eq!(
+            result.content,
+            "/api/ @b\n/src/ @a\n\n/docs/ @c\n# x\n/api/ @d\n"
+        );
+    }
+}
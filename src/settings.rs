@@ -5,6 +5,10 @@ use std::path::Path;
 use serde::Deserialize;
 
 use crate::diagnostics::DiagnosticConfig;
+use crate::formatter::{CommentStyle, FormatOptions, LineEndings};
+
+/// Base URL for owner links when `github_url` is not set
+pub const DEFAULT_GITHUB_URL: &str = "https://github.com";
 
 pub const CONFIG_FILE: &str = ".codeowners-lsp.toml";
 pub const CONFIG_FILE_LOCAL: &str = ".codeowners-lsp.local.toml";
@@ -20,6 +24,23 @@ pub struct SuggestSettings {
     pub anchored: bool,
 }
 
+/// Settings for the formatter (`fmt` and LSP formatting)
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct FormatSettings {
+    /// Align owner columns within each section
+    #[serde(default)]
+    pub align_owners: bool,
+    /// Sort and deduplicate owners on each rule
+    #[serde(default)]
+    pub sort_owners: bool,
+    /// "preserve" (default) or "space"
+    pub comment_style: Option<CommentStyle>,
+    /// Maximum consecutive blank lines (default: 1)
+    pub max_blank_lines: Option<usize>,
+    /// "auto" (default, keep the file's endings), "lf" or "crlf"
+    pub line_endings: Option<LineEndings>,
+}
+
 /// Shared settings for both LSP and CLI
 #[derive(Debug, Default, Deserialize, Clone)]
 pub struct Settings {
@@ -31,6 +52,8 @@ pub struct Settings {
     pub team: Option<String>,
     /// GitHub token for validating owners (reads from env if prefixed with "env:")
     pub github_token: Option<String>,
+    /// Base URL for owner links, for GitHub Enterprise (default: https://github.com)
+    pub github_url: Option<String>,
     /// Whether to validate owners against GitHub API
     #[serde(default)]
     pub validate_owners: bool,
@@ -40,6 +63,9 @@ pub struct Settings {
     /// Suggest command settings
     #[serde(default)]
     pub suggest: SuggestSettings,
+    /// Formatter settings
+    #[serde(default)]
+    pub format: FormatSettings,
 }
 
 impl Settings {
@@ -57,6 +83,9 @@ impl Settings {
         if other.github_token.is_some() {
             self.github_token = other.github_token;
         }
+        if other.github_url.is_some() {
+            self.github_url = other.github_url;
+        }
         if other.validate_owners {
             self.validate_owners = true;
         }
@@ -71,6 +100,22 @@ impl Settings {
         if other.suggest.anchored {
             self.suggest.anchored = true;
         }
+        // Merge format settings
+        if other.format.align_owners {
+            self.format.align_owners = true;
+        }
+        if other.format.sort_owners {
+            self.format.sort_owners = true;
+        }
+        if other.format.comment_style.is_some() {
+            self.format.comment_style = other.format.comment_style;
+        }
+        if other.format.max_blank_lines.is_some() {
+            self.format.max_blank_lines = other.format.max_blank_lines;
+        }
+        if other.format.line_endings.is_some() {
+            self.format.line_endings = other.format.line_endings;
+        }
     }
 
     /// Get DiagnosticConfig from settings
@@ -78,6 +123,21 @@ impl Settings {
         DiagnosticConfig::from_map(&self.diagnostics)
     }
 
+    /// Get FormatOptions from settings
+    pub fn format_options(&self) -> FormatOptions {
+        let defaults = FormatOptions::default();
+        FormatOptions {
+            align_owners: self.format.align_owners,
+            sort_owners: self.format.sort_owners,
+            comment_style: self.format.comment_style.unwrap_or(defaults.comment_style),
+            max_blank_lines: self
+                .format
+                .max_blank_lines
+                .unwrap_or(defaults.max_blank_lines),
+            line_endings: self.format.line_endings.unwrap_or(defaults.line_endings),
+        }
+    }
+
     /// Resolve GitHub token (handles env: prefix)
     pub fn resolve_token(&self) -> Option<String> {
         self.github_token.as_ref().and_then(|token| {
@@ -89,6 +149,11 @@ impl Settings {
         })
     }
 
+    /// Base URL for owner profile and team links
+    pub fn github_url(&self) -> &str {
+        self.github_url.as_deref().unwrap_or(DEFAULT_GITHUB_URL)
+    }
+
     /// Get the lookup command template if configured
     #[allow(dead_code)] // Used by CLI only
     pub fn lookup_cmd(&self) -> Option<&str> {
@@ -369,6 +434,24 @@ mod tests {
         assert!(settings.suggest.anchored);
     }
 
+    #[test]
+    fn test_load_settings_with_format() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join(CONFIG_FILE);
+        let mut file = fs::File::create(&config_path).unwrap();
+        writeln!(file, r#"[format]"#).unwrap();
+        writeln!(file, r#"align_owners = true"#).unwrap();
+        writeln!(file, r#"comment_style = "space""#).unwrap();
+        writeln!(file, r#"line_endings = "crlf""#).unwrap();
+
+        let options = load_settings_from_path(dir.path()).format_options();
+        assert!(options.align_owners);
+        assert!(!options.sort_owners);
+        assert_eq!(options.comment_style, CommentStyle::Space);
+        assert_eq!(options.max_blank_lines, 1);
+        assert_eq!(options.line_endings, LineEndings::Crlf);
+    }
+
     #[test]
     fn test_diagnostic_config() {
         let mut settings = Settings::default();
@@ -405,4 +488,15 @@ mod tests {
         // Base preserved when other is None
         assert_eq!(base.github_token, Some("base-token".to_string()));
     }
+
+    #[test]
+    fn test_github_url() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.github_url(), "https://github.com");
+        settings.merge(Settings {
+            github_url: Some("https://ghe.example.com".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(settings.github_url(), "https://ghe.example.com");
+    }
 }
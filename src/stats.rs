@@ -0,0 +1,205 @@
+//! Ownership statistics per owner and per owner set
+//!
+//! Every file is assigned to its winning rule, the same way `tree` and
+//! `check` resolve it. A file with several owners counts towards each of
+//! them, so per-owner shares can add up to more than 100%; per-set shares
+//! don't overlap.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::file_cache::FileCache;
+use crate::ownership::check_file_ownership_parsed;
+use crate::parser::{parse_codeowners_file_with_positions, CodeownersLine};
+
+/// Totals for one owner or one owner set
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OwnerStats {
+    /// A single owner, or every owner of the set
+    pub owners: Vec<String>,
+    /// Files this owner (set) wins
+    pub files: usize,
+    pub lines: usize,
+    /// Percent of all files
+    pub file_share: f64,
+    /// Percent of all lines
+    pub line_share: f64,
+    /// Rules listing this owner (or exactly this owner set)
+    pub rules: usize,
+}
+
+/// Repository-wide ownership statistics
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipStats {
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub unowned_files: usize,
+    pub unowned_lines: usize,
+    /// Per owner, most files first
+    pub owners: Vec<OwnerStats>,
+    /// Per owner set, most files first
+    pub owner_sets: Vec<OwnerStats>,
+    /// Owners whose rules match files but lose every one to later rules
+    pub shadowed_only: Vec<String>,
+}
+
+/// `part` as a percentage of `total`, rounded to one decimal
+pub fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (part as f64 / total as f64 * 1000.0).round() / 10.0
+    }
+}
+
+/// Aggregate ownership over every file in `cache`. `line_count` returns the
+/// number of lines in a repo-relative file.
+pub fn ownership_stats<F>(content: &str, cache: &FileCache, line_count: F) -> OwnershipStats
+where
+    F: Fn(&str) -> usize + Sync,
+{
+    let parsed = parse_codeowners_file_with_positions(content);
+
+    let assigned: Vec<(Vec<String>, usize)> = cache
+        .all_files()
+        .par_iter()
+        .map(|file| {
+            let mut owners = check_file_ownership_parsed(&parsed, file)
+                .map(|r| r.owners)
+                .unwrap_or_default();
+            owners.sort();
+            owners.dedup();
+            (owners, line_count(file))
+        })
+        .collect();
+
+    let mut by_owner: BTreeMap<String, OwnerStats> = BTreeMap::new();
+    let mut by_set: BTreeMap<Vec<String>, OwnerStats> = BTreeMap::new();
+    let mut total_lines = 0;
+    let mut unowned_files = 0;
+    let mut unowned_lines = 0;
+
+    for (owners, lines) in &assigned {
+        total_lines += lines;
+        if owners.is_empty() {
+            unowned_files += 1;
+            unowned_lines += lines;
+            continue;
+        }
+        for owner in owners {
+            let stats = by_owner.entry(owner.clone()).or_default();
+            stats.files += 1;
+            stats.lines += lines;
+        }
+        let stats = by_set.entry(owners.clone()).or_default();
+        stats.files += 1;
+        stats.lines += lines;
+    }
+
+    // Rule counts, and owners that only appear on rules losing all files
+    let mut matching_owners: BTreeSet<&str> = BTreeSet::new();
+    for line in &parsed {
+        if let CodeownersLine::Rule { pattern, owners } = &line.content {
+            if owners.is_empty() {
+                continue;
+            }
+            let mut set = owners.clone();
+            set.sort();
+            set.dedup();
+            for owner in &set {
+                by_owner.entry(owner.clone()).or_default().rules += 1;
+            }
+            by_set.entry(set).or_default().rules += 1;
+
+            if cache.has_matches(pattern) {
+                matching_owners.extend(owners.iter().map(String::as_str));
+            }
+        }
+    }
+    let shadowed_only = matching_owners
+        .into_iter()
+        .filter(|owner| by_owner.get(*owner).is_some_and(|s| s.files == 0))
+        .map(str::to_string)
+        .collect();
+
+    let total_files = assigned.len();
+    let finish = |entries: Vec<(Vec<String>, OwnerStats)>| {
+        let mut stats: Vec<OwnerStats> = entries
+            .into_iter()
+            .map(|(owners, stats)| OwnerStats {
+                owners,
+                file_share: percent(stats.files, total_files),
+                line_share: percent(stats.lines, total_lines),
+                ..stats
+            })
+            .collect();
+        stats.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.owners.cmp(&b.owners)));
+        stats
+    };
+
+    OwnershipStats {
+        total_files,
+        total_lines,
+        unowned_files,
+        unowned_lines,
+        owners: finish(
+            by_owner
+                .into_iter()
+                .map(|(owner, stats)| (vec![owner], stats))
+                .collect(),
+        ),
+        owner_sets: finish(by_set.into_iter().collect()),
+        shadowed_only,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ownership_stats() {
+        let cache = FileCache::from_files(vec![
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "docs/a.md".to_string(),
+            "README.md".to_string(),
+        ]);
+        let content = "/src/ @old\n/src/ @core @rust\n/docs/ @core\n/vendor/ @vendor\n";
+        let stats = ownership_stats(
+            content,
+            &cache,
+            |file| {
+                if file.ends_with(".rs") {
+                    10
+                } else {
+                    5
+                }
+            },
+        );
+
+        assert_eq!(stats.total_files, 4);
+        assert_eq!(stats.total_lines, 30);
+        assert_eq!(stats.unowned_files, 1);
+        assert_eq!(stats.unowned_lines, 5);
+
+        let core = &stats.owners[0];
+        assert_eq!(core.owners, vec!["@core"]);
+        assert_eq!((core.files, core.lines, core.rules), (3, 25, 2));
+        assert_eq!(core.file_share, 75.0);
+
+        let rust = stats.owners.iter().find(|s| s.owners == ["@rust"]).unwrap();
+        assert_eq!((rust.files, rust.rules), (2, 1));
+        let old = stats.owners.iter().find(|s| s.owners == ["@old"]).unwrap();
+        assert_eq!((old.files, old.rules), (0, 1));
+
+        assert_eq!(stats.owner_sets[0].owners, vec!["@core", "@rust"]);
+        assert_eq!(stats.owner_sets[0].files, 2);
+        assert_eq!(stats.owner_sets[0].line_share, 66.7);
+
+        // @vendor matches nothing at all, so it isn't shadowed
+        assert_eq!(stats.shadowed_only, vec!["@old"]);
+    }
+}